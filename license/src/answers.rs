@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+/// A single answer given to an interactive prompt.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    pub prompt: String,
+    pub value: String,
+}
+
+/// The on-disk format of an answers file.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct AnswerFile {
+    #[serde(default)]
+    pub answers: Vec<Answer>,
+}
+
+#[derive(Debug, Default)]
+struct Session {
+    replay: VecDeque<Answer>,
+    recorded: Vec<Answer>,
+}
+
+static SESSION: LazyLock<Mutex<Session>> = LazyLock::new(|| Mutex::new(Session::default()));
+
+static STUBBED: AtomicBool = AtomicBool::new(false);
+
+static LOG_ANSWERS: AtomicBool = AtomicBool::new(false);
+
+static DETERMINISTIC: AtomicBool = AtomicBool::new(false);
+
+static PROMPT_RETRIES: AtomicUsize = AtomicUsize::new(DEFAULT_PROMPT_RETRIES);
+
+/// How many times a prompt is asked again after an invalid answer before
+/// giving up.
+pub const DEFAULT_PROMPT_RETRIES: usize = 5;
+
+/// Placeholder answers used by [`use_stubs`]. Each prompt takes the first
+/// one that parses as the type it asks for.
+pub const STUB_ANSWERS: &[&str] = &["yes", "2025", "1"];
+
+/// Loads previously saved answers so that matching prompts are answered
+/// without asking.
+#[tracing::instrument]
+pub fn load(path: &Path) -> Result<(), String> {
+    let contents =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let file: AnswerFile = toml::from_str(&contents)
+        .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?;
+    SESSION.lock().unwrap().replay = file.answers.into();
+    Ok(())
+}
+
+/// Writes every answer given during this run to `path`.
+#[tracing::instrument]
+pub fn save(path: &Path) -> Result<(), String> {
+    let file = AnswerFile {
+        answers: SESSION.lock().unwrap().recorded.clone(),
+    };
+    let contents =
+        toml::to_string(&file).map_err(|e| format!("Failed to serialize answers: {e}"))?;
+    fs::write(path, contents).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Takes the next saved answer for `prompt`, if there is one.
+#[tracing::instrument]
+pub fn replay(prompt: &str) -> Option<String> {
+    let mut session = SESSION.lock().unwrap();
+    let index = session.replay.iter().position(|a| a.prompt == prompt)?;
+    session.replay.remove(index).map(|a| a.value)
+}
+
+/// Records the answer given to `prompt`. The value is only logged if
+/// [`set_log_answers`] enabled it.
+#[tracing::instrument(skip(value))]
+pub fn record(prompt: &str, value: &str) {
+    if log_answers() {
+        tracing::debug!(value, "Answered prompt");
+    }
+    SESSION.lock().unwrap().recorded.push(Answer {
+        prompt: prompt.to_string(),
+        value: value.to_string(),
+    });
+}
+
+/// Answers every prompt with the first of [`STUB_ANSWERS`] that parses,
+/// without asking. Used to render licenses unattended.
+#[tracing::instrument]
+pub fn use_stubs() {
+    STUBBED.store(true, Ordering::Relaxed);
+}
+
+/// Whether prompts are answered with [`STUB_ANSWERS`].
+#[tracing::instrument]
+pub fn stubbed() -> bool {
+    STUBBED.load(Ordering::Relaxed)
+}
+
+/// Whether the answers given to prompts may be written to the log. They
+/// may contain names and other personal details, so this is off by
+/// default.
+#[tracing::instrument]
+pub fn set_log_answers(enabled: bool) {
+    LOG_ANSWERS.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether the answers given to prompts may be written to the log.
+#[tracing::instrument]
+pub fn log_answers() -> bool {
+    LOG_ANSWERS.load(Ordering::Relaxed)
+}
+
+/// Stops asking questions: prompts without a saved or configured answer
+/// fail instead, and no defaults are taken from the environment.
+#[tracing::instrument]
+pub fn set_deterministic(enabled: bool) {
+    DETERMINISTIC.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether prompts may only be answered from explicit inputs.
+#[tracing::instrument]
+pub fn deterministic() -> bool {
+    DETERMINISTIC.load(Ordering::Relaxed)
+}
+
+/// Sets how many times a prompt is asked again after an invalid answer.
+#[tracing::instrument]
+pub fn set_prompt_retries(retries: usize) {
+    PROMPT_RETRIES.store(retries, Ordering::Relaxed);
+}
+
+/// How many times a prompt is asked again after an invalid answer.
+#[tracing::instrument]
+pub fn prompt_retries() -> usize {
+    PROMPT_RETRIES.load(Ordering::Relaxed)
+}
@@ -0,0 +1,105 @@
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+static PACKAGE: OnceLock<Package> = OnceLock::new();
+
+/// The `package` fields of a `Cargo.toml` manifest that answer prompts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Package {
+    pub name: Option<String>,
+    /// The authors without their email addresses.
+    pub authors: Vec<String>,
+}
+
+/// Reads the `license` field from a `Cargo.toml` manifest.
+///
+/// Falls back to `workspace.package.license` when the package inherits
+/// its license from the workspace.
+#[tracing::instrument]
+pub fn read_license_expression(manifest: &Path) -> Result<String, String> {
+    let manifest_table = read_manifest(manifest)?;
+
+    let package_license = manifest_table.get("package").and_then(|p| p.get("license"));
+    let workspace_license = manifest_table
+        .get("workspace")
+        .and_then(|w| w.get("package"))
+        .and_then(|p| p.get("license"));
+
+    match (package_license, workspace_license) {
+        (Some(toml::Value::String(expr)), _) => Ok(expr.clone()),
+        (Some(toml::Value::Table(_)) | None, Some(toml::Value::String(expr))) => Ok(expr.clone()),
+        (Some(toml::Value::Table(_)), None) => Err(format!(
+            "The license in {} is inherited from the workspace, but no workspace.package.license field was found",
+            manifest.display()
+        )),
+        (Some(_), _) => Err(format!(
+            "The license field in {} is not a string",
+            manifest.display()
+        )),
+        (None, _) => Err(format!("No license field found in {}", manifest.display())),
+    }
+}
+
+/// Reads the package name and authors from a `Cargo.toml` manifest,
+/// following fields inherited from `workspace.package`.
+#[tracing::instrument]
+pub fn read_package(manifest: &Path) -> Result<Package, String> {
+    let manifest_table = read_manifest(manifest)?;
+    let field = |key: &str| {
+        let value = manifest_table.get("package")?.get(key)?;
+        match value.get("workspace") {
+            Some(toml::Value::Boolean(true)) => {
+                manifest_table.get("workspace")?.get("package")?.get(key)
+            }
+            _ => Some(value),
+        }
+    };
+    let name = field("name")
+        .and_then(toml::Value::as_str)
+        .map(String::from);
+    let authors = field("authors")
+        .and_then(toml::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(toml::Value::as_str)
+        .map(|author| match author.split_once('<') {
+            Some((name, _)) => name.trim().to_string(),
+            None => author.trim().to_string(),
+        })
+        .filter(|author| !author.is_empty())
+        .collect();
+    Ok(Package { name, authors })
+}
+
+/// Offers the fields of `package` as defaults for the prompts they
+/// answer.
+#[tracing::instrument(skip_all)]
+pub fn set_package(package: Package) {
+    if PACKAGE.set(package).is_err() {
+        tracing::warn!("Cargo package was already set");
+    }
+}
+
+/// The default answer to `prompt` from the manifest given with
+/// '--from-manifest', if it has one.
+#[tracing::instrument]
+pub fn prompt_default(prompt: &str) -> Option<String> {
+    let package = PACKAGE.get()?;
+    match prompt {
+        "Enter the full name of the copyright holder" if !package.authors.is_empty() => {
+            Some(package.authors.join(", "))
+        }
+        "Enter the name of the program" => package.name.clone(),
+        _ => None,
+    }
+}
+
+#[tracing::instrument]
+fn read_manifest(manifest: &Path) -> Result<toml::Table, String> {
+    let contents = fs::read_to_string(manifest)
+        .map_err(|e| format!("Failed to read {}: {e}", manifest.display()))?;
+    contents
+        .parse()
+        .map_err(|e| format!("Failed to parse {}: {e}", manifest.display()))
+}
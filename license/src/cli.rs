@@ -1,8 +1,15 @@
+use crate::answers;
+use crate::comment;
+use crate::io::{FinalNewline, HeaderPosition, LicenseFilename};
 use crate::license;
+use crate::summary::OutputFormat;
+use crate::texts::{CopyrightYear, EuplLanguage, MplHeader, QuoteStyle, TemplateVar};
 use clap::builder::styling::{AnsiColor, Effects, Styles};
-use clap::{Parser, value_parser};
+use clap::{CommandFactory, Parser, Subcommand, value_parser};
 use clap_verbosity_flag::Verbosity;
-use std::path::PathBuf;
+use regex::Regex;
+use std::env;
+use std::path::{Path, PathBuf};
 
 pub const STYLES: Styles = Styles::styled()
     .header(AnsiColor::Green.on_default().effects(Effects::BOLD))
@@ -13,20 +20,85 @@ pub const STYLES: Styles = Styles::styled()
     .valid(AnsiColor::Cyan.on_default().effects(Effects::BOLD))
     .invalid(AnsiColor::Yellow.on_default().effects(Effects::BOLD));
 
+/// The name the binary was invoked as (the file stem of `argv[0]`), so a
+/// renamed binary (e.g. `licensegen`) shows its own name. Falls back to the
+/// compiled name.
+#[tracing::instrument]
+pub fn invoked_name() -> String {
+    env::args_os()
+        .next()
+        .and_then(|arg0| {
+            Path::new(&arg0)
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+        })
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| Cli::command().get_name().to_string())
+}
+
+/// The [`Cli`] command named after [`invoked_name`]. Help, version, and
+/// anything generated from the command at runtime, such as completions
+/// and man pages, use this name.
+#[tracing::instrument]
+pub fn command() -> clap::Command {
+    let name = invoked_name();
+    Cli::command().name(name.clone()).bin_name(name)
+}
+
+/// Prints the completion script for `shell` to stdout, using the invoked
+/// name so a renamed binary completes under its own name.
+#[tracing::instrument]
+pub fn print_completions(shell: clap_complete::Shell) {
+    let mut cmd = command();
+    let name = cmd.get_name().to_string();
+    clap_complete::generate(shell, &mut cmd, name, &mut std::io::stdout());
+}
+
+/// Renders the manpage to stdout.
+#[tracing::instrument]
+pub fn print_man() -> std::io::Result<()> {
+    clap_mangen::Man::new(command()).render(&mut std::io::stdout())
+}
+
 /// Command line interface for generating license texts.
 #[derive(Parser, Debug)]
-#[command(name = "license", author, version, about, long_about = None, styles = STYLES)]
+#[command(name = "license", author, version, about, long_about = None, styles = STYLES, subcommand_negates_reqs = true, args_conflicts_with_subcommands = true)]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+
     /// Whether to add the license comment headers to the
     /// source files. If this is not set, the program will
     /// only print the license comment header to the console.
+    /// Markdown files get their "License" section updated instead,
+    /// unless '--comment' is set.
     #[arg(short = 'c', long, default_value_t = false, action = clap::ArgAction::SetTrue)]
     pub add_comment: bool,
 
+    /// Only add the license headers to the source files (implies
+    /// '--add-comment'), without creating or overwriting the license
+    /// file, e.g., when the repository root already carries it.
+    #[arg(long, visible_alias = "no-license-file", default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub spdx_only: bool,
+
     /// How to denote comments in the license header comment.
-    /// (e.g., `//` in rust vs. `#` in python).
-    #[arg(long, default_value = "//")]
-    pub comment: String,
+    /// (e.g., `//` in rust vs. `#` in python). If this is not set,
+    /// `comment` from the config file is used, and otherwise the comment
+    /// syntax is detected from each file's extension.
+    #[arg(long, global = true, value_parser = comment::parse_comment_token)]
+    pub comment: Option<String>,
+
+    /// The comment syntax to fall back to when '--comment' is not set
+    /// and a file's extension is not recognized. If this is not set,
+    /// files with unrecognized extensions are skipped.
+    #[arg(long, global = true, value_parser = comment::parse_comment_token)]
+    pub default_comment: Option<String>,
+
+    /// A file mapping glob patterns to comment tokens, one per line (e.g.,
+    /// `*.lua = "--"`). Patterns are matched against each file's path
+    /// relative to '--source-path' and take precedence over '--comment'.
+    #[arg(long, value_name = "PATH", global = true)]
+    pub comment_map: Option<PathBuf>,
 
     /// The path to the source files to add the license headers to.
     /// If '--add-comment' is set:
@@ -34,17 +106,399 @@ pub struct Cli {
     /// to that file.
     /// (B) and this points to a directory, the license header will be added
     /// to all files in that directory recursively.
-    #[arg(short, long, default_value = "src")]
+    #[arg(short, long, default_value = "src", global = true)]
     pub source_path: PathBuf,
 
-    /// The output file to write the license text to.
-    #[arg(short, long, default_value = "LICENSE.txt")]
+    /// The maximum number of subdirectory levels to descend into when
+    /// '--source-path' is a directory. `0` only adds headers to the files
+    /// directly inside it. If this is not set, there is no limit.
+    #[arg(long, value_name = "DEPTH", global = true)]
+    pub max_depth: Option<usize>,
+
+    /// Skip files and directories matching this glob pattern when
+    /// '--source-path' is a directory (e.g., `vendor/**`). Patterns are
+    /// matched against paths relative to '--source-path'. Can be repeated.
+    /// Dotfiles and common binary files are always skipped.
+    #[arg(long, value_name = "GLOB", global = true)]
+    pub exclude: Vec<String>,
+
+    /// Also add headers to files listed in `.gitignore`. By default,
+    /// paths ignored by the `.gitignore` files of the git work tree are
+    /// skipped.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue, global = true)]
+    pub no_ignore: bool,
+
+    /// Add headers to the files listed in this file, one path per line,
+    /// instead of walking '--source-path'. `-` reads the list from stdin
+    /// (e.g., `git diff --name-only | license --add-comment --files-from -
+    /// MIT`). Listed paths that don't exist are reported and skipped.
+    #[arg(long, value_name = "PATH", requires = "add_comment")]
+    pub files_from: Option<PathBuf>,
+
+    /// Remove every leading comment block that contains an
+    /// `SPDX-License-Identifier` before adding the new header, cleaning up
+    /// stacked headers from earlier runs. A shebang line and leading
+    /// comments without an identifier are kept. With '--position bottom',
+    /// trailing blocks with an identifier are removed too and the new
+    /// header is appended.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub strip_existing_headers: bool,
+
+    /// Only add headers to files whose content matches this regular
+    /// expression (e.g., `TODO: add license`).
+    #[arg(long, value_name = "REGEX")]
+    pub content_match: Option<Regex>,
+
+    /// Put the header in place of the line(s) matched by
+    /// '--content-match' instead of at the top of the file.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue, requires = "content_match")]
+    pub replace_match: bool,
+
+    /// Where to put the header in each file. `bottom` appends it as a
+    /// trailing notice, for files where leading comments are not allowed.
+    #[arg(long, value_enum, value_name = "POSITION", default_value_t = HeaderPosition::Top, conflicts_with = "replace_match")]
+    pub position: HeaderPosition,
+
+    /// Check that every file under '--source-path' already has the
+    /// expected `SPDX-License-Identifier` header without modifying
+    /// anything. Exits with code 1 and lists the offending files on
+    /// stderr if any are missing it.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub check: bool,
+
+    /// Check that this license file still matches the license text this
+    /// tool generates, ignoring differences in whitespace, without
+    /// modifying anything. The license's questions are answered as when
+    /// generating it (e.g., with '--answers'). Exits with code 1 and
+    /// prints a diff if it doesn't match. With several licenses, each is
+    /// compared with its `LICENSE-<NAME>` next to this file.
+    #[arg(long, value_name = "LICENSE_FILE", conflicts_with = "check")]
+    pub verify: Option<PathBuf>,
+
+    /// Replace the license header in files that already contain an
+    /// `SPDX-License-Identifier` instead of skipping them.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub force: bool,
+
+    /// Warn when adding a header to a file that git tracks as unchanged,
+    /// since it will show up in the next commit's diff.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub warn_clean: bool,
+
+    /// Only add headers to files with uncommitted changes or not tracked
+    /// by git, leaving unchanged files alone (e.g., in a pre-commit hook).
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub only_dirty: bool,
+
+    /// Keep an existing license file instead of overwriting it, only
+    /// adding the license's amendment (e.g., the employer's disclaimer of
+    /// the GNU licenses) to its end if it is not there yet.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub append: bool,
+
+    /// Copy each source file to `<file><SUFFIX>` before adding its header
+    /// (e.g., `--backup` for `main.rs.bak`, or `--backup=.orig`). Files
+    /// that already have the header are not copied.
+    #[arg(long, value_name = "SUFFIX", num_args = 0..=1, require_equals = true, default_missing_value = ".bak", value_parser = clap::builder::NonEmptyStringValueParser::new())]
+    pub backup: Option<String>,
+
+    /// How many files to add headers to at once when stamping a directory
+    /// (default: one per CPU). '--jobs 1' and '--dry-run' stamp the files
+    /// one after another.
+    #[arg(short, long, value_name = "N")]
+    pub jobs: Option<std::num::NonZeroUsize>,
+
+    /// After adding headers, warn about files where the comment token is
+    /// likely invalid, such as `//` in a YAML file or any comment in a JSON
+    /// file. This is a heuristic based on file extensions.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub verify_comments: bool,
+
+    /// Show what would change without writing anything: print a unified
+    /// diff for every source file that would get a header and the path
+    /// the license would be written to.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue, global = true)]
+    pub dry_run: bool,
+
+    /// Write the license file and every header change to this file as a
+    /// patch for `git apply` instead of modifying the working tree.
+    #[arg(long, value_name = "PATCH", global = true)]
+    pub as_patch: Option<PathBuf>,
+
+    /// The output file to write the license text to, or '-' to print it
+    /// to stdout. Status messages then go to stderr. Defaults to the
+    /// `LICENSE_GEN_OUTPUT` environment variable, then to `output` from
+    /// the config file, if set.
+    #[arg(short, long, default_value = "LICENSE.txt", env = "LICENSE_GEN_OUTPUT")]
     pub output: PathBuf,
 
+    /// Write the license files into this directory with conventional
+    /// names instead of to '--output': `LICENSE`, and where the license
+    /// has them `NOTICE`, `AMENDMENT.txt`, and `INTERACTIVE_NOTICE.txt`.
+    /// The directory is created if needed.
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["output", "amendment_output"])]
+    pub output_dir: Option<PathBuf>,
+
+    /// Name the license file by this convention instead of '--output's
+    /// default: `auto` picks `COPYING` for the GNU licenses and
+    /// `LICENSE.txt` for the rest. Also names the license in
+    /// '--output-dir' and 'new'. An explicit '--output' wins.
+    #[arg(long, value_enum, value_name = "CONVENTION", global = true)]
+    pub license_filename: Option<LicenseFilename>,
+
+    /// How to report what was generated. 'json' prints a single JSON
+    /// object with the SPDX expression, each license and the file it was
+    /// written to, and the modified source files, for use in CI. 'md'
+    /// writes the license file as Markdown, e.g. with '--license-filename
+    /// license-md'.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    pub format: OutputFormat,
+
+    /// Run `git add` on the license file and every modified source file
+    /// after writing them. Skipped when not inside a git repository.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub git_add: bool,
+
+    /// Write a `LICENSES.toml` index next to '--output' listing the SPDX
+    /// identifier and file of every generated license, for compliance
+    /// tooling that scans the repository.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub write_index: bool,
+
+    /// The format of the copyright line in headers and license texts,
+    /// with `{year}` and `{name}` placeholders (e.g., `© {year} {name}`).
+    /// Defaults to each license's canonical form.
+    #[arg(long, value_name = "TEMPLATE")]
+    pub copyright_format: Option<String>,
+
+    /// Normalize the quotes in the license text to 'straight' (`"`) or
+    /// 'curly' (`“”`) quotes, e.g., to match a documentation house style.
+    /// Straight quotes, the default, match the canonical text of nearly
+    /// every license.
+    #[arg(long, value_enum, value_name = "STYLE", default_value_t = QuoteStyle::Straight)]
+    pub quote_style: QuoteStyle,
+
+    /// The header MPL-2.0 adds to source files: the SPDX line with the
+    /// notice from Exhibit A of the license (the default), or only the
+    /// SPDX line.
+    #[arg(long, value_enum, value_name = "FORM")]
+    pub mpl_header: Option<MplHeader>,
+
+    /// The linguistic version of the EUPL-1.2 to note in the header (e.g.,
+    /// `FR` for "Licensed under the EUPL-1.2, in its French linguistic
+    /// version."). The license file is always the English text. If this is
+    /// not set, the language is prompted for.
+    #[arg(long, value_enum, value_name = "LANG", ignore_case = true)]
+    pub language: Option<EuplLanguage>,
+
+    /// Render license texts from handlebars templates in this directory,
+    /// named after the SPDX identifier (e.g., `MIT.hbs`). Licenses without
+    /// a template use the built-in text. MIT, 0BSD, Zlib, WTFPL, CC-BY-4.0,
+    /// and the BSD variants get `year`, `fullname`, and
+    /// `copyright` (BSD also `organization` and `website`); the v3 GNU
+    /// licenses get `license`; the rest get no variables. Licenses with a
+    /// title line also get it as `title`. Use triple braces (e.g.,
+    /// `{{{copyright}}}`) to avoid HTML escaping.
+    #[arg(long, value_name = "DIR")]
+    pub template_dir: Option<PathBuf>,
+
+    /// Add a variable to the license text template (e.g., `--var
+    /// project=Widget` for `{{project}}` in a '--template-dir' template).
+    /// Can be repeated. Variables cannot replace the license's own
+    /// variables, except `title`.
+    #[arg(long = "var", value_name = "KEY=VALUE", value_parser = value_parser!(TemplateVar))]
+    pub vars: Vec<TemplateVar>,
+
+    /// Replace the license's title line (e.g., "License (MIT)"), like
+    /// `--var title=...`. Licenses without a title get it as their first
+    /// line.
+    #[arg(long)]
+    pub title: Option<String>,
+
+    /// The copyright year, or a range of years for long-lived projects
+    /// (e.g., `2019-2025`). Answers every copyright year prompt and takes
+    /// precedence over `year` in the config file.
+    #[arg(long, value_name = "YEAR", value_parser = value_parser!(CopyrightYear))]
+    pub year: Option<CopyrightYear>,
+
+    /// Append "All rights reserved." to the copyright line. Off by default
+    /// since it is redundant for most open-source licenses.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub all_rights_reserved: bool,
+
+    /// Start each header with an `SPDX-FileCopyrightText` line for the
+    /// copyright year and holder, as the REUSE specification asks.
+    /// Licenses that don't otherwise need them ask for both. The stamped
+    /// files are also annotated in a `REUSE.toml` next to the license.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue, global = true)]
+    pub reuse: bool,
+
+    /// Also write a `NOTICE` file next to the license for licenses that
+    /// expect one (Apache-2.0), asking for the project name. Other
+    /// licenses are unaffected.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub notice: bool,
+
+    /// Write the amendment some licenses need (e.g., the employer's
+    /// copyright disclaimer of the GNU licenses) to this file instead of
+    /// appending it to the license file.
+    #[arg(long, value_name = "PATH")]
+    pub amendment_output: Option<PathBuf>,
+
+    /// How the license file ends: with exactly one newline, without one,
+    /// or however the license text ends.
+    #[arg(long, value_enum, value_name = "POLICY", default_value_t = FinalNewline::Ensure)]
+    pub final_newline: FinalNewline,
+
+    /// Re-flow the license text and the headers to this many columns,
+    /// keeping paragraph breaks. The comment token counts toward the
+    /// width of a header. Lines are left as they are by default.
+    #[arg(long, value_name = "COLS", value_parser = clap::value_parser!(u16).range(20..))]
+    pub wrap: Option<u16>,
+
+    /// Don't offer the year, name, and organization from the previous
+    /// run as defaults, and don't remember this run's answers.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub no_remember: bool,
+
+    /// Never prompt and never fall back to defaults from the environment,
+    /// so every run with the same inputs writes byte-identical files.
+    /// Implies '--no-remember', skips git config defaults and the per-user
+    /// config file, and fails if a required answer is not given with
+    /// '--year', '--answers', or a `license.toml` in the current directory.
+    /// Optional questions are left blank.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub deterministic: bool,
+
+    /// How many times to ask a question again after an invalid answer
+    /// before giving up.
+    #[arg(long, value_name = "N", default_value_t = answers::DEFAULT_PROMPT_RETRIES)]
+    pub prompt_retries: usize,
+
+    /// Read default answers and options from this file instead of
+    /// searching for `license.toml` in the current directory and
+    /// `$XDG_CONFIG_HOME/license-gen`. Command line options take
+    /// precedence over the config.
+    #[arg(long, value_name = "PATH", global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Write the answers given to the interactive prompts to this file
+    /// so the run can be replayed with '--answers'.
+    #[arg(long, value_name = "PATH")]
+    pub save_answers: Option<PathBuf>,
+
+    /// Answer the interactive prompts from a file written by
+    /// '--save-answers'. Prompts without a saved answer are still asked.
+    #[arg(long, value_name = "PATH")]
+    pub answers: Option<PathBuf>,
+
+    /// Include the answers given to the interactive prompts in debug
+    /// logs. Off by default since they may contain names and other
+    /// personal details.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub log_answers: bool,
+
+    /// When to color the output. `auto` colors each stream only if it is a
+    /// terminal, and honors the `NO_COLOR` and `CLICOLOR_FORCE` environment
+    /// variables.
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t = clap::ColorChoice::Auto, global = true)]
+    pub color: clap::ColorChoice,
+
     #[command(flatten)]
     pub verbosity: Verbosity,
 
-    /// The license to generate text for.
-    #[arg(value_parser = value_parser!(license::Licenses))]
-    pub license: license::Licenses,
+    /// Read the license expression from the `license` field of a
+    /// Cargo.toml manifest (defaults to `./Cargo.toml`) and generate every
+    /// license it references. When the expression names more than one
+    /// license, each is written to `LICENSE-<NAME>` next to '--output'.
+    #[arg(long, value_name = "MANIFEST", num_args = 0..=1, default_missing_value = "Cargo.toml", conflicts_with_all = ["license", "spdx_expression"])]
+    pub from_cargo: Option<PathBuf>,
+
+    /// Offer the package name and authors of a Cargo.toml manifest
+    /// (defaults to `./Cargo.toml`) as the defaults for the program name
+    /// and copyright holder prompts.
+    #[arg(long, value_name = "MANIFEST", num_args = 0..=1, default_missing_value = "Cargo.toml")]
+    pub from_manifest: Option<PathBuf>,
+
+    /// Generate every license in an SPDX expression (e.g.,
+    /// "MIT OR Apache-2.0"). Each license is written to `LICENSE-<NAME>`
+    /// next to '--output' and the source files get the combined
+    /// identifier.
+    #[arg(
+        long,
+        value_name = "EXPRESSION",
+        alias = "spdx",
+        conflicts_with = "license"
+    )]
+    pub spdx_expression: Option<String>,
+
+    /// Write this license text verbatim instead of a built-in license,
+    /// for licenses this tool doesn't include. Takes a file path, or a URL
+    /// if built with the `network` feature. The source files get the
+    /// identifier given with '--spdx'.
+    #[arg(long, value_name = "PATH_OR_URL", requires = "spdx_expression", conflicts_with_all = ["license", "from_cargo", "select"])]
+    pub license_text: Option<String>,
+
+    /// Pick one or more licenses from a list and build the SPDX
+    /// expression for them interactively, as with '--spdx-expression'.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue, conflicts_with_all = ["license", "spdx_expression", "from_cargo"])]
+    pub select: bool,
+
+    /// The license to generate text for. Without it, and without
+    /// '--from-cargo', '--spdx-expression', or '--select', pick one from a
+    /// list when run in a terminal.
+    #[arg(value_parser = license::LicenseParser)]
+    pub license: Option<license::Licenses>,
+}
+
+/// Subcommands that do not generate a license.
+#[derive(Subcommand, Debug, Clone)]
+pub enum Commands {
+    /// List every supported license as tab-separated SPDX identifier,
+    /// whether it asks questions ('interactive' or 'static'), and
+    /// description.
+    List,
+    /// Replace legacy freeform license headers (e.g., "Licensed under the
+    /// MIT license") under '--source-path' with SPDX headers. Files that
+    /// cannot be mapped to an SPDX identifier are listed for review.
+    Modernize,
+    /// Remove the SPDX license headers under '--source-path' that
+    /// '--add-comment' added. Only a leading comment block that contains
+    /// `SPDX-License-Identifier` is removed.
+    #[command(alias = "uncomment")]
+    RemoveHeader,
+    /// Set up a new project: write `LICENSE` and add the license header
+    /// to every source file under '--source-path' (default: the current
+    /// directory), skipping paths listed in `.gitignore` and files with an
+    /// unknown comment syntax. The package name and authors in
+    /// `./Cargo.toml`, if there is one, are offered as defaults. Prints
+    /// every file written.
+    New {
+        /// The license to set the project up with.
+        #[arg(value_parser = license::LicenseParser)]
+        license: license::Licenses,
+    },
+    /// Print the built-in license that best matches an existing license
+    /// file, with a confidence score. Exits non-zero if none is close.
+    Guess {
+        /// The license file to identify (e.g., `LICENSE`).
+        path: PathBuf,
+    },
+    /// Render every license with placeholder answers and report any that
+    /// fail. Exits non-zero if one does.
+    SelfTest {
+        /// Render only this license, in this process.
+        #[arg(long, hide = true, value_parser = license::LicenseParser)]
+        only: Option<license::Licenses>,
+    },
+    /// Print a shell completion script to stdout (e.g.,
+    /// `license completions zsh > _license`).
+    Completions {
+        /// The shell to generate completions for.
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// Print the manpage to stdout (e.g., `license man | man -l -`).
+    #[command(hide = true)]
+    Man,
 }
@@ -0,0 +1,56 @@
+use clap::ColorChoice;
+
+/// Sets whether output is colored. With [`ColorChoice::Auto`] each stream
+/// is colored only if it is a terminal, `NO_COLOR` is unset, or
+/// `CLICOLOR_FORCE` is set.
+#[tracing::instrument]
+pub fn set_color(choice: ColorChoice) {
+    let choice = match choice {
+        ColorChoice::Auto => anstream::ColorChoice::Auto,
+        ColorChoice::Always => anstream::ColorChoice::Always,
+        ColorChoice::Never => anstream::ColorChoice::Never,
+    };
+    choice.write_global();
+}
+
+/// Whether output written to `stream` is colored.
+#[tracing::instrument(skip(stream))]
+pub fn enabled<S: anstream::stream::RawStream>(stream: &S) -> bool {
+    anstream::AutoStream::choice(stream) != anstream::ColorChoice::Never
+}
+
+// The macros below can't capture variables inline in the format string
+// (`{e}`): `color_print` expands it with the span of the wrapping macro,
+// where the caller's variables aren't visible. Pass them as arguments.
+
+/// Like [`color_print::cprint!`], but honors [`set_color`].
+#[macro_export]
+macro_rules! cprint {
+    ($($arg:tt)*) => {
+        ::anstream::print!("{}", ::color_print::cformat!($($arg)*))
+    };
+}
+
+/// Like [`color_print::cprintln!`], but honors [`set_color`].
+#[macro_export]
+macro_rules! cprintln {
+    ($($arg:tt)*) => {
+        ::anstream::println!("{}", ::color_print::cformat!($($arg)*))
+    };
+}
+
+/// Like [`color_print::ceprint!`], but honors [`set_color`].
+#[macro_export]
+macro_rules! ceprint {
+    ($($arg:tt)*) => {
+        ::anstream::eprint!("{}", ::color_print::cformat!($($arg)*))
+    };
+}
+
+/// Like [`color_print::ceprintln!`], but honors [`set_color`].
+#[macro_export]
+macro_rules! ceprintln {
+    ($($arg:tt)*) => {
+        ::anstream::eprintln!("{}", ::color_print::cformat!($($arg)*))
+    };
+}
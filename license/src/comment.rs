@@ -0,0 +1,204 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::fs;
+use std::path::Path;
+
+/// Line comment tokens keyed on file extension.
+pub const COMMENT_TOKENS: &[(&str, &str)] = &[
+    // `#` style
+    ("py", "#"),
+    ("pyi", "#"),
+    ("rb", "#"),
+    ("sh", "#"),
+    ("bash", "#"),
+    ("zsh", "#"),
+    ("fish", "#"),
+    ("pl", "#"),
+    ("pm", "#"),
+    ("r", "#"),
+    ("jl", "#"),
+    ("ex", "#"),
+    ("exs", "#"),
+    ("nix", "#"),
+    ("tf", "#"),
+    ("cmake", "#"),
+    ("ps1", "#"),
+    ("toml", "#"),
+    ("yaml", "#"),
+    ("yml", "#"),
+    // `//` style
+    ("rs", "//"),
+    ("c", "//"),
+    ("h", "//"),
+    ("cc", "//"),
+    ("cpp", "//"),
+    ("cxx", "//"),
+    ("hh", "//"),
+    ("hpp", "//"),
+    ("hxx", "//"),
+    ("cs", "//"),
+    ("java", "//"),
+    ("kt", "//"),
+    ("kts", "//"),
+    ("scala", "//"),
+    ("groovy", "//"),
+    ("go", "//"),
+    ("swift", "//"),
+    ("dart", "//"),
+    ("zig", "//"),
+    ("js", "//"),
+    ("jsx", "//"),
+    ("mjs", "//"),
+    ("cjs", "//"),
+    ("ts", "//"),
+    ("tsx", "//"),
+    ("mts", "//"),
+    ("cts", "//"),
+    ("proto", "//"),
+    // `--` style
+    ("sql", "--"),
+    ("hs", "--"),
+    ("lhs", "--"),
+    ("lua", "--"),
+    ("elm", "--"),
+    ("ada", "--"),
+    ("adb", "--"),
+    ("ads", "--"),
+    // `%` style
+    ("tex", "%"),
+    ("sty", "%"),
+    ("erl", "%"),
+    ("hrl", "%"),
+    // `;` style
+    ("lisp", ";"),
+    ("el", ";"),
+    ("clj", ";"),
+    ("cljs", ";"),
+    ("scm", ";"),
+    ("ini", ";"),
+    // `"` style
+    ("vim", "\""),
+];
+
+/// Looks up the line comment token for a file based on its extension.
+///
+/// Returns `None` if the file has no extension or the extension is not
+/// in [`COMMENT_TOKENS`].
+#[tracing::instrument]
+pub fn comment_for_path(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    COMMENT_TOKENS
+        .iter()
+        .find(|(e, _)| *e == ext)
+        .map(|(_, token)| *token)
+}
+
+/// Parses a comment token from the command line or config file,
+/// rejecting empty or whitespace-only tokens, which would start every
+/// header line with a bare space.
+#[tracing::instrument]
+pub fn parse_comment_token(s: &str) -> Result<String, String> {
+    if s.trim().is_empty() {
+        Err("the comment token cannot be empty (e.g., `//` or `#`)".to_string())
+    } else {
+        Ok(s.to_string())
+    }
+}
+
+/// Whether `comment` starts with one of the tokens in [`COMMENT_TOKENS`],
+/// so `///` and `#!` count but `/*` does not.
+#[tracing::instrument]
+pub fn is_known_comment(comment: &str) -> bool {
+    COMMENT_TOKENS
+        .iter()
+        .any(|(_, token)| comment.trim_start().starts_with(token))
+}
+
+/// Extensions of text formats that have no comment syntax at all.
+pub const UNCOMMENTABLE_EXTENSIONS: &[&str] = &["json", "csv", "tsv", "ipynb"];
+
+/// Checks whether `comment` is likely to start a valid comment in `path`,
+/// based on its extension. This is a heuristic, not a parser: longer
+/// tokens such as `///` are accepted where `//` is expected. Returns the
+/// reason the header may break the file, or `None` if it looks fine or
+/// the extension is unknown.
+#[tracing::instrument]
+pub fn verify_comment(path: &Path, comment: &str) -> Option<String> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    if UNCOMMENTABLE_EXTENSIONS.contains(&ext.as_str()) {
+        return Some(format!(".{ext} files do not support comments"));
+    }
+    let expected = comment_for_path(path)?;
+    if comment.starts_with(expected) {
+        return None;
+    }
+    Some(format!(
+        "`{comment}` does not start a comment in .{ext} files (expected `{expected}`)"
+    ))
+}
+
+/// Comment tokens for files matching glob patterns, read from a
+/// '--comment-map' file. When several patterns match, the first one in
+/// the file wins.
+#[derive(Debug, Clone)]
+pub struct CommentMap {
+    globs: GlobSet,
+    tokens: Vec<String>,
+}
+
+impl CommentMap {
+    /// Reads and compiles a comment map file.
+    #[tracing::instrument]
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        CommentMap::parse(&contents).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    /// Parses lines of the form `<glob> = "<token>"`, such as
+    /// `*.lua = "--"`. Either side may be quoted, so the file can also be
+    /// written as TOML. Blank lines and lines starting with `#` are
+    /// ignored.
+    #[tracing::instrument(skip(contents))]
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut builder = GlobSetBuilder::new();
+        let mut tokens = Vec::new();
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((pattern, token)) = line.rsplit_once('=') else {
+                return Err(format!(
+                    "line {}: expected `<glob> = \"<token>\"`",
+                    index + 1
+                ));
+            };
+            let (pattern, token) = (unquote(pattern), unquote(token));
+            if pattern.is_empty() || token.is_empty() {
+                return Err(format!(
+                    "line {}: expected `<glob> = \"<token>\"`",
+                    index + 1
+                ));
+            }
+            let glob = Glob::new(pattern).map_err(|e| format!("line {}: {e}", index + 1))?;
+            builder.add(glob);
+            tokens.push(token.to_string());
+        }
+        let globs = builder.build().map_err(|e| e.to_string())?;
+        Ok(CommentMap { globs, tokens })
+    }
+
+    /// The comment token of the first pattern matching `path`.
+    #[tracing::instrument]
+    pub fn comment_for(&self, path: &Path) -> Option<&str> {
+        let index = self.globs.matches(path).into_iter().min()?;
+        Some(&self.tokens[index])
+    }
+}
+
+fn unquote(s: &str) -> &str {
+    let s = s.trim();
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(s)
+}
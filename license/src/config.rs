@@ -0,0 +1,121 @@
+use serde::Deserialize;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// The name of the config file searched for in the current directory and
+/// in [`config_dir`].
+pub const CONFIG_FILE_NAME: &str = "license.toml";
+
+/// Prompts that are answered from the config file, and the key that
+/// answers them.
+pub const CONFIG_PROMPTS: &[(&str, &str)] = &[
+    ("Enter the copyright year", "year"),
+    (
+        "Enter the full name of the copyright holder",
+        "copyright_holder",
+    ),
+    ("Enter the name of the organization", "organization"),
+];
+
+/// A value that may be written as a TOML string or integer, such as
+/// `year = 2025` or `year = "2020-2025"`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ConfigValue {
+    Integer(i64),
+    String(String),
+}
+
+impl fmt::Display for ConfigValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigValue::Integer(value) => write!(f, "{value}"),
+            ConfigValue::String(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// Default answers and options read from a [`CONFIG_FILE_NAME`] file.
+/// Options given on the command line take precedence.
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub copyright_holder: Option<ConfigValue>,
+    pub year: Option<ConfigValue>,
+    pub organization: Option<ConfigValue>,
+    /// The default for '--comment'.
+    pub comment: Option<String>,
+    /// The default for '--output'.
+    pub output: Option<PathBuf>,
+}
+
+impl Config {
+    #[tracing::instrument(skip(self))]
+    fn get(&self, key: &str) -> Option<&ConfigValue> {
+        match key {
+            "year" => self.year.as_ref(),
+            "copyright_holder" => self.copyright_holder.as_ref(),
+            "organization" => self.organization.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// The directory holding per-user files: `$XDG_CONFIG_HOME/license-gen`,
+/// falling back to `~/.config/license-gen`.
+#[tracing::instrument]
+pub fn config_dir() -> Option<PathBuf> {
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config.join("license-gen"))
+}
+
+/// The first config file that exists: [`CONFIG_FILE_NAME`] in the current
+/// directory, then in [`config_dir`] if `user_dir` is set.
+#[tracing::instrument]
+pub fn find(user_dir: bool) -> Option<PathBuf> {
+    let local = PathBuf::from(CONFIG_FILE_NAME);
+    if local.is_file() {
+        return Some(local);
+    }
+    if !user_dir {
+        return None;
+    }
+    config_dir()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+        .filter(|path| path.is_file())
+}
+
+/// Loads the config from `path`, or from [`find`] if it is not set. A
+/// missing config file is not an error unless `path` was given.
+#[tracing::instrument]
+pub fn load(path: Option<&Path>, user_dir: bool) -> Result<Config, String> {
+    let Some(path) = path.map(Path::to_path_buf).or_else(|| find(user_dir)) else {
+        return Ok(Config::default());
+    };
+    let contents =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    toml::from_str(&contents).map_err(|e| format!("Failed to parse {}: {e}", path.display()))
+}
+
+/// Uses `config` to answer the prompts in [`CONFIG_PROMPTS`].
+#[tracing::instrument(skip(config))]
+pub fn install(config: Config) {
+    if CONFIG.set(config).is_err() {
+        tracing::warn!("Config was already installed");
+    }
+}
+
+/// The config value that answers `prompt`, if one is set.
+#[tracing::instrument]
+pub fn answer(prompt: &str) -> Option<String> {
+    let (_, key) = CONFIG_PROMPTS.iter().find(|(p, _)| *p == prompt)?;
+    CONFIG.get()?.get(key).map(ToString::to_string)
+}
@@ -0,0 +1,193 @@
+use crate::io::{HeaderOptions, SPDX_MARKER, rewrite_or_diff};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// File extensions of Markdown documents whose license section is updated
+/// instead of adding a comment header.
+pub const MARKDOWN_EXTENSIONS: &[&str] = &["md", "markdown"];
+
+/// Heading titles that mark a license section, compared case-insensitively.
+pub const LICENSE_HEADINGS: &[&str] = &["license", "licence", "licenses", "licensing"];
+
+/// Marks the start of the generated part of a license section.
+pub const SECTION_START: &str = "<!-- license-gen:start -->";
+
+/// Marks the end of the generated part of a license section.
+pub const SECTION_END: &str = "<!-- license-gen:end -->";
+
+/// The outcome of updating the license section of a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionOutcome {
+    Updated,
+    Unchanged,
+    NoSection,
+}
+
+/// Whether `path` is a Markdown document.
+#[tracing::instrument]
+pub fn is_markdown(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| MARKDOWN_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// Rewrites the body of the "License" section of the Markdown document at
+/// `path` to state the SPDX expression from `comment_block` with links to
+/// each license. The generated text is wrapped in [`SECTION_START`] and
+/// [`SECTION_END`] so later runs only replace what they wrote.
+#[tracing::instrument(skip(comment_block))]
+pub fn update_license_section(
+    path: &Path,
+    comment_block: &str,
+    header: &HeaderOptions,
+) -> io::Result<SectionOutcome> {
+    let Some(expression) = comment_block
+        .lines()
+        .find_map(|line| line.split_once(SPDX_MARKER))
+        .map(|(_, expression)| expression.trim())
+    else {
+        return Ok(SectionOutcome::NoSection);
+    };
+    let src = fs::read_to_string(path)?;
+    if header
+        .content_match
+        .as_ref()
+        .is_some_and(|pattern| !pattern.is_match(&src))
+    {
+        return Ok(SectionOutcome::NoSection);
+    }
+    let Some(contents) = replace_section(&src, &section_body(expression)) else {
+        return Ok(SectionOutcome::NoSection);
+    };
+    if contents == src {
+        return Ok(SectionOutcome::Unchanged);
+    }
+    rewrite_or_diff(path, &src, &contents, header.dry_run)?;
+    Ok(SectionOutcome::Updated)
+}
+
+/// The generated section text: the SPDX expression and a link to each
+/// license or exception it names.
+#[tracing::instrument]
+pub fn section_body(expression: &str) -> String {
+    let mut ids: Vec<&str> = Vec::new();
+    for id in expression
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        .filter(|t| !t.is_empty() && !matches!(*t, "AND" | "OR" | "WITH"))
+    {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    match ids.as_slice() {
+        [id] if *id == expression => {
+            format!("SPDX-License-Identifier: [`{id}`]({})", spdx_url(id))
+        }
+        ids => {
+            let links: Vec<String> = ids
+                .iter()
+                .map(|id| format!("[{id}]({})", spdx_url(id)))
+                .collect();
+            format!(
+                "SPDX-License-Identifier: `{expression}` ({})",
+                links.join(", ")
+            )
+        }
+    }
+}
+
+fn spdx_url(id: &str) -> String {
+    format!("https://spdx.org/licenses/{id}.html")
+}
+
+/// Replaces the text between the section markers, or the whole body of
+/// the first license heading if there are no markers yet. Returns `None`
+/// if the document has neither.
+#[tracing::instrument(skip(src, body))]
+fn replace_section(src: &str, body: &str) -> Option<String> {
+    let lines: Vec<&str> = src.lines().collect();
+    let start = lines.iter().position(|l| l.trim() == SECTION_START);
+    let end = start.and_then(|s| {
+        lines[s..]
+            .iter()
+            .position(|l| l.trim() == SECTION_END)
+            .map(|e| s + e)
+    });
+    let (before, after) = match (start, end) {
+        (Some(start), Some(end)) => (&lines[..start], &lines[end + 1..]),
+        _ => {
+            let (heading, end) = license_section(&lines)?;
+            (&lines[..=heading], &lines[end..])
+        }
+    };
+
+    let mut contents = String::with_capacity(src.len() + body.len());
+    for line in before {
+        contents.push_str(line);
+        contents.push('\n');
+    }
+    if start.is_none() {
+        contents.push('\n');
+    }
+    contents.push_str(SECTION_START);
+    contents.push('\n');
+    contents.push_str(body);
+    contents.push('\n');
+    contents.push_str(SECTION_END);
+    contents.push('\n');
+    if start.is_none() && !after.is_empty() {
+        contents.push('\n');
+    }
+    for line in after {
+        contents.push_str(line);
+        contents.push('\n');
+    }
+    Some(contents)
+}
+
+/// Finds the first license heading, returning its line and the line that
+/// ends its section: the next heading of the same or a higher level, or
+/// the end of the document. Headings inside fenced code blocks are ignored.
+#[tracing::instrument(skip(lines))]
+fn license_section(lines: &[&str]) -> Option<(usize, usize)> {
+    let mut fenced = false;
+    let mut section: Option<(usize, usize)> = None;
+    for (index, line) in lines.iter().enumerate() {
+        if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+            fenced = !fenced;
+            continue;
+        }
+        if fenced {
+            continue;
+        }
+        let Some((level, title)) = heading(line) else {
+            continue;
+        };
+        match section {
+            Some((start, section_level)) if level <= section_level => {
+                return Some((start, index));
+            }
+            Some(_) => {}
+            None if LICENSE_HEADINGS.contains(&title.to_ascii_lowercase().as_str()) => {
+                section = Some((index, level));
+            }
+            None => {}
+        }
+    }
+    section.map(|(start, _)| (start, lines.len()))
+}
+
+/// Parses an ATX heading such as `## License`, returning its level and
+/// title.
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    Some((level, rest.trim().trim_end_matches('#').trim_end()))
+}
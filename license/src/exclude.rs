@@ -0,0 +1,50 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::path::Path;
+
+/// Extensions of binary files that are always skipped when walking a
+/// directory, since prepending a header would corrupt them.
+pub const BINARY_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "bmp", "ico", "webp", "tif", "tiff", "psd", "mp3", "wav", "ogg",
+    "flac", "mp4", "mov", "avi", "mkv", "webm", "ttf", "otf", "woff", "woff2", "eot", "zip", "tar",
+    "gz", "tgz", "bz2", "xz", "zst", "7z", "rar", "jar", "pdf", "doc", "docx", "xls", "xlsx",
+    "ppt", "pptx", "exe", "dll", "so", "dylib", "a", "o", "obj", "lib", "rlib", "class", "pyc",
+    "wasm", "bin", "dat", "db", "sqlite",
+];
+
+/// Paths skipped while walking '--source-path': dotfiles, files with a
+/// [`BINARY_EXTENSIONS`] extension, and anything matching an '--exclude'
+/// pattern.
+#[derive(Debug, Clone)]
+pub struct Excludes {
+    globs: GlobSet,
+}
+
+impl Excludes {
+    /// Compiles the '--exclude' patterns.
+    #[tracing::instrument]
+    pub fn new(patterns: &[String]) -> Result<Self, String> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            let glob = Glob::new(pattern).map_err(|e| format!("'{pattern}': {e}"))?;
+            builder.add(glob);
+        }
+        let globs = builder.build().map_err(|e| e.to_string())?;
+        Ok(Excludes { globs })
+    }
+
+    /// Whether `path`, relative to the source path, should be skipped.
+    /// Binary extensions are only checked for files.
+    #[tracing::instrument]
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        let dotfile = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with('.'));
+        let binary = !is_dir
+            && path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| BINARY_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()));
+        dotfile || binary || self.globs.is_match(path)
+    }
+}
@@ -0,0 +1,201 @@
+use devx_cmd::Cmd;
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{self, Path, PathBuf};
+use std::process::Stdio;
+use std::sync::OnceLock;
+
+/// Prompts whose default is read from the git config when nothing was
+/// remembered from a previous run, and the config key to read.
+pub const PROMPT_DEFAULTS: &[(&str, &str)] =
+    &[("Enter the full name of the copyright holder", "user.name")];
+
+/// Whether the current directory is inside a git work tree. Returns
+/// `false` if git is not installed.
+#[tracing::instrument]
+pub fn is_repo() -> bool {
+    let Some(mut git) = Cmd::lookup_in_path("git") else {
+        return false;
+    };
+    git.args(["rev-parse", "--is-inside-work-tree"])
+        .log_cmd(None)
+        .log_err(None);
+    git.spawn_with(Stdio::null(), Stdio::null())
+        .and_then(|mut child| child.wait())
+        .is_ok()
+}
+
+/// Runs `git add` on `paths`.
+#[tracing::instrument]
+pub fn stage(paths: &[PathBuf]) -> Result<(), String> {
+    let mut git = Cmd::lookup_in_path("git").ok_or("git is not installed")?;
+    git.arg("add").arg("--").args(paths).log_cmd(None);
+    git.run().map_err(|e| e.to_string())
+}
+
+/// The files tracked by git without staged or unstaged changes, as
+/// canonical paths. Looked up once per run.
+static CLEAN_FILES: OnceLock<HashSet<PathBuf>> = OnceLock::new();
+
+/// Whether `path` is tracked by git and unchanged since the last commit.
+/// Returns `false` outside a git work tree.
+#[tracing::instrument]
+pub fn is_clean(path: &Path) -> bool {
+    let clean = CLEAN_FILES.get_or_init(clean_files);
+    fs::canonicalize(path).is_ok_and(|path| clean.contains(&path))
+}
+
+#[tracing::instrument]
+fn clean_files() -> HashSet<PathBuf> {
+    let Some(root) = read(&["rev-parse", "--show-toplevel"]) else {
+        return HashSet::new();
+    };
+    let Ok(root) = fs::canonicalize(root.trim()) else {
+        return HashSet::new();
+    };
+    // Without a commit to compare against, no file is clean.
+    let (Some(tracked), Some(changed)) = (
+        read(&["ls-files", "-z", "--full-name"]),
+        read(&["diff", "--name-only", "-z", "HEAD"]),
+    ) else {
+        return HashSet::new();
+    };
+    let changed: HashSet<&str> = changed.split('\0').collect();
+    tracked
+        .split('\0')
+        .filter(|file| !file.is_empty() && !changed.contains(file))
+        .map(|file| root.join(file))
+        .collect()
+}
+
+/// Runs git with `args` and returns its output, or `None` if git is not
+/// installed or failed.
+#[tracing::instrument]
+fn read(args: &[&str]) -> Option<String> {
+    let mut git = Cmd::lookup_in_path("git")?;
+    git.args(args).log_cmd(None).log_err(None);
+    git.spawn_with(Stdio::piped(), Stdio::null())
+        .and_then(|child| child.read())
+        .ok()
+}
+
+/// Reads `key` (e.g., `user.name`) from the git config. Returns `None` if
+/// git is not installed or the value is unset or empty.
+#[tracing::instrument]
+pub fn config_value(key: &str) -> Option<String> {
+    let mut git = Cmd::lookup_in_path("git")?;
+    git.args(["config", "--get", key])
+        .log_cmd(None)
+        .log_err(None);
+    let value = git.read().ok()?;
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// The default answer to `prompt` from the git config, if it has one in
+/// [`PROMPT_DEFAULTS`] and the current directory is inside a git work tree.
+#[tracing::instrument]
+pub fn prompt_default(prompt: &str) -> Option<String> {
+    let (_, key) = PROMPT_DEFAULTS.iter().find(|(p, _)| *p == prompt)?;
+    if !is_repo() {
+        return None;
+    }
+    config_value(key)
+}
+
+/// The `.gitignore` rules in effect while walking a directory tree, from
+/// the outermost directory to the innermost.
+#[derive(Debug, Default)]
+pub struct IgnoreRules {
+    rules: Vec<Gitignore>,
+}
+
+impl IgnoreRules {
+    /// The rules for walking `dir`: the `.gitignore` files of its parent
+    /// directories up to the root of the git work tree, and the work
+    /// tree's `.git/info/exclude`. Outside a work tree, only the
+    /// `.gitignore` files inside `dir` apply.
+    #[tracing::instrument]
+    pub fn for_walk(dir: &Path) -> Self {
+        let mut rules = IgnoreRules::default();
+        let Ok(dir) = path::absolute(dir) else {
+            return rules;
+        };
+        let mut parents: Vec<&Path> = Vec::new();
+        for (index, parent) in dir.ancestors().enumerate() {
+            // `dir`'s own `.gitignore` is added when the walk enters it.
+            if index > 0 {
+                parents.push(parent);
+            }
+            if parent.join(".git").exists() {
+                rules.add(parent, &parent.join(".git/info/exclude"));
+                for parent in parents.iter().rev() {
+                    rules.add(parent, &parent.join(".gitignore"));
+                }
+                break;
+            }
+        }
+        rules
+    }
+
+    /// Adds the rules of `dir`'s `.gitignore`, if it has one. Returns
+    /// whether any were added, so they can be dropped with [`leave`] after
+    /// walking `dir`.
+    ///
+    /// [`leave`]: IgnoreRules::leave
+    #[tracing::instrument]
+    pub fn enter(&mut self, dir: &Path) -> bool {
+        match path::absolute(dir) {
+            Ok(dir) => self.add(&dir, &dir.join(".gitignore")),
+            Err(_) => false,
+        }
+    }
+
+    /// Drops the rules added by the last successful [`enter`].
+    ///
+    /// [`enter`]: IgnoreRules::enter
+    #[tracing::instrument]
+    pub fn leave(&mut self) {
+        self.rules.pop();
+    }
+
+    /// Whether `path` is ignored. The innermost matching rule wins, so a
+    /// `!pattern` in a subdirectory can re-include a path.
+    #[tracing::instrument]
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let Ok(path) = path::absolute(path) else {
+            return false;
+        };
+        for rules in self.rules.iter().rev() {
+            match rules.matched(&path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+        false
+    }
+
+    #[tracing::instrument]
+    fn add(&mut self, root: &Path, file: &Path) -> bool {
+        if !file.is_file() {
+            return false;
+        }
+        let mut builder = GitignoreBuilder::new(root);
+        if let Some(e) = builder.add(file) {
+            tracing::warn!("Ignoring part of {}: {e}", file.display());
+        }
+        match builder.build() {
+            Ok(rules) => {
+                self.rules.push(rules);
+                true
+            }
+            Err(e) => {
+                tracing::warn!("Ignoring {}: {e}", file.display());
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use crate::answers;
+use crate::license::{self, Licenses};
+use crate::{ceprintln, cprintln};
+use clap::ValueEnum;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// How similar a license file must be to a built-in license text to be
+/// reported as a match.
+pub const MIN_CONFIDENCE: f64 = 0.8;
+
+/// Compares the license file at `path` with every built-in license text,
+/// rendered with placeholder answers. Returns each license with its
+/// confidence between `0.0` and `1.0`, best match first.
+#[tracing::instrument]
+pub fn guess(path: &Path) -> Result<Vec<(Licenses, f64)>, String> {
+    let text =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let words = word_counts(&text);
+    answers::use_stubs();
+    let mut scores: Vec<(Licenses, f64)> = Licenses::value_variants()
+        .iter()
+        .map(|license| {
+            let reference = license::generate_license_text(license);
+            let score = similarity(&words, &word_counts(&reference.text));
+            (license.clone(), score)
+        })
+        .collect();
+    // A stable sort keeps the order of the variants among equal texts
+    // (e.g., GPL-3.0-only and GPL-3.0-or-later).
+    scores.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    Ok(scores)
+}
+
+/// Prints the license that best matches the file at `path` with its
+/// confidence. Returns `false` if the file could not be read or no
+/// license matches it closely enough.
+#[tracing::instrument]
+pub fn run(path: &Path) -> bool {
+    let scores = match guess(path) {
+        Ok(scores) => scores,
+        Err(e) => {
+            ceprintln!("<bold><red>Failed to guess the license</></>: {}", e);
+            return false;
+        }
+    };
+    match scores.first() {
+        Some((license, score)) if *score >= MIN_CONFIDENCE => {
+            cprintln!("{}\t<green>{:.1}%</>", license, score * 100.0);
+            true
+        }
+        best => {
+            ceprintln!(
+                "<bold><red>No built-in license matches {}</></>: the closest is {}",
+                path.display(),
+                match best {
+                    Some((license, score)) => format!("{license} ({:.1}%)", score * 100.0),
+                    None => "none".to_string(),
+                },
+            );
+            false
+        }
+    }
+}
+
+/// How often each word of `text` appears, ignoring case, punctuation,
+/// and whitespace.
+#[tracing::instrument(skip(text))]
+fn word_counts(text: &str) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for word in text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        *counts.entry(word.to_lowercase()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// The Sørensen–Dice coefficient of two bags of words: `1.0` for the
+/// same words, `0.0` for none in common. The few words that differ, such
+/// as the year and the copyright holder, barely move it.
+#[tracing::instrument(skip(a, b))]
+fn similarity(a: &HashMap<String, usize>, b: &HashMap<String, usize>) -> f64 {
+    let total: usize = a.values().sum::<usize>() + b.values().sum::<usize>();
+    if total == 0 {
+        return 0.0;
+    }
+    let shared: usize = a
+        .iter()
+        .map(|(word, count)| (*count).min(b.get(word).copied().unwrap_or(0)))
+        .sum();
+    2.0 * shared as f64 / total as f64
+}
@@ -0,0 +1,49 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The name of the index file written next to the license files.
+pub const INDEX_FILE_NAME: &str = "LICENSES.toml";
+
+/// A license that was generated and the file it was written to.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct IndexEntry {
+    pub id: String,
+    pub file: String,
+}
+
+/// The on-disk format of the license index.
+#[derive(Serialize, Debug, Default)]
+pub struct LicenseIndex {
+    /// The SPDX expression the source files are stamped with.
+    pub expression: String,
+    pub licenses: Vec<IndexEntry>,
+}
+
+/// Writes an index of the generated licenses to [`INDEX_FILE_NAME`] next
+/// to `output`, so compliance tooling can discover them. Each entry pairs
+/// an SPDX identifier with its license file, relative to the index.
+#[tracing::instrument]
+pub fn write_index(
+    expression: &str,
+    licenses: &[(String, PathBuf)],
+    output: &Path,
+) -> Result<PathBuf, String> {
+    let index = LicenseIndex {
+        expression: expression.to_string(),
+        licenses: licenses
+            .iter()
+            .map(|(id, path)| IndexEntry {
+                id: id.clone(),
+                file: path
+                    .file_name()
+                    .map_or_else(String::new, |n| n.to_string_lossy().into_owned()),
+            })
+            .collect(),
+    };
+    let path = output.with_file_name(INDEX_FILE_NAME);
+    let contents =
+        toml::to_string(&index).map_err(|e| format!("Failed to serialize index: {e}"))?;
+    fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    Ok(path)
+}
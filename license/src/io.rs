@@ -1,174 +1,1755 @@
+use crate::answers;
+use crate::cargo;
+use crate::comment::{self, CommentMap};
+use crate::config;
+use crate::docs::{self, SectionOutcome};
+use crate::exclude::Excludes;
+use crate::git::{self, IgnoreRules};
+use crate::license::Licenses;
+use crate::markdown;
+use crate::notebook::{self, NotebookOutcome};
+use crate::progress::Progress;
+use crate::remember;
+use crate::reuse;
+use crate::summary::{LicenseSummary, RunSummary};
 use crate::texts::LicenseTexts;
-use color_print::{ceprintln, cformat, cprint, cprintln};
+use crate::wrap;
+use crate::{ceprint, ceprintln, cprintln};
+use clap::ValueEnum;
+use color_print::cformat;
+use rayon::ThreadPoolBuilder;
+use rayon::prelude::*;
+use regex::Regex;
+use std::borrow::Cow;
 use std::fs::{self, OpenOptions};
-use std::io::{self, BufRead, Write};
+use std::io::{self, Write};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::process;
 use std::str::FromStr;
+use std::sync::Mutex;
 use tempfile::NamedTempFile;
 
-#[tracing::instrument]
+/// Options controlling how license headers are added to source files.
+#[derive(Debug, Clone)]
+pub struct HeaderOptions {
+    /// Whether to write the headers to the source files or only print them.
+    pub add_comment: bool,
+    /// Leave the license file alone and only add the headers.
+    pub spdx_only: bool,
+    /// The comment token to use for every file.
+    pub comment: Option<String>,
+    /// The comment token to use when detection by extension fails.
+    pub default_comment: Option<String>,
+    /// Comment tokens for files matching glob patterns. Takes precedence
+    /// over `comment`.
+    pub comment_map: Option<CommentMap>,
+    /// The file or directory to add the headers to.
+    pub source_path: PathBuf,
+    /// How many levels of subdirectories to descend into. `None` means
+    /// no limit.
+    pub max_depth: Option<usize>,
+    /// Paths skipped while walking `source_path`.
+    pub exclude: Excludes,
+    /// Walk into paths listed in `.gitignore` files too.
+    pub no_ignore: bool,
+    /// The files to add headers to instead of walking `source_path`, from
+    /// '--files-from'.
+    pub files: Option<Vec<PathBuf>>,
+    /// The license file being written. It is never stamped with a header.
+    pub license_file: PathBuf,
+    /// Replace existing SPDX headers instead of skipping the file.
+    pub force: bool,
+    /// Warn when adding a header to a file that git tracks as unchanged.
+    pub warn_clean: bool,
+    /// Only add headers to files with uncommitted changes or not tracked
+    /// by git.
+    pub only_dirty: bool,
+    /// Copy each file to the file name plus this suffix before adding its
+    /// header.
+    pub backup: Option<String>,
+    /// Warn about headers whose comment token is likely invalid for the
+    /// file they were added to.
+    pub verify_comments: bool,
+    /// Remove every leading comment block with an SPDX identifier before
+    /// adding the new header.
+    pub strip_existing: bool,
+    /// Only add headers to files whose content matches this pattern.
+    pub content_match: Option<Regex>,
+    /// Put the header in place of the lines matched by `content_match`
+    /// instead of at the top of the file.
+    pub replace_match: bool,
+    /// Whether the header goes at the top or the bottom of each file.
+    pub position: HeaderPosition,
+    /// Print what would change instead of writing anything.
+    pub dry_run: bool,
+    /// Whether stdout is reserved for the JSON summary.
+    pub json: bool,
+    /// Keep an existing license file, only adding the amendment to it.
+    pub append: bool,
+    /// Don't print the amendment and interactive notices, for scripts.
+    pub quiet: bool,
+    /// Where the license's amendment goes. `None` appends it to the
+    /// license file.
+    pub amendment_output: Option<PathBuf>,
+    /// How the license file ends.
+    pub final_newline: FinalNewline,
+    /// The column to re-flow the license text and headers to. `None`
+    /// keeps their lines as they are.
+    pub wrap: Option<usize>,
+    /// Write the license file as Markdown, from '--format md'.
+    pub markdown: bool,
+    /// Annotate the stamped files in a `REUSE.toml` next to the license
+    /// file.
+    pub reuse: bool,
+    /// Show a progress bar while adding headers to a directory.
+    pub progress: bool,
+    /// How many files to add headers to at once. `None` means one per
+    /// CPU.
+    pub jobs: Option<usize>,
+}
+
+impl HeaderOptions {
+    /// Whether status messages go to stderr because stdout is reserved for
+    /// the license text or the JSON summary.
+    #[tracing::instrument]
+    pub fn status_to_stderr(&self) -> bool {
+        self.json || is_stdout(&self.license_file)
+    }
+
+    /// `text` re-flowed to '--wrap', less the `prefix` columns put before
+    /// each of its lines.
+    #[tracing::instrument(skip(text))]
+    pub fn wrapped<'a>(&self, text: &'a str, prefix: usize) -> Cow<'a, str> {
+        match self.wrap {
+            Some(width) => Cow::Owned(wrap::reflow(text, width.saturating_sub(prefix))),
+            None => Cow::Borrowed(text),
+        }
+    }
+}
+
+/// Adds the license headers, writes the license file, and prints any
+/// notices. Returns a summary of what was written, or `None` if writing
+/// failed.
+#[tracing::instrument(skip(license))]
 pub fn output(
+    id: &str,
     license: &LicenseTexts,
-    add_comment: bool,
-    comment: &str,
-    source_path: PathBuf,
+    header: &HeaderOptions,
     output: PathBuf,
-) {
+) -> Option<RunSummary> {
+    let stats = add_headers(&license.comment, header)?;
+    if !header.spdx_only
+        && (!write_license_file_unless_dry_run(id, license, &output, header)
+            || !write_notice_unless_dry_run(license, &output, header)
+            || !write_amendments_unless_dry_run(license, &output, header))
+    {
+        return None;
+    }
+    if !header.quiet {
+        print_notices(license);
+    }
+    let reuse = write_reuse_unless_dry_run(id, &license.comment, &stats.modified, &output, header)?;
+    Some(RunSummary {
+        expression: id.to_string(),
+        licenses: vec![LicenseSummary::new(id.to_string(), output, license)],
+        modified: stats.modified,
+        failed: stats.failed,
+        index: None,
+        reuse,
+    })
+}
+
+/// Adds a combined `SPDX-License-Identifier: <expression>` header to the
+/// source files and writes each license to its [`Licenses::file_name`]
+/// next to `output`. Returns a summary of what was written, or `None` if
+/// writing failed.
+#[tracing::instrument(skip(licenses))]
+pub fn output_all(
+    expression: &str,
+    licenses: &[(Licenses, LicenseTexts)],
+    header: &HeaderOptions,
+    output: &Path,
+) -> Option<RunSummary> {
+    // The copyright lines '--reuse' gave each license, once each.
+    let mut comment = String::new();
+    for line in licenses
+        .iter()
+        .flat_map(|(_, license)| license.comment.lines())
+        .filter(|line| line.starts_with(COPYRIGHT_TEXT_MARKER))
+    {
+        if !comment.lines().any(|seen| seen == line) {
+            comment.push_str(line);
+            comment.push('\n');
+        }
+    }
+    comment.push_str(&format!("{SPDX_MARKER} {expression}"));
+    let stats = add_headers(&comment, header)?;
+    let mut summaries = Vec::with_capacity(licenses.len());
+    for (id, license) in licenses {
+        let path = if is_stdout(output) {
+            output.to_path_buf()
+        } else {
+            output.with_file_name(id.file_name())
+        };
+        if !header.spdx_only
+            && (!write_license_file_unless_dry_run(&id.to_string(), license, &path, header)
+                || !write_notice_unless_dry_run(license, &path, header)
+                || !write_amendments_unless_dry_run(license, &path, header))
+        {
+            return None;
+        }
+        if !header.quiet {
+            print_notices(license);
+        }
+        summaries.push(LicenseSummary::new(id.to_string(), path, license));
+    }
+    let reuse = write_reuse_unless_dry_run(expression, &comment, &stats.modified, output, header)?;
+    Some(RunSummary {
+        expression: expression.to_string(),
+        licenses: summaries,
+        modified: stats.modified,
+        failed: stats.failed,
+        index: None,
+        reuse,
+    })
+}
+
+/// With '--reuse', annotates the `modified` files in the `REUSE.toml` next
+/// to `output`, or with '--dry-run' only prints where it would go. Returns
+/// the path written, `Some(None)` if there was nothing to write, or `None`
+/// if writing failed.
+#[tracing::instrument(skip(comment_block, modified))]
+fn write_reuse_unless_dry_run(
+    expression: &str,
+    comment_block: &str,
+    modified: &[PathBuf],
+    output: &Path,
+    header: &HeaderOptions,
+) -> Option<Option<PathBuf>> {
+    if !header.reuse || modified.is_empty() {
+        return Some(None);
+    }
+    let path = reuse::reuse_path(output);
+    let text = match reuse::render(&path, expression, comment_block, modified) {
+        Ok(text) => text,
+        Err(e) => {
+            ceprintln!("<bold><red>Error</></>: {}", e);
+            return None;
+        }
+    };
+    if !header.dry_run {
+        if let Err(e) = fs::write(&path, text) {
+            ceprintln!("<bold><red>Error writing {}</></>: {}", path.display(), e);
+            return None;
+        }
+        return Some(Some(path));
+    }
+    match PATCH.lock().unwrap().as_mut() {
+        Some(patch) => {
+            let old = fs::read_to_string(&path).ok();
+            patch.push_str(&unified_diff(&path, old.as_deref(), &text));
+        }
+        None => println!("Would write {}", path.display()),
+    }
+    Some(Some(path))
+}
+
+/// Adds the license header to the source files, or prints it if
+/// `add_comment` is not set. Files that could not be read or written are
+/// reported and recorded in [`HeaderStats::failed`] without stopping the
+/// rest. Returns `None` if the source path does not exist.
+#[tracing::instrument(skip(comment_block))]
+pub fn add_headers(comment_block: &str, header: &HeaderOptions) -> Option<HeaderStats> {
+    let source_path = &header.source_path;
+    let mut stats = HeaderStats::default();
     match (
-        add_comment,
+        header.add_comment,
         source_path.exists(),
         source_path.is_dir(),
         source_path.is_file(),
     ) {
-        (true, true, true, _) => iterate_dir(&source_path, comment, &license.comment),
-        (true, true, _, true) => {
-            if let Err(e) = write_comment(comment, &license.comment, &source_path) {
+        (true, _, _, _) if header.files.is_some() => {
+            stamp_listed_files(header, comment_block, &mut stats)
+        }
+        (true, true, true, _) => {
+            let mut files = Vec::new();
+            let walk = iterate_dir(source_path, header, &mut |path| {
+                files.push(path.to_path_buf());
+                true
+            });
+            stats.ignored = walk.ignored;
+            stats.failed = walk.failed;
+            stamp_files(&files, header, comment_block, &mut stats);
+        }
+        (true, true, _, true) => stamp_file(source_path, header, comment_block, &mut stats),
+        (true, true, false, false) => {
+            ceprintln!(
+                "<bold><red>Source path is neither a file nor a directory</></>: {}",
+                source_path.display()
+            );
+            return None;
+        }
+        (true, false, _, _) => {
+            ceprintln!(
+                "<bold><red>Source path does not exist</></>: {}",
+                source_path.display()
+            );
+            return None;
+        }
+        (false, _, _, _) => {
+            print_status(
+                header.status_to_stderr(),
+                &cformat!(
+                    "<bold><magenta>Add this as a comment to the top of your source file(s):</></>\n"
+                ),
+            );
+            print_status(header.status_to_stderr(), &header.wrapped(comment_block, 0));
+            return Some(stats);
+        }
+    }
+    stats.report(header);
+    Some(stats)
+}
+
+/// Verifies that every source file carries an `SPDX-License-Identifier`
+/// matching `expected`, without modifying anything. Every offending file
+/// is listed on stderr. Returns `false` if any file is missing the header,
+/// has the wrong identifier, or could not be read.
+#[tracing::instrument]
+pub fn check_headers(expected: &str, header: &HeaderOptions) -> bool {
+    let source_path = &header.source_path;
+    let mut offending = 0usize;
+    let mut check = |path: &Path| {
+        if resolve_comment(header, path).is_none() {
+            tracing::debug!("Skipping {}: unrecognized file extension", path.display());
+            return true;
+        }
+        match read_identifier(path, header.position) {
+            Ok(Some(found)) if found == expected => {}
+            Ok(Some(found)) => {
+                ceprintln!(
+                    "<bold><red>Wrong license identifier</></>: {} (found '{}', expected '{}')",
+                    path.display(),
+                    found,
+                    expected,
+                );
+                offending += 1;
+            }
+            Ok(None) => {
+                ceprintln!(
+                    "<bold><red>Missing license header</></>: {}",
+                    path.display()
+                );
+                offending += 1;
+            }
+            Err(e) => {
+                ceprintln!(
+                    "<bold><red>Failed to read file {}</></>: {}",
+                    path.display(),
+                    e,
+                );
+                offending += 1;
+            }
+        }
+        true
+    };
+    match (source_path.is_dir(), source_path.is_file()) {
+        (true, _) => {
+            offending += iterate_dir(source_path, header, &mut check).failed.len();
+        }
+        (_, true) => {
+            check(source_path);
+        }
+        _ => {
+            ceprintln!(
+                "<bold><red>Source path does not exist</></>: {}",
+                source_path.display()
+            );
+            return false;
+        }
+    }
+    if offending > 0 {
+        ceprintln!(
+            "<bold><red>{} file(s) failed the license header check</></> (expected '{} {}')",
+            offending,
+            SPDX_MARKER,
+            expected,
+        );
+        return false;
+    }
+    true
+}
+
+/// Compares the license file at `path` with the text this tool would
+/// write there for `license`, identified by `id`, ignoring differences
+/// in whitespace. Prints a diff on mismatch. Returns `false` if it
+/// doesn't match or could not be read.
+#[tracing::instrument(skip(license))]
+pub fn verify_license(
+    id: &str,
+    license: &LicenseTexts,
+    path: &Path,
+    header: &HeaderOptions,
+) -> bool {
+    let found = match fs::read_to_string(path) {
+        Ok(found) => found,
+        Err(e) => {
+            ceprintln!(
+                "<bold><red>Failed to read license file {}</></>: {}",
+                path.display(),
+                e,
+            );
+            return false;
+        }
+    };
+    let found = normalize_whitespace(&found);
+    let expected = normalize_whitespace(&license_file_text(id, license, path, header));
+    if found == expected {
+        cprintln!(
+            "<bold><green>{} matches the license text</></>",
+            path.display()
+        );
+        return true;
+    }
+    ceprintln!(
+        "<bold><red>{} does not match the license text</></>",
+        path.display()
+    );
+    print!("{}", unified_diff(path, Some(&found), &expected));
+    false
+}
+
+/// `text` with runs of spaces and tabs collapsed, trailing whitespace and
+/// repeated blank lines removed, and exactly one newline at the end.
+#[tracing::instrument(skip(text))]
+fn normalize_whitespace(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    let mut blank = true;
+    for line in text.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.is_empty() {
+            blank = true;
+            continue;
+        }
+        if blank && !normalized.is_empty() {
+            normalized.push('\n');
+        }
+        blank = false;
+        normalized.push_str(&line);
+        normalized.push('\n');
+    }
+    normalized
+}
+
+/// Reads the SPDX license identifier from the top (or bottom) of a file,
+/// if present.
+#[tracing::instrument]
+fn read_identifier(path: &Path, position: HeaderPosition) -> io::Result<Option<String>> {
+    let src = fs::read_to_string(path)?;
+    let lines: Vec<&str> = src.lines().collect();
+    Ok(lines[position.scan_range(lines.len())]
+        .iter()
+        .find_map(|line| line.split_once(SPDX_MARKER))
+        .map(|(_, id)| id.trim().to_string()))
+}
+
+/// Counts of what happened to each file while adding headers.
+#[derive(Debug, Default)]
+pub struct HeaderStats {
+    pub modified: Vec<PathBuf>,
+    pub skipped: usize,
+    /// Paths skipped because of `.gitignore`.
+    pub ignored: usize,
+    /// Files skipped because git tracks them as unchanged.
+    pub clean: usize,
+    /// Files and directories that could not be read or written.
+    pub failed: Vec<PathBuf>,
+}
+
+impl HeaderStats {
+    /// Adds the counts of `other`, keeping the order of the modified files.
+    #[tracing::instrument]
+    fn merge(&mut self, other: HeaderStats) {
+        self.modified.extend(other.modified);
+        self.skipped += other.skipped;
+        self.ignored += other.ignored;
+        self.clean += other.clean;
+        self.failed.extend(other.failed);
+    }
+
+    #[tracing::instrument]
+    pub fn report(&self, header: &HeaderOptions) {
+        let verb = if header.dry_run { "Would add" } else { "Added" };
+        let mut message = cformat!(
+            "<bold><green>{verb} license headers to {} file(s)</></>, skipped {} file(s) that already had an SPDX header.",
+            self.modified.len(),
+            self.skipped,
+        );
+        if self.ignored > 0 {
+            message.push_str(&format!(
+                " Ignored {} path(s) listed in .gitignore.",
+                self.ignored
+            ));
+        }
+        if self.clean > 0 {
+            message.push_str(&format!(
+                " Left {} file(s) unchanged in git alone.",
+                self.clean
+            ));
+        }
+        if !self.failed.is_empty() {
+            message.push_str(&cformat!(
+                " <bold><red>{} file(s) failed.</></>",
+                self.failed.len()
+            ));
+        }
+        print_status(header.status_to_stderr(), &message);
+    }
+}
+
+/// The '--output' value that writes the license text to stdout.
+pub const STDOUT_OUTPUT: &str = "-";
+
+/// Whether `output` means the license text goes to stdout.
+#[tracing::instrument]
+pub fn is_stdout(output: &Path) -> bool {
+    output.as_os_str() == STDOUT_OUTPUT
+}
+
+/// Prints a message for the user on stdout, or on stderr when stdout is
+/// reserved for the license text or the JSON summary.
+#[tracing::instrument]
+fn print_status(to_stderr: bool, message: &str) {
+    if to_stderr {
+        anstream::eprintln!("{message}");
+    } else {
+        anstream::println!("{message}");
+    }
+}
+
+/// Writes the license text like [`write_license`], or with '--dry-run'
+/// only prints where it would go. With '--as-patch' the new license file
+/// is added to the patch instead.
+#[tracing::instrument(skip(text))]
+fn write_license_unless_dry_run(text: &str, output: &Path, header: &HeaderOptions) -> bool {
+    if !header.dry_run {
+        return write_license(text, output);
+    }
+    if !is_stdout(output)
+        && let Some(patch) = PATCH.lock().unwrap().as_mut()
+    {
+        let old = fs::read_to_string(output).ok();
+        patch.push_str(&unified_diff(output, old.as_deref(), text));
+        return true;
+    }
+    if is_stdout(output) {
+        eprintln!("Would write license text to stdout");
+    } else {
+        println!("Would write license text to {}", output.display());
+    }
+    true
+}
+
+/// Where the `NOTICE` file of the license written to `output` goes: next
+/// to it, or stdout along with the license.
+#[tracing::instrument]
+pub fn notice_path(output: &Path) -> PathBuf {
+    if is_stdout(output) {
+        output.to_path_buf()
+    } else {
+        output.with_file_name("NOTICE")
+    }
+}
+
+/// Writes the license's `NOTICE` file, if it has one, next to `output`.
+/// Returns `false` if it could not be written.
+#[tracing::instrument(skip(license))]
+fn write_notice_unless_dry_run(
+    license: &LicenseTexts,
+    output: &Path,
+    header: &HeaderOptions,
+) -> bool {
+    match &license.notice {
+        Some(notice) => write_license_unless_dry_run(notice, &notice_path(output), header),
+        None => true,
+    }
+}
+
+/// The name of the license file '--output-dir' and 'new' write.
+pub const OUTPUT_DIR_LICENSE: &str = "LICENSE";
+
+/// The name of the amendment file '--output-dir' writes.
+pub const OUTPUT_DIR_AMENDMENT: &str = "AMENDMENT.txt";
+
+/// Where the interactive notice of the license written to `output` goes.
+#[tracing::instrument]
+pub fn interactive_notice_path(output: &Path) -> PathBuf {
+    output.with_file_name("INTERACTIVE_NOTICE.txt")
+}
+
+/// The text written to the license file at `output`, with the license's
+/// amendment appended unless '--amendment-output' is set, re-flowed to
+/// '--wrap', rendered as Markdown titled after `id` with '--format md',
+/// and ending as '--final-newline' says. Stdout only gets the license
+/// text.
+#[tracing::instrument(skip(license))]
+fn license_file_text<'a>(
+    id: &str,
+    license: &'a LicenseTexts,
+    output: &Path,
+    header: &HeaderOptions,
+) -> Cow<'a, str> {
+    let text = match (&license.alt, &header.amendment_output) {
+        (Some(alt), None) if !is_stdout(output) => Cow::Owned(format!(
+            "{}\n\n{}\n",
+            license.text.trim_end(),
+            alt.trim_end()
+        )),
+        _ => Cow::Borrowed(license.text.as_str()),
+    };
+    let text = match header.wrapped(&text, 0) {
+        Cow::Owned(wrapped) => Cow::Owned(wrapped),
+        Cow::Borrowed(_) => text,
+    };
+    let text = if header.markdown {
+        Cow::Owned(markdown::render(&text, &markdown::title(id)))
+    } else {
+        text
+    };
+    match text {
+        Cow::Borrowed(text) => header.final_newline.apply(text),
+        Cow::Owned(text) => Cow::Owned(header.final_newline.apply(&text).into_owned()),
+    }
+}
+
+/// Writes the license's amendment to '--amendment-output', if both are
+/// set, and its interactive notice next to `output`, if it has one and
+/// `output` is a file. Returns `false` if they could not be written.
+#[tracing::instrument(skip(license))]
+fn write_amendments_unless_dry_run(
+    license: &LicenseTexts,
+    output: &Path,
+    header: &HeaderOptions,
+) -> bool {
+    if let (Some(alt), Some(path)) = (&license.alt, &header.amendment_output)
+        && !write_license_unless_dry_run(alt, path, header)
+    {
+        return false;
+    }
+    match &license.interactive {
+        Some(interactive) if !is_stdout(output) => {
+            write_license_unless_dry_run(interactive, &interactive_notice_path(output), header)
+        }
+        _ => true,
+    }
+}
+
+/// Writes the license file at `output`. With '--append' an existing file
+/// is kept as it is, and only the license's amendment is added to its end
+/// unless it is already there. Returns `false` if the file could not be
+/// read or written.
+#[tracing::instrument(skip(license))]
+fn write_license_file_unless_dry_run(
+    id: &str,
+    license: &LicenseTexts,
+    output: &Path,
+    header: &HeaderOptions,
+) -> bool {
+    if !header.append || is_stdout(output) || !output.exists() {
+        let text = license_file_text(id, license, output, header);
+        return write_license_unless_dry_run(&text, output, header);
+    }
+    let Some(alt) = license
+        .alt
+        .as_ref()
+        .filter(|_| header.amendment_output.is_none())
+    else {
+        return true;
+    };
+    let existing = match fs::read_to_string(output) {
+        Ok(existing) => existing,
+        Err(e) => {
+            ceprintln!(
+                "<bold><red>Failed to read license file {}</></>: {}",
+                output.display(),
+                e
+            );
+            return false;
+        }
+    };
+    if existing.contains(alt.trim()) {
+        return true;
+    }
+    let separator = if existing.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    };
+    let addition = format!("{separator}{}\n", alt.trim_end());
+    if header.dry_run {
+        write_license_unless_dry_run(&format!("{existing}{addition}"), output, header)
+    } else {
+        append_license(&addition, output)
+    }
+}
+
+/// Writes the license text to `output`, replacing any existing file, or
+/// to stdout if `output` is [`STDOUT_OUTPUT`]. Returns `false` if the text
+/// could not be written.
+#[tracing::instrument(skip(text))]
+pub fn write_license(text: &str, output: &Path) -> bool {
+    write_license_to(text, output, false)
+}
+
+/// Adds `text` to the end of the license file at `output`. Returns `false`
+/// if the text could not be written.
+#[tracing::instrument(skip(text))]
+pub fn append_license(text: &str, output: &Path) -> bool {
+    write_license_to(text, output, true)
+}
+
+/// Writes `text` to the license file at `output`, or stdout, either
+/// replacing the file or adding to its end.
+#[tracing::instrument(skip(text))]
+fn write_license_to(text: &str, output: &Path, append: bool) -> bool {
+    if is_stdout(output) {
+        let mut stdout = io::stdout().lock();
+        if let Err(e) = stdout
+            .write_all(text.as_bytes())
+            .and_then(|_| stdout.flush())
+        {
+            ceprintln!(
+                "<bold><red>Failed to write license text to stdout</></>: {}",
+                e
+            );
+            return false;
+        }
+        return true;
+    }
+    let mut license_file = match OpenOptions::new()
+        .write(true)
+        .create(true)
+        .append(append)
+        .truncate(!append)
+        .open(output)
+    {
+        Ok(file) => file,
+        Err(e) => {
+            ceprintln!(
+                "<bold><red>Failed to open license file {}</></>: {}",
+                output.display(),
+                e,
+            );
+            return false;
+        }
+    };
+
+    match license_file.write_all(text.as_bytes()) {
+        Ok(_) => (),
+        Err(e) => {
+            ceprintln!(
+                "<bold><red>Failed to write license text to {}</></>: {}",
+                output.display(),
+                e,
+            );
+            return false;
+        }
+    };
+
+    if let Err(e) = license_file.flush() {
+        ceprintln!(
+            "<bold><red>Failed to flush license file {}</></>: {}",
+            output.display(),
+            e,
+        );
+        return false;
+    };
+    true
+}
+
+/// Prints the amendment and interactive notices that need to accompany
+/// the license, if any. They go to stderr so that stdout only carries
+/// generated content.
+#[tracing::instrument(skip(license))]
+pub fn print_notices(license: &LicenseTexts) {
+    if let Some(alt) = &license.alt {
+        ceprintln!(
+            r#"<magenta><bold>
+You'll need to include the following amendment to your license.</> 
+This is usually added to the end of the license file, but there is no strict requirement 
+for where it goes. Another common place is to add it as a comment at the top of your source 
+files or to the readme.</>
+"#,
+        );
+        eprintln!("{alt}");
+    };
+
+    if let Some(interactive) = &license.interactive {
+        ceprintln!(
+            r#"<magenta><bold>
+Since your program is interactive, you should also include the following notice in your program's output.</>
+This needs to be easily accessible to users, such as in a help command, at the start of the program, in 
+a footer section, or in an about section.</>
+"#,
+        );
+        eprintln!("{interactive}");
+    };
+}
+
+/// The marker used to detect files that already have a license header.
+pub const SPDX_MARKER: &str = "SPDX-License-Identifier:";
+
+/// The tag of the copyright line '--reuse' adds to each header.
+pub const COPYRIGHT_TEXT_MARKER: &str = "SPDX-FileCopyrightText:";
+
+/// How many lines at the top of a file are searched for [`SPDX_MARKER`].
+pub const SPDX_SCAN_LINES: usize = 20;
+
+/// Whether the license file ends with a newline. The embedded license
+/// texts don't agree on it.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FinalNewline {
+    /// End with exactly one newline.
+    #[default]
+    Ensure,
+    /// End without a newline.
+    Strip,
+    /// Keep the license text's own ending.
+    Preserve,
+}
+
+impl FinalNewline {
+    /// `text` with its trailing newlines changed to follow this policy.
+    #[tracing::instrument(skip(text))]
+    pub fn apply(self, text: &str) -> Cow<'_, str> {
+        let trimmed = text.trim_end_matches(['\r', '\n']);
+        match self {
+            FinalNewline::Preserve => Cow::Borrowed(text),
+            FinalNewline::Strip => Cow::Borrowed(trimmed),
+            FinalNewline::Ensure if text.strip_suffix('\n') == Some(trimmed) => Cow::Borrowed(text),
+            FinalNewline::Ensure => Cow::Owned(format!("{trimmed}\n")),
+        }
+    }
+}
+
+/// Where [`write_comment`] puts the header in a file.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HeaderPosition {
+    /// Before the file contents.
+    #[default]
+    Top,
+    /// After the file contents, for files where leading comments are not
+    /// allowed or a trailing notice is the convention.
+    Bottom,
+}
+
+impl HeaderPosition {
+    /// The lines of a file with `len` lines that are searched for
+    /// [`SPDX_MARKER`]: the first or the last [`SPDX_SCAN_LINES`].
+    #[tracing::instrument]
+    fn scan_range(self, len: usize) -> Range<usize> {
+        match self {
+            HeaderPosition::Top => 0..len.min(SPDX_SCAN_LINES),
+            HeaderPosition::Bottom => len.saturating_sub(SPDX_SCAN_LINES)..len,
+        }
+    }
+}
+
+/// The license file name conventions '--license-filename' picks from.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LicenseFilename {
+    /// `COPYING` for the GNU licenses (`COPYING.LESSER` for LGPL-3.0),
+    /// `LICENSE.txt` for the rest.
+    #[default]
+    Auto,
+    /// `LICENSE`.
+    License,
+    /// `LICENSE.md`.
+    LicenseMd,
+    /// `LICENSE.txt`.
+    LicenseTxt,
+    /// `COPYING`.
+    Copying,
+}
+
+impl LicenseFilename {
+    /// The file name this convention gives `license`. `None` is a license
+    /// that isn't built in, or several.
+    #[tracing::instrument]
+    pub fn file_name(self, license: Option<&Licenses>) -> &'static str {
+        match (self, license) {
+            (LicenseFilename::Auto, Some(Licenses::Lgpl3(_))) => "COPYING.LESSER",
+            (
+                LicenseFilename::Auto,
+                Some(
+                    Licenses::Gpl2(_)
+                    | Licenses::Gpl3(_)
+                    | Licenses::Agpl3(_)
+                    | Licenses::Lgpl21(_),
+                ),
+            ) => "COPYING",
+            (LicenseFilename::Auto | LicenseFilename::LicenseTxt, _) => "LICENSE.txt",
+            (LicenseFilename::License, _) => "LICENSE",
+            (LicenseFilename::LicenseMd, _) => "LICENSE.md",
+            (LicenseFilename::Copying, _) => "COPYING",
+        }
+    }
+}
+
+/// File names, without extension, that are treated as license files and
+/// skipped when stamping a directory.
+pub const LICENSE_FILE_NAMES: &[&str] = &[
+    "LICENSE",
+    "LICENSES",
+    "LICENCE",
+    "COPYING",
+    "COPYRIGHT",
+    "NOTICE",
+    "UNLICENSE",
+];
+
+/// What [`write_comment`] did to a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CommentOutcome {
+    Added,
+    Replaced,
+    Skipped,
+    NoMatch,
+    Binary,
+}
+
+/// How many leading bytes of a file are checked for a null byte to tell
+/// binary files apart from text.
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+/// Whether `bytes` look like a binary file rather than text.
+#[tracing::instrument(skip(bytes))]
+fn is_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0)
+}
+
+/// The UTF-8 byte order mark some editors put at the start of a file.
+const BOM: &str = "\u{feff}";
+
+/// The line ending used by `text`: `\r\n` if its first line ends with
+/// one, otherwise `\n`.
+#[tracing::instrument(skip(text))]
+fn line_ending(text: &str) -> &'static str {
+    match text.find('\n') {
+        Some(index) if text[..index].ends_with('\r') => "\r\n",
+        _ => "\n",
+    }
+}
+
+#[tracing::instrument(skip(comment_block))]
+fn write_comment<P: AsRef<Path> + std::fmt::Debug>(
+    comment: &str,
+    comment_block: &str,
+    output_file: P,
+    header: &HeaderOptions,
+) -> io::Result<CommentOutcome> {
+    let bytes = fs::read(&output_file)?;
+    if is_binary(&bytes) {
+        return Ok(CommentOutcome::Binary);
+    }
+    let src =
+        String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let (bom, body) = match src.strip_prefix(BOM) {
+        Some(body) => (BOM, body),
+        None => ("", src.as_str()),
+    };
+    let newline = line_ending(body);
+    let marker = match &header.content_match {
+        Some(pattern) => match pattern.find(body) {
+            Some(m) => Some(m),
+            None => return Ok(CommentOutcome::NoMatch),
+        },
+        None => None,
+    };
+    let lines: Vec<&str> = body.lines().collect();
+    let scan = header.position.scan_range(lines.len());
+    let existing = lines[scan.clone()]
+        .iter()
+        .position(|line| line.contains(SPDX_MARKER))
+        .map(|index| scan.start + index);
+    let (before, after, outcome) = match (existing, marker) {
+        _ if header.strip_existing => {
+            let (start, mut after, mut stripped) = strip_headers(&lines, comment);
+            let mut before = lines[..start].to_vec();
+            if header.position == HeaderPosition::Bottom {
+                before.append(&mut after);
+                stripped |= strip_trailing_headers(&mut before, comment);
+                while before.last().is_some_and(|line| line.trim().is_empty()) {
+                    before.pop();
+                }
+                if !before.is_empty() {
+                    before.push("");
+                }
+            }
+            let outcome = if stripped {
+                CommentOutcome::Replaced
+            } else {
+                CommentOutcome::Added
+            };
+            (before, after, outcome)
+        }
+        (Some(_), _) if !header.force => return Ok(CommentOutcome::Skipped),
+        (Some(index), _) => {
+            let (start, end) = header_block(&lines, index, comment);
+            (
+                lines[..start].to_vec(),
+                lines[end..].to_vec(),
+                CommentOutcome::Replaced,
+            )
+        }
+        (None, Some(m)) if header.replace_match => {
+            // A match ending in a newline ends on the line before.
+            let start = body[..m.start()].matches('\n').count();
+            let end =
+                body[..m.end()].matches('\n').count() + usize::from(!m.as_str().ends_with('\n'));
+            (
+                lines[..start].to_vec(),
+                lines[end.min(lines.len())..].to_vec(),
+                CommentOutcome::Added,
+            )
+        }
+        (None, _) if header.position == HeaderPosition::Bottom => {
+            (lines.clone(), Vec::new(), CommentOutcome::Added)
+        }
+        (None, _) => {
+            let start = shebang_len(&lines);
+            (
+                lines[..start].to_vec(),
+                lines[start..].to_vec(),
+                CommentOutcome::Added,
+            )
+        }
+    };
+
+    let mut contents = String::with_capacity(src.len() + comment_block.len());
+    contents.push_str(bom);
+    for line in &before {
+        contents.push_str(line);
+        contents.push_str(newline);
+    }
+    if header.position == HeaderPosition::Bottom
+        && outcome == CommentOutcome::Added
+        && before.last().is_some_and(|line| !line.trim().is_empty())
+    {
+        contents.push_str(newline);
+    }
+    for line in header
+        .wrapped(comment_block, comment.chars().count() + 1)
+        .lines()
+    {
+        contents.push_str(&format!("{comment} {line}{newline}"));
+    }
+    for line in after {
+        contents.push_str(line);
+        contents.push_str(newline);
+    }
+    if let Some(suffix) = &header.backup
+        && !header.dry_run
+    {
+        fs::copy(&output_file, backup_path(output_file.as_ref(), suffix))?;
+    }
+    rewrite_or_diff(output_file.as_ref(), &src, &contents, header.dry_run)?;
+    Ok(outcome)
+}
+
+/// Where '--backup' copies `path` to.
+#[tracing::instrument]
+fn backup_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Collects the diffs of an '--as-patch' run. `None` unless
+/// [`start_patch`] was called.
+static PATCH: Mutex<Option<String>> = Mutex::new(None);
+
+/// Collects the diffs of '--dry-run' into a patch for [`write_patch`]
+/// instead of printing them.
+#[tracing::instrument]
+pub fn start_patch() {
+    *PATCH.lock().unwrap() = Some(String::new());
+}
+
+/// Writes the diffs collected since [`start_patch`] to `path` as a patch
+/// that applies with `git apply`. Returns `false` if it could not be
+/// written.
+#[tracing::instrument]
+pub fn write_patch(path: &Path, header: &HeaderOptions) -> bool {
+    let patch = PATCH.lock().unwrap().take().unwrap_or_default();
+    if let Err(e) = fs::write(path, &patch) {
+        ceprintln!(
+            "<bold><red>Failed to write patch {}</></>: {}",
+            path.display(),
+            e,
+        );
+        return false;
+    }
+    print_status(
+        header.status_to_stderr(),
+        &cformat!(
+            "<bold><green>Wrote patch to {}</></>. Apply it with '<italics>git apply {}</>'.",
+            path.display(),
+            path.display(),
+        ),
+    );
+    true
+}
+
+/// Replaces the contents of `path` with `new`, or with '--dry-run' only
+/// prints a unified diff from `old` to `new`, or adds it to the patch
+/// with '--as-patch'.
+#[tracing::instrument(skip(old, new))]
+pub(crate) fn rewrite_or_diff(path: &Path, old: &str, new: &str, dry_run: bool) -> io::Result<()> {
+    if !dry_run {
+        return rewrite_file(path, new);
+    }
+    tracing::info!("Would modify {}", path.display());
+    let diff = unified_diff(path, Some(old), new);
+    match PATCH.lock().unwrap().as_mut() {
+        Some(patch) => patch.push_str(&diff),
+        None => print!("{diff}"),
+    }
+    Ok(())
+}
+
+/// Renders the change from `old` to `new` as a unified diff with a single
+/// hunk, which is all a header change needs. `None` means the file does
+/// not exist yet.
+#[tracing::instrument(skip(old, new))]
+pub(crate) fn unified_diff(path: &Path, old: Option<&str>, new: &str) -> String {
+    const CONTEXT: usize = 3;
+    const NO_NEWLINE: &str = "\\ No newline at end of file\n";
+    let exists = old.is_some();
+    let old_src = old.unwrap_or_default();
+    let old_missing_newline = !old_src.is_empty() && !old_src.ends_with('\n');
+    let new_missing_newline = !new.is_empty() && !new.ends_with('\n');
+    let old: Vec<&str> = old_src.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    // The last lines differ if only one of them ends with a newline.
+    let last_differs = old_missing_newline != new_missing_newline;
+    let mut prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    if last_differs {
+        prefix = prefix
+            .min(old.len().saturating_sub(1))
+            .min(new.len().saturating_sub(1));
+    }
+    let mut suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    if last_differs {
+        suffix = suffix.saturating_sub(1);
+    }
+    if !last_differs && prefix == old.len() && prefix == new.len() {
+        return String::new();
+    }
+    let start = prefix.saturating_sub(CONTEXT);
+    let old_end = (old.len() - suffix + CONTEXT).min(old.len());
+    let new_end = (new.len() - suffix + CONTEXT).min(new.len());
+    let range = |len: usize| match len {
+        0 => format!("{start},0"),
+        len => format!("{},{len}", start + 1),
+    };
+
+    let path = path.strip_prefix(".").unwrap_or(path).display();
+    let old_name = if exists {
+        format!("a/{path}")
+    } else {
+        "/dev/null".to_string()
+    };
+    let mut diff = format!(
+        "--- {old_name}\n+++ b/{path}\n@@ -{} +{} @@\n",
+        range(old_end - start),
+        range(new_end - start),
+    );
+    for line in &old[start..prefix] {
+        diff.push_str(&format!(" {line}\n"));
+    }
+    for (index, line) in old.iter().enumerate().take(old.len() - suffix).skip(prefix) {
+        diff.push_str(&format!("-{line}\n"));
+        if old_missing_newline && index + 1 == old.len() {
+            diff.push_str(NO_NEWLINE);
+        }
+    }
+    for (index, line) in new.iter().enumerate().take(new.len() - suffix).skip(prefix) {
+        diff.push_str(&format!("+{line}\n"));
+        if new_missing_newline && index + 1 == new.len() {
+            diff.push_str(NO_NEWLINE);
+        }
+    }
+    for line in &old[old.len() - suffix..old_end] {
+        diff.push_str(&format!(" {line}\n"));
+    }
+    if suffix > 0 && old_end == old.len() && old_missing_newline {
+        diff.push_str(NO_NEWLINE);
+    }
+    diff
+}
+
+/// Replaces the contents of `path` by writing to a temporary file in the
+/// same directory, so the final rename is atomic, and giving it the
+/// permissions of the original (e.g., to keep scripts executable).
+#[tracing::instrument(skip(contents))]
+pub(crate) fn rewrite_file<P: AsRef<Path> + std::fmt::Debug>(
+    path: P,
+    contents: &str,
+) -> io::Result<()> {
+    let path = path.as_ref();
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let permissions = fs::metadata(path)?.permissions();
+    let mut tmp_file = NamedTempFile::new_in(dir)?;
+    tmp_file.write_all(contents.as_bytes())?;
+    tmp_file.flush()?;
+    tmp_file.as_file().set_permissions(permissions)?;
+    tmp_file.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Whether `line` looks like a line [`write_comment`] produces: the
+/// comment token on its own or followed by a space. Doc comments such as
+/// `//!` are not header lines.
+pub(crate) fn is_header_line(line: &str, comment: &str) -> bool {
+    let line = line.trim_end();
+    line == comment
+        || line
+            .strip_prefix(comment)
+            .is_some_and(|rest| rest.starts_with(' '))
+}
+
+/// How many lines at the top of a file a header goes below: one for a
+/// `#!` interpreter line, which must stay first. Rust's `#![...]` inner
+/// attributes are not one.
+#[tracing::instrument]
+fn shebang_len(lines: &[&str]) -> usize {
+    usize::from(
+        lines
+            .first()
+            .is_some_and(|line| line.starts_with("#!") && !line.starts_with("#![")),
+    )
+}
+
+/// Removes every comment block at the top of the file that contains an
+/// SPDX identifier. A shebang line and leading comment blocks without an
+/// identifier are kept. Returns where the new header should be inserted,
+/// the lines that follow it, and whether anything was removed.
+#[tracing::instrument]
+fn strip_headers<'a>(lines: &[&'a str], comment: &str) -> (usize, Vec<&'a str>, bool) {
+    let start = shebang_len(lines);
+    let end = lines[start..]
+        .iter()
+        .position(|line| !line.trim().is_empty() && !is_header_line(line, comment))
+        .map_or(lines.len(), |offset| start + offset);
+
+    let mut kept: Vec<&str> = Vec::new();
+    let mut stripped = false;
+    for block in lines[start..end].split(|line| line.trim().is_empty()) {
+        if block.iter().any(|line| line.contains(SPDX_MARKER)) {
+            stripped = true;
+        } else if !block.is_empty() {
+            kept.push("");
+            kept.extend_from_slice(block);
+        }
+    }
+    if !stripped {
+        return (start, lines[start..].to_vec(), false);
+    }
+    if !kept.is_empty() && end < lines.len() {
+        kept.push("");
+    }
+    kept.extend_from_slice(&lines[end..]);
+    (start, kept, true)
+}
+
+/// Removes the trailing comment blocks of `lines` that contain an
+/// `SPDX-License-Identifier`, as left by a header put at the bottom.
+/// Returns whether any were removed.
+#[tracing::instrument(skip(lines))]
+fn strip_trailing_headers(lines: &mut Vec<&str>, comment: &str) -> bool {
+    let start = lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty() && !is_header_line(line, comment))
+        .map_or(0, |index| index + 1);
+    let mut kept: Vec<&str> = Vec::new();
+    let mut stripped = false;
+    for block in lines[start..].split(|line| line.trim().is_empty()) {
+        if block.iter().any(|line| line.contains(SPDX_MARKER)) {
+            stripped = true;
+        } else if !block.is_empty() {
+            kept.push("");
+            kept.extend_from_slice(block);
+        }
+    }
+    if stripped {
+        lines.truncate(start);
+        lines.extend(kept);
+    }
+    stripped
+}
+
+/// Finds the bounds of the header comment block around the line at
+/// `index`, so doc comments directly below the header are left alone.
+#[tracing::instrument]
+fn header_block(lines: &[&str], index: usize, comment: &str) -> (usize, usize) {
+    let mut start = index;
+    while start > 0 && is_header_line(lines[start - 1], comment) {
+        start -= 1;
+    }
+    let mut end = index + 1;
+    while end < lines.len() && is_header_line(lines[end], comment) {
+        end += 1;
+    }
+    (start, end)
+}
+
+/// Reads the '--files-from' list at `path`, or stdin if `path` is `-`:
+/// one path per line, ignoring blank lines.
+#[tracing::instrument]
+pub fn read_file_list(path: &Path) -> io::Result<Vec<PathBuf>> {
+    let list = if is_stdout(path) {
+        io::read_to_string(io::stdin())?
+    } else {
+        fs::read_to_string(path)?
+    };
+    Ok(list
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+/// Adds the license header to the files listed with '--files-from',
+/// reporting and skipping the ones that are not regular files.
+#[tracing::instrument(skip(comment_block))]
+fn stamp_listed_files(header: &HeaderOptions, comment_block: &str, stats: &mut HeaderStats) {
+    let files: Vec<PathBuf> = header
+        .files
+        .iter()
+        .flatten()
+        .filter(|path| {
+            if !path.is_file() {
+                ceprintln!(
+                    "<bold><yellow>Skipping {}</></>: not an existing file",
+                    path.display(),
+                );
+                return false;
+            }
+            if is_license_file(path, &header.license_file) {
+                tracing::debug!("Skipping {}: license file", path.display());
+                return false;
+            }
+            true
+        })
+        .cloned()
+        .collect();
+    stamp_files(&files, header, comment_block, stats);
+}
+
+/// Adds the license header to each of `files`, in parallel unless
+/// '--jobs 1' or '--dry-run' (whose diffs must stay in order) is set. The
+/// results are recorded in `stats` in the order of `files`.
+#[tracing::instrument(skip(files, comment_block))]
+fn stamp_files(
+    files: &[PathBuf],
+    header: &HeaderOptions,
+    comment_block: &str,
+    stats: &mut HeaderStats,
+) {
+    let progress = Progress::new(files.len(), header.progress);
+    if header.dry_run || header.jobs == Some(1) {
+        for path in files {
+            stamp_file(path, header, comment_block, stats);
+            progress.inc();
+        }
+    } else {
+        let pool = match ThreadPoolBuilder::new()
+            .num_threads(header.jobs.unwrap_or(0))
+            .build()
+        {
+            Ok(pool) => pool,
+            Err(e) => {
+                ceprintln!("<bold><red>Failed to start worker threads</></>: {}", e);
+                stats.failed.extend(files.iter().cloned());
+                return;
+            }
+        };
+        let results: Vec<HeaderStats> = pool.install(|| {
+            files
+                .par_iter()
+                .map(|path| {
+                    let mut file_stats = HeaderStats::default();
+                    stamp_file(path, header, comment_block, &mut file_stats);
+                    progress.inc();
+                    file_stats
+                })
+                .collect()
+        });
+        for file_stats in results {
+            stats.merge(file_stats);
+        }
+    }
+    progress.finish();
+}
+
+/// Adds the license header to a single file, recording the result in
+/// `stats`, unless '--only-dirty' leaves it alone because git tracks it as
+/// unchanged.
+#[tracing::instrument(skip(comment_block))]
+fn stamp_file(path: &Path, header: &HeaderOptions, comment_block: &str, stats: &mut HeaderStats) {
+    let clean = (header.warn_clean || header.only_dirty) && git::is_clean(path);
+    if clean && header.only_dirty {
+        tracing::info!("Skipping {}: unchanged in git", path.display());
+        stats.clean += 1;
+        return;
+    }
+    let modified = stats.modified.len();
+    if !stamp_contents(path, header, comment_block, stats) {
+        stats.failed.push(path.to_path_buf());
+    }
+    if clean && stats.modified.len() > modified {
+        ceprintln!(
+            "<bold><yellow>{} was unchanged in git</></>: adding the header will show up in its diff.",
+            path.display(),
+        );
+    }
+}
+
+/// Adds the license header to a single file, recording the result in
+/// `stats`. Returns `false` if the file could not be written.
+#[tracing::instrument(skip(comment_block))]
+fn stamp_contents(
+    path: &Path,
+    header: &HeaderOptions,
+    comment_block: &str,
+    stats: &mut HeaderStats,
+) -> bool {
+    if let Some(suffix) = &header.backup
+        && path
+            .as_os_str()
+            .to_string_lossy()
+            .ends_with(suffix.as_str())
+    {
+        tracing::debug!("Skipping {}: backup file", path.display());
+        return true;
+    }
+    if notebook::is_notebook(path) {
+        return stamp_notebook(path, header, comment_block, stats);
+    }
+    if header.comment.is_none() && mapped_comment(header, path).is_none() && docs::is_markdown(path)
+    {
+        return stamp_doc(path, header, comment_block, stats);
+    }
+    let Some(comment) = resolve_comment(header, path) else {
+        warn_unknown_comment(path);
+        return true;
+    };
+    match write_comment(comment, comment_block, path, header) {
+        Ok(CommentOutcome::Added | CommentOutcome::Replaced) => {
+            if header.verify_comments
+                && let Some(reason) = comment::verify_comment(path, comment)
+            {
                 ceprintln!(
-                    "<bold><red>Failed to write comment for file {}</></>: {e}",
-                    source_path.display(),
+                    "<bold><yellow>Header may break {}</></>: {}",
+                    path.display(),
+                    reason,
                 );
-                return;
             }
+            stats.modified.push(path.to_path_buf())
         }
-        (true, true, false, false) => {
-            ceprintln!(
-                "<bold><red>Source path is neither a file nor a directory</></>: {}",
-                source_path.display()
-            );
-            return;
+        Ok(CommentOutcome::Skipped) => {
+            tracing::info!("Skipping {}: SPDX header already present", path.display());
+            stats.skipped += 1;
         }
-        (true, false, _, _) => {
+        Ok(CommentOutcome::NoMatch) => {
+            tracing::debug!("Skipping {}: content does not match", path.display());
+        }
+        Ok(CommentOutcome::Binary) => {
             ceprintln!(
-                "<bold><red>Source path does not exist</></>: {}",
-                source_path.display()
+                "<bold><yellow>Skipping {}</></>: binary file",
+                path.display(),
             );
-            return;
         }
-        (false, _, _, _) => {
-            cprintln!(
-                "<bold><magenta>Add this as a comment to the top of your source file(s):</></>\n"
+        Err(e) => {
+            ceprintln!(
+                "<bold><red>Failed to write comment for file {}</></>: {}",
+                path.display(),
+                e,
             );
-            println!("{}", license.comment);
+            return false;
         }
-    };
+    }
+    true
+}
 
-    let mut license_file = match OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(&output)
-    {
-        Ok(file) => file,
+/// Updates the license section of a Markdown document, recording the
+/// result in `stats`. Returns `false` if the file could not be written.
+#[tracing::instrument(skip(comment_block))]
+fn stamp_doc(
+    path: &Path,
+    header: &HeaderOptions,
+    comment_block: &str,
+    stats: &mut HeaderStats,
+) -> bool {
+    match docs::update_license_section(path, comment_block, header) {
+        Ok(SectionOutcome::Updated) => stats.modified.push(path.to_path_buf()),
+        Ok(SectionOutcome::Unchanged) => {
+            tracing::info!("Skipping {}: license section up to date", path.display());
+            stats.skipped += 1;
+        }
+        Ok(SectionOutcome::NoSection) => {
+            tracing::debug!("Skipping {}: no license section", path.display());
+        }
         Err(e) => {
             ceprintln!(
-                "<bold><red>Failed to open license file {}</></>: {e}",
-                output.display(),
+                "<bold><red>Failed to update license section of {}</></>: {}",
+                path.display(),
+                e,
             );
-            return;
+            return false;
         }
-    };
+    }
+    true
+}
 
-    match license_file.write_all(license.text.as_bytes()) {
-        Ok(_) => (),
+/// Adds the license cell to a Jupyter notebook, recording the result in
+/// `stats`. Malformed notebooks are skipped with a warning. Returns
+/// `false` if the file could not be written.
+#[tracing::instrument(skip(comment_block))]
+fn stamp_notebook(
+    path: &Path,
+    header: &HeaderOptions,
+    comment_block: &str,
+    stats: &mut HeaderStats,
+) -> bool {
+    match notebook::add_license_cell(path, &header.wrapped(comment_block, 0), header) {
+        Ok(NotebookOutcome::Added | NotebookOutcome::Replaced) => {
+            stats.modified.push(path.to_path_buf())
+        }
+        Ok(NotebookOutcome::Skipped) => {
+            tracing::info!("Skipping {}: SPDX header already present", path.display());
+            stats.skipped += 1;
+        }
+        Ok(NotebookOutcome::NoMatch) => {
+            tracing::debug!("Skipping {}: content does not match", path.display());
+        }
+        Ok(NotebookOutcome::Malformed(reason)) => {
+            ceprintln!(
+                "<bold><yellow>Skipping malformed notebook {}</></>: {}",
+                path.display(),
+                reason,
+            );
+        }
         Err(e) => {
             ceprintln!(
-                "<bold><red>Failed to write license text to {}</></>: {e}",
-                output.display(),
+                "<bold><red>Failed to add license cell to {}</></>: {}",
+                path.display(),
+                e,
             );
-            return;
+            return false;
         }
-    };
+    }
+    true
+}
 
-    if let Err(e) = license_file.flush() {
-        ceprintln!(
-            "<bold><red>Failed to flush license file {}</></>: {e}",
-            output.display(),
-        );
-        return;
-    };
+#[tracing::instrument]
+pub(crate) fn resolve_comment<'a>(header: &'a HeaderOptions, path: &Path) -> Option<&'a str> {
+    mapped_comment(header, path)
+        .or(header.comment.as_deref())
+        .or_else(|| comment::comment_for_path(path))
+        .or(header.default_comment.as_deref())
+}
 
-    if let Some(alt) = &license.alt {
-        cprintln!(
-            r#"<magenta><bold>
-You'll need to include the following amendment to your license.</> 
-This is usually added to the end of the license file, but there is no strict requirement 
-for where it goes. Another common place is to add it as a comment at the top of your source 
-files or to the readme.</>
-"#,
-        );
-        println!("{alt}");
+/// The comment token '--comment-map' assigns to `path`, matched relative
+/// to the source path.
+#[tracing::instrument]
+fn mapped_comment<'a>(header: &'a HeaderOptions, path: &Path) -> Option<&'a str> {
+    let map = header.comment_map.as_ref()?;
+    let relative = match path.strip_prefix(&header.source_path) {
+        Ok(relative) if !relative.as_os_str().is_empty() => relative,
+        _ => Path::new(path.file_name()?),
     };
+    map.comment_for(relative)
+}
 
-    if let Some(interactive) = &license.interactive {
-        cprintln!(
-            r#"<magenta><bold>
-Since your program is interactive, you should also include the following notice in your program's output.</>
-This needs to be easily accessible to users, such as in a help command, at the start of the program, in 
-a footer section, or in an about section.</>
-"#,
-        );
-        println!("{interactive}");
+#[tracing::instrument]
+pub(crate) fn warn_unknown_comment(path: &Path) {
+    ceprintln!(
+        "<bold><yellow>Skipping {}</></>: unrecognized file extension. Use '<italics>--comment</>' or '<italics>--default-comment</>' to set the comment syntax.",
+        path.display(),
+    );
+}
+
+/// What [`iterate_dir`] skipped or could not read.
+#[derive(Debug, Default)]
+pub struct WalkStats {
+    /// Paths skipped because of `.gitignore`.
+    pub ignored: usize,
+    /// Directories and entries that could not be read. The walk goes on
+    /// past them.
+    pub failed: Vec<PathBuf>,
+}
+
+/// Walks `path` recursively and calls `visit` on every regular file that
+/// is not excluded or ignored by `.gitignore`, until `visit` returns
+/// `false`. Paths that can't be read are reported and skipped.
+#[tracing::instrument(skip(visit))]
+pub(crate) fn iterate_dir(
+    path: &Path,
+    header: &HeaderOptions,
+    visit: &mut dyn FnMut(&Path) -> bool,
+) -> WalkStats {
+    let mut rules = if header.no_ignore {
+        IgnoreRules::default()
+    } else {
+        IgnoreRules::for_walk(path)
     };
+    let mut walk = WalkStats::default();
+    walk_dir(path, header, 0, &mut rules, &mut walk, visit);
+    walk
 }
 
-#[tracing::instrument]
-fn write_comment<P: AsRef<Path> + std::fmt::Debug>(
-    comment: &str,
-    comment_block: &str,
-    output_file: P,
-) -> io::Result<()> {
-    let tmp_path = NamedTempFile::new()?.into_temp_path();
-    let mut tmp_file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(&tmp_path)?;
-    for line in comment_block.lines() {
-        writeln!(tmp_file, "{comment} {line}")?;
-    }
-    let src = OpenOptions::new().read(true).open(&output_file)?;
-    for line in io::BufReader::new(src).lines() {
-        writeln!(tmp_file, "{}", line?)?;
+/// Walks one directory of [`iterate_dir`] with its `.gitignore` rules.
+/// Returns `false` if `visit` stopped the walk.
+#[tracing::instrument(skip(visit))]
+fn walk_dir(
+    path: &Path,
+    header: &HeaderOptions,
+    depth: usize,
+    rules: &mut IgnoreRules,
+    walk: &mut WalkStats,
+    visit: &mut dyn FnMut(&Path) -> bool,
+) -> bool {
+    let entered = !header.no_ignore && rules.enter(path);
+    let ok = walk_entries(path, header, depth, rules, walk, visit);
+    if entered {
+        rules.leave();
     }
-    tmp_file.flush()?;
-    fs::remove_file(&output_file)?;
-    fs::rename(tmp_path, output_file)?;
-    Ok(())
+    ok
 }
 
-#[tracing::instrument]
-fn iterate_dir<P: AsRef<Path> + std::fmt::Debug>(path: P, comment: &str, comment_block: &str) {
-    let files = match path.as_ref().read_dir() {
+#[tracing::instrument(skip(visit))]
+fn walk_entries(
+    path: &Path,
+    header: &HeaderOptions,
+    depth: usize,
+    rules: &mut IgnoreRules,
+    walk: &mut WalkStats,
+    visit: &mut dyn FnMut(&Path) -> bool,
+) -> bool {
+    let files = match path.read_dir() {
         Ok(files) => files,
         Err(e) => {
             ceprintln!(
-                "<bold><red>Failed to read directory {}</></>: {e}",
-                path.as_ref().display(),
+                "<bold><red>Failed to read directory {}</></>: {}",
+                path.display(),
+                e,
             );
-            return;
+            walk.failed.push(path.to_path_buf());
+            return true;
         }
     };
     for file in files {
-        match file {
-            Ok(entry) => {
-                if let Err(e) = write_comment(comment, comment_block, entry.path()) {
-                    ceprintln!(
-                        "<bold><red>Failed to write comment for file {}</></>: {e}",
-                        entry.path().display(),
-                    );
-                    return;
-                }
+        let entry = match file {
+            Ok(entry) => entry,
+            Err(e) => {
+                ceprintln!(
+                    "<bold><red>Failed to read entry in directory {}</></>: {}",
+                    path.display(),
+                    e,
+                );
+                walk.failed.push(path.to_path_buf());
+                continue;
             }
+        };
+        let entry_path = entry.path();
+        let file_type = match entry.file_type() {
+            Ok(file_type) => file_type,
             Err(e) => {
                 ceprintln!(
-                    "<bold><red>Failed to read entry in directory {}</></>: {e}",
-                    path.as_ref().display(),
+                    "<bold><red>Failed to read file type of {}</></>: {}",
+                    entry_path.display(),
+                    e,
                 );
-                return;
+                walk.failed.push(entry_path);
+                continue;
+            }
+        };
+        let relative = entry_path
+            .strip_prefix(&header.source_path)
+            .unwrap_or(&entry_path);
+        if header.exclude.is_excluded(relative, file_type.is_dir()) {
+            tracing::debug!("Skipping {}: excluded", entry_path.display());
+            continue;
+        }
+        if rules.is_ignored(&entry_path, file_type.is_dir()) {
+            tracing::debug!("Skipping {}: ignored by .gitignore", entry_path.display());
+            walk.ignored += 1;
+            continue;
+        }
+        if file_type.is_dir() {
+            if header.max_depth.is_some_and(|max| depth >= max) {
+                tracing::debug!("Skipping {}: max depth reached", entry_path.display());
+                continue;
+            }
+            if !walk_dir(&entry_path, header, depth + 1, rules, walk, visit) {
+                return false;
             }
+            continue;
+        }
+        if !file_type.is_file() {
+            tracing::debug!("Skipping {}: not a regular file", entry_path.display());
+            continue;
+        }
+        if is_license_file(&entry_path, &header.license_file) {
+            tracing::debug!("Skipping {}: license file", entry_path.display());
+            continue;
+        }
+        if !visit(&entry_path) {
+            return false;
         }
     }
+    true
+}
+
+/// Whether `path` is the license file being written or has a common
+/// license file name such as `LICENSE`, `COPYING.LIB` or `LICENSE-MIT`.
+#[tracing::instrument]
+fn is_license_file(path: &Path, license_file: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    let name = name.to_ascii_uppercase();
+    let common = LICENSE_FILE_NAMES.iter().any(|base| {
+        name.strip_prefix(base)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '-', '_']))
+    });
+    common || same_path(path, license_file)
+}
+
+/// Compares two paths that may not exist yet by resolving their parent
+/// directories.
+#[tracing::instrument]
+fn same_path(a: &Path, b: &Path) -> bool {
+    let resolve = |p: &Path| {
+        let parent = match p.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        Some(fs::canonicalize(parent).ok()?.join(p.file_name()?))
+    };
+    matches!((resolve(a), resolve(b)), (Some(a), Some(b)) if a == b)
 }
 
 #[tracing::instrument]
@@ -176,31 +1757,141 @@ pub fn prompt<T>(q: &str) -> T
 where
     T: FromStr,
 {
-    loop {
-        cprint!("<bold><cyan>{}</></>: ", q);
-        match io::stdout().flush() {
-            Ok(_) => (),
-            Err(e) => {
-                ceprintln!("<bold><red>Failed to flush stdout</></>: {e}");
-                process::exit(1);
-            }
-        };
+    let default = if answers::deterministic() {
+        None
+    } else {
+        cargo::prompt_default(q)
+            .or_else(|| remember::last(q))
+            .or_else(|| git::prompt_default(q))
+    };
+    prompt_default(q, default.as_deref())
+}
 
-        let mut input = String::new();
-        match io::stdin().read_line(&mut input) {
-            Ok(_) => (),
-            Err(e) => {
-                ceprintln!("<bold><red>Failed to read line</></>: {e}");
-                process::exit(1);
-            }
+/// Prompts with `q`, using `default` when the answer is left blank.
+#[tracing::instrument(skip(default))]
+pub fn prompt_default<T>(q: &str, default: Option<&str>) -> T
+where
+    T: FromStr,
+{
+    prompt_keyed(q, q, default)
+}
+
+/// Records the answer given to `key` and remembers it for the next run.
+#[tracing::instrument(skip(value))]
+fn accept_answer(key: &str, value: &str) {
+    answers::record(key, value);
+    remember::update(key, value);
+}
+
+/// Takes the saved answer for `key` if there is one and it parses.
+#[tracing::instrument]
+fn replay_answer<T>(key: &str) -> Option<T>
+where
+    T: FromStr,
+{
+    let value = answers::replay(key)?;
+    match value.parse::<T>() {
+        Ok(parsed) => {
+            accept_answer(key, &value);
+            Some(parsed)
+        }
+        Err(_) => {
+            ceprintln!(
+                "<bold><yellow>Ignoring invalid saved answer for '{}'</></>: {}.",
+                key,
+                value
+            );
+            None
         }
+    }
+}
 
-        let trimmed_input = input.trim();
+/// Takes the value for `key` from the config file if it sets one and it
+/// parses.
+#[tracing::instrument]
+fn config_answer<T>(key: &str) -> Option<T>
+where
+    T: FromStr,
+{
+    let value = config::answer(key)?;
+    match value.parse::<T>() {
+        Ok(parsed) => {
+            accept_answer(key, &value);
+            Some(parsed)
+        }
+        Err(_) => {
+            ceprintln!(
+                "<bold><yellow>Ignoring invalid config value for '{}'</></>: {}.",
+                key,
+                value
+            );
+            None
+        }
+    }
+}
+
+/// The first of [`answers::STUB_ANSWERS`] that parses, if prompts are
+/// being stubbed.
+#[tracing::instrument]
+fn stub_answer<T>() -> Option<T>
+where
+    T: FromStr,
+{
+    if !answers::stubbed() {
+        return None;
+    }
+    answers::STUB_ANSWERS
+        .iter()
+        .find_map(|value| value.parse().ok())
+}
+
+/// Prompts with `q`, recording and replaying the answer under `key`. A
+/// blank answer takes `default`, if there is one.
+#[tracing::instrument(skip(default))]
+fn prompt_keyed<T>(key: &str, q: &str, default: Option<&str>) -> T
+where
+    T: FromStr,
+{
+    if let Some(value) = replay_answer(key)
+        .or_else(|| config_answer(key))
+        .or_else(stub_answer)
+    {
+        return value;
+    }
+    if answers::deterministic() {
+        ceprintln!(
+            "<bold><red>'<italics>--deterministic</>' needs an answer for '{}'</></>: give it with '<italics>--answers</>' or in <italics>license.toml</>.",
+            key,
+        );
+        process::exit(1);
+    }
+    let mut invalid = 0;
+    loop {
+        match default {
+            Some(default) => ceprint!("<bold><cyan>{}</></> <dim>[{}]</>: ", q, default),
+            None => ceprint!("<bold><cyan>{}</></>: ", q),
+        }
+        let Some(input) = read_answer() else {
+            ceprintln!(
+                "<bold><red>Unexpected end of input</></>: stdin closed before '{}' was answered. Give the answers with '<italics>--answers</>' or in <italics>license.toml</>.",
+                key,
+            );
+            process::exit(1);
+        };
+
+        let trimmed_input = match (input.trim(), default) {
+            ("", Some(default)) => default,
+            (trimmed, _) => trimmed,
+        };
 
         match trimmed_input.parse::<T>() {
-            Ok(value) => return value,
+            Ok(value) => {
+                accept_answer(key, trimmed_input);
+                return value;
+            }
             Err(_) => {
-                ceprintln!("<bold><yellow>Invalid input</></>: {trimmed_input}.");
+                ceprintln!("<bold><yellow>Invalid input</></>: {}.", trimmed_input);
+                retry_or_exit(key, &mut invalid);
                 ceprintln!("<bold><yellow>Please try again.</></>");
             }
         }
@@ -212,34 +1903,66 @@ pub fn prompt_optional<T>(q: &str) -> Option<T>
 where
     T: FromStr,
 {
-    loop {
-        cprint!("<bold><cyan>{q}</></> <dim>(<italics>optional</>)</>: ");
-        match io::stdout().flush() {
-            Ok(_) => (),
-            Err(e) => {
-                ceprintln!("<bold><red>Failed to flush stdout</></>: {e}");
-                process::exit(1);
-            }
-        };
+    prompt_optional_keyed(q, q)
+}
 
-        let mut input = String::new();
-        match io::stdin().read_line(&mut input) {
-            Ok(_) => (),
-            Err(e) => {
-                ceprintln!("<bold><red>Failed to read line</></>: {e}");
-                process::exit(1);
+/// Prompts with `q`, recording and replaying the answer under `key`. An
+/// empty answer is recorded as an empty string.
+#[tracing::instrument]
+fn prompt_optional_keyed<T>(key: &str, q: &str) -> Option<T>
+where
+    T: FromStr,
+{
+    if let Some(value) = answers::replay(key) {
+        if value.is_empty() {
+            answers::record(key, &value);
+            return None;
+        }
+        match value.parse::<T>() {
+            Ok(parsed) => {
+                answers::record(key, &value);
+                return Some(parsed);
+            }
+            Err(_) => {
+                ceprintln!(
+                    "<bold><yellow>Ignoring invalid saved answer for '{}'</></>: {}.",
+                    key,
+                    value,
+                );
             }
         }
+    }
+    if let Some(value) = config_answer(key).or_else(stub_answer) {
+        return Some(value);
+    }
+    // None of the stub answers fit, so leave it blank.
+    if answers::stubbed() {
+        return None;
+    }
+    if answers::deterministic() {
+        answers::record(key, "");
+        return None;
+    }
+    let mut invalid = 0;
+    loop {
+        ceprint!("<bold><cyan>{}</></> <dim>(<italics>optional</>)</>: ", q);
+        // A closed stdin leaves the answer blank.
+        let input = read_answer().unwrap_or_default();
 
         let trimmed_input = input.trim();
 
         if trimmed_input.is_empty() {
+            answers::record(key, trimmed_input);
             return None;
         } else {
             match trimmed_input.parse::<T>() {
-                Ok(value) => return Some(value),
+                Ok(value) => {
+                    answers::record(key, trimmed_input);
+                    return Some(value);
+                }
                 Err(_) => {
-                    ceprintln!("<bold><yellow>Invalid input</></>: {trimmed_input}.");
+                    ceprintln!("<bold><yellow>Invalid input</></>: {}.", trimmed_input);
+                    retry_or_exit(key, &mut invalid);
                     ceprintln!("<bold><yellow>Please try again or leave blank for none.</></>");
                 }
             }
@@ -247,38 +1970,165 @@ where
     }
 }
 
+/// Reads one answer from stdin, or `None` if stdin is closed. Exits if
+/// stdin is unreadable.
+#[tracing::instrument]
+fn read_answer() -> Option<String> {
+    if let Err(e) = io::stderr().flush() {
+        ceprintln!("<bold><red>Failed to flush stderr</></>: {}", e);
+        process::exit(1);
+    }
+    let mut input = String::new();
+    match io::stdin().read_line(&mut input) {
+        Ok(0) => {
+            eprintln!();
+            None
+        }
+        Ok(_) => Some(input),
+        Err(e) => {
+            ceprintln!("<bold><red>Failed to read line</></>: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Counts an invalid answer to the prompt for `key` in `invalid`, exiting
+/// once there are more than '--prompt-retries' of them.
+#[tracing::instrument]
+fn retry_or_exit(key: &str, invalid: &mut usize) {
+    *invalid += 1;
+    if *invalid > answers::prompt_retries() {
+        ceprintln!(
+            "<bold><red>Too many invalid answers</></>: giving up on '{}' after {} tries.",
+            key,
+            invalid,
+        );
+        process::exit(1);
+    }
+}
+
 #[tracing::instrument]
 pub fn prompt_bool(q: &str) -> bool {
+    let mut invalid = 0;
     loop {
-        let response = prompt::<String>(&cformat!(
-            "{q} <dim>(<italics>[<bold>y</bold>]es</italics>/<italics>[<bold>n</bold>]o</italics>)</dim>",
-        ));
+        let response = prompt_keyed::<String>(
+            q,
+            &cformat!(
+                "{q} <dim>(<italics>[<bold>y</bold>]es</italics>/<italics>[<bold>n</bold>]o</italics>)</dim>",
+            ),
+            None,
+        );
         match response.to_lowercase().as_str() {
             "yes" | "y" | "true" | "t" => return true,
             "no" | "n" | "false" | "f" => return false,
-            _ => ceprintln!(
-                "<bold><yellow>Please answer '<italics>yes</>' or '<italics>no</>'.</></>"
-            ),
+            _ => {
+                retry_or_exit(q, &mut invalid);
+                ceprintln!(
+                    "<bold><yellow>Please answer '<italics>yes</>' or '<italics>no</>'.</></>"
+                );
+            }
         }
     }
 }
 
 #[tracing::instrument]
 pub fn prompt_optional_bool(q: &str) -> Option<bool> {
+    let mut invalid = 0;
     loop {
-        let response = prompt_optional::<String>(&cformat!(
-            "{q} <dim>(<italics>[<bold>y</bold>]es</italics>/<italics>[<bold>n</bold>]o</italics>)</dim>",
-        ));
+        let response = prompt_optional_keyed::<String>(
+            q,
+            &cformat!(
+                "{q} <dim>(<italics>[<bold>y</bold>]es</italics>/<italics>[<bold>n</bold>]o</italics>)</dim>",
+            ),
+        );
         match response {
             Some(r) => match r.to_lowercase().as_str() {
                 "yes" | "y" | "true" | "t" => return Some(true),
                 "no" | "n" | "false" | "f" => return Some(false),
                 "" => return None,
-                _ => ceprintln!(
-                    "<bold><yellow>Please answer '<italics>yes</>', '<italics>no</>', or <italics>leave blank</> for none.</></>"
-                ),
+                _ => {
+                    retry_or_exit(q, &mut invalid);
+                    ceprintln!(
+                        "<bold><yellow>Please answer '<italics>yes</>', '<italics>no</>', or <italics>leave blank</> for none.</></>"
+                    );
+                }
             },
             None => return None,
         }
     }
 }
+
+/// Lists `options` with numbers and prompts with `q` until one or more of
+/// them are picked, as numbers separated by commas or spaces. Returns the
+/// indices of the picked options in the order they were given.
+#[tracing::instrument(skip(options))]
+pub fn prompt_multi_select(q: &str, options: &[String]) -> Vec<usize> {
+    for (index, option) in options.iter().enumerate() {
+        ceprintln!("<bold>{:>3}</>) {}", index + 1, option);
+    }
+    let mut invalid = 0;
+    loop {
+        let response = prompt_keyed::<String>(
+            q,
+            &cformat!("{q} <dim>(<italics>e.g., 1, 3</>)</dim>"),
+            None,
+        );
+        match parse_selection(&response, options.len()) {
+            Some(picked) => return picked,
+            None => {
+                retry_or_exit(q, &mut invalid);
+                ceprintln!(
+                    "<bold><yellow>Please enter one or more numbers between 1 and {}.</></>",
+                    options.len()
+                );
+            }
+        }
+    }
+}
+
+/// Lists `options` with numbers and prompts with `q` until one of them is
+/// picked by its number. Returns the index of the picked option.
+#[tracing::instrument(skip(options))]
+pub fn prompt_select(q: &str, options: &[String]) -> usize {
+    for (index, option) in options.iter().enumerate() {
+        ceprintln!("<bold>{:>3}</>) {}", index + 1, option);
+    }
+    let mut invalid = 0;
+    loop {
+        let response = prompt_keyed::<String>(
+            q,
+            &cformat!("{q} <dim>(<italics>1-{}</>)</dim>", options.len()),
+            None,
+        );
+        match response.trim().parse::<usize>() {
+            Ok(number) if (1..=options.len()).contains(&number) => return number - 1,
+            _ => {
+                retry_or_exit(q, &mut invalid);
+                ceprintln!(
+                    "<bold><yellow>Please enter a number between 1 and {}.</></>",
+                    options.len()
+                );
+            }
+        }
+    }
+}
+
+/// Parses a list of 1-based option numbers into distinct 0-based indices.
+/// Returns `None` if a number is out of range or nothing was picked.
+#[tracing::instrument(skip(response))]
+fn parse_selection(response: &str, len: usize) -> Option<Vec<usize>> {
+    let mut picked = Vec::new();
+    for token in response
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|t| !t.is_empty())
+    {
+        let number: usize = token.parse().ok()?;
+        if !(1..=len).contains(&number) {
+            return None;
+        }
+        if !picked.contains(&(number - 1)) {
+            picked.push(number - 1);
+        }
+    }
+    (!picked.is_empty()).then_some(picked)
+}
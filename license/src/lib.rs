@@ -1,4 +1,24 @@
+pub mod answers;
+pub mod cargo;
 pub mod cli;
+pub mod color;
+pub mod comment;
+pub mod config;
+pub mod docs;
+pub mod exclude;
+pub mod git;
+pub mod guess;
+pub mod index;
 pub mod io;
 pub mod license;
+pub mod markdown;
+pub mod modernize;
+pub mod notebook;
+pub mod progress;
+pub mod remember;
+pub mod remove;
+pub mod reuse;
+pub mod selftest;
+pub mod summary;
 pub mod texts;
+pub mod wrap;
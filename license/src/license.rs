@@ -1,6 +1,18 @@
+use crate::ceprintln;
+use crate::io;
 use crate::texts;
 use clap::ValueEnum;
+use clap::builder::{PossibleValue, TypedValueParser};
+use clap::error::ErrorKind;
+use color_print::cformat;
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use std::ffi::OsStr;
 use std::fmt;
+use std::fs;
+use std::process;
+use std::str::FromStr;
+use std::sync::LazyLock;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum VersionAmmendment {
@@ -21,6 +33,21 @@ impl fmt::Display for VersionAmmendment {
     }
 }
 
+impl FromStr for VersionAmmendment {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "" => Ok(VersionAmmendment::None),
+            "-only" => Ok(VersionAmmendment::Only),
+            "-or-later" => Ok(VersionAmmendment::OrLater),
+            _ => Err(format!(
+                "Unknown version suffix '{s}' (expected '-only' or '-or-later')"
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum BsdAmmendment {
     None,
@@ -43,19 +70,46 @@ impl fmt::Display for BsdAmmendment {
     }
 }
 
+impl FromStr for BsdAmmendment {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "" => Ok(BsdAmmendment::None),
+            "-attribution" => Ok(BsdAmmendment::Attribution),
+            "-modification" => Ok(BsdAmmendment::Modification),
+            "-no-military-license" => Ok(BsdAmmendment::NoMilitary),
+            _ => Err(format!(
+                "Unknown BSD-3-Clause variant '{s}' (expected '-Attribution', '-Modification', or '-No-Military-License')"
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Licenses {
     Mit,
     Agpl3(VersionAmmendment),
     Gpl3(VersionAmmendment),
     Lgpl3(VersionAmmendment),
+    Gpl2(VersionAmmendment),
+    Lgpl21(VersionAmmendment),
     Apache2,
     Bsl1,
     Unlicense,
     Cddl1,
     Epl2,
     Mpl2,
+    Bsd2Clause,
+    Bsd2ClausePatent,
     Bsd3Clause(BsdAmmendment),
+    Zlib,
+    ZeroBsd,
+    Cc0,
+    CcBy4,
+    Cecill21,
+    Wtfpl,
+    Eupl12,
 }
 
 impl fmt::Display for Licenses {
@@ -65,17 +119,111 @@ impl fmt::Display for Licenses {
             Licenses::Agpl3(a) => write!(f, "AGPL-3.0").and_then(|_| write!(f, "{a}")),
             Licenses::Gpl3(a) => write!(f, "GPL-3.0").and_then(|_| write!(f, "{a}")),
             Licenses::Lgpl3(a) => write!(f, "LGPL-3.0").and_then(|_| write!(f, "{a}")),
+            Licenses::Gpl2(a) => write!(f, "GPL-2.0").and_then(|_| write!(f, "{a}")),
+            Licenses::Lgpl21(a) => write!(f, "LGPL-2.1").and_then(|_| write!(f, "{a}")),
             Licenses::Apache2 => write!(f, "Apache-2.0"),
             Licenses::Bsl1 => write!(f, "BSL-1.0"),
             Licenses::Unlicense => write!(f, "Unlicense"),
             Licenses::Cddl1 => write!(f, "CDDL-1.0"),
             Licenses::Epl2 => write!(f, "EPL-2.0"),
             Licenses::Mpl2 => write!(f, "MPL-2.0"),
+            Licenses::Bsd2Clause => write!(f, "BSD-2-Clause"),
+            Licenses::Bsd2ClausePatent => write!(f, "BSD-2-Clause-Patent"),
             Licenses::Bsd3Clause(a) => write!(f, "BSD-3-Clause").and_then(|_| write!(f, "{a}")),
+            Licenses::Zlib => write!(f, "Zlib"),
+            Licenses::ZeroBsd => write!(f, "0BSD"),
+            Licenses::Cc0 => write!(f, "CC0-1.0"),
+            Licenses::CcBy4 => write!(f, "CC-BY-4.0"),
+            Licenses::Cecill21 => write!(f, "CECILL-2.1"),
+            Licenses::Wtfpl => write!(f, "WTFPL"),
+            Licenses::Eupl12 => write!(f, "EUPL-1.2"),
         }
     }
 }
 
+/// Strips the prefix `base` from `s`, ignoring ASCII case.
+fn strip_id<'a>(s: &'a str, base: &str) -> Option<&'a str> {
+    let prefix = s.get(..base.len())?;
+    prefix.eq_ignore_ascii_case(base).then(|| &s[base.len()..])
+}
+
+/// Parses an SPDX identifier, the inverse of the [`fmt::Display`] impl.
+/// Like SPDX, the case of the identifier is ignored.
+impl FromStr for Licenses {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || format!("Unknown SPDX license identifier '{s}' (see 'license list')");
+        let versioned: [(&str, fn(_) -> _); 5] = [
+            ("AGPL-3.0", Licenses::Agpl3),
+            ("LGPL-3.0", Licenses::Lgpl3),
+            ("LGPL-2.1", Licenses::Lgpl21),
+            ("GPL-3.0", Licenses::Gpl3),
+            ("GPL-2.0", Licenses::Gpl2),
+        ];
+        for (base, license) in versioned {
+            if let Some(suffix) = strip_id(s, base) {
+                return suffix.parse().map(license).map_err(|_| unknown());
+            }
+        }
+        if let Some(suffix) = strip_id(s, "BSD-3-Clause") {
+            return suffix
+                .parse()
+                .map(Licenses::Bsd3Clause)
+                .map_err(|_| unknown());
+        }
+        match s.to_ascii_lowercase().as_str() {
+            "mit" => Ok(Licenses::Mit),
+            "apache-2.0" => Ok(Licenses::Apache2),
+            "bsl-1.0" => Ok(Licenses::Bsl1),
+            "unlicense" => Ok(Licenses::Unlicense),
+            "cddl-1.0" => Ok(Licenses::Cddl1),
+            "epl-2.0" => Ok(Licenses::Epl2),
+            "mpl-2.0" => Ok(Licenses::Mpl2),
+            "bsd-2-clause" => Ok(Licenses::Bsd2Clause),
+            "bsd-2-clause-patent" => Ok(Licenses::Bsd2ClausePatent),
+            "zlib" => Ok(Licenses::Zlib),
+            "0bsd" => Ok(Licenses::ZeroBsd),
+            "cc0-1.0" => Ok(Licenses::Cc0),
+            "cc-by-4.0" => Ok(Licenses::CcBy4),
+            "cecill-2.1" => Ok(Licenses::Cecill21),
+            "wtfpl" => Ok(Licenses::Wtfpl),
+            "eupl-1.2" => Ok(Licenses::Eupl12),
+            _ => Err(unknown()),
+        }
+    }
+}
+
+impl TryFrom<&str> for Licenses {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// (De)serializes the types as their SPDX strings, e.g. `"GPL-3.0-or-later"`
+/// and `"-or-later"`, through their `Display` and `FromStr` impls.
+macro_rules! serde_as_spdx {
+    ($($ty:ty),*) => {$(
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer)?
+                    .parse()
+                    .map_err(de::Error::custom)
+            }
+        }
+    )*};
+}
+
+serde_as_spdx!(Licenses, VersionAmmendment, BsdAmmendment);
+
 impl ValueEnum for Licenses {
     #[tracing::instrument]
     fn value_variants<'a>() -> &'a [Self] {
@@ -90,16 +238,31 @@ impl ValueEnum for Licenses {
             Licenses::Lgpl3(VersionAmmendment::None),
             Licenses::Lgpl3(VersionAmmendment::Only),
             Licenses::Lgpl3(VersionAmmendment::OrLater),
+            Licenses::Gpl2(VersionAmmendment::None),
+            Licenses::Gpl2(VersionAmmendment::Only),
+            Licenses::Gpl2(VersionAmmendment::OrLater),
+            Licenses::Lgpl21(VersionAmmendment::None),
+            Licenses::Lgpl21(VersionAmmendment::Only),
+            Licenses::Lgpl21(VersionAmmendment::OrLater),
             Licenses::Apache2,
             Licenses::Bsl1,
             Licenses::Unlicense,
             Licenses::Cddl1,
             Licenses::Epl2,
             Licenses::Mpl2,
+            Licenses::Bsd2Clause,
+            Licenses::Bsd2ClausePatent,
             Licenses::Bsd3Clause(BsdAmmendment::None),
             Licenses::Bsd3Clause(BsdAmmendment::Attribution),
             Licenses::Bsd3Clause(BsdAmmendment::Modification),
             Licenses::Bsd3Clause(BsdAmmendment::NoMilitary),
+            Licenses::Zlib,
+            Licenses::ZeroBsd,
+            Licenses::Cc0,
+            Licenses::CcBy4,
+            Licenses::Cecill21,
+            Licenses::Wtfpl,
+            Licenses::Eupl12,
         ]
     }
 
@@ -109,19 +272,369 @@ impl ValueEnum for Licenses {
     }
 }
 
+/// Parses the license argument like [`FromStr`], suggesting the closest
+/// supported identifier for a near miss (e.g., `Apache2`).
+#[derive(Debug, Clone, Copy)]
+pub struct LicenseParser;
+
+impl TypedValueParser for LicenseParser {
+    type Value = Licenses;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        _arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, clap::Error> {
+        let Some(value) = value.to_str() else {
+            return Err(clap::Error::new(ErrorKind::InvalidUtf8).with_cmd(cmd));
+        };
+        value.parse().map_err(|_| {
+            let message = match closest_license(value) {
+                Some(closest) => format!("unknown license '{value}', did you mean '{closest}'?\n"),
+                None => format!("unknown license '{value}' (see 'license list')\n"),
+            };
+            clap::Error::raw(ErrorKind::InvalidValue, message).with_cmd(cmd)
+        })
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(
+            Licenses::value_variants()
+                .iter()
+                .filter_map(ValueEnum::to_possible_value),
+        ))
+    }
+}
+
+/// The supported license whose identifier is closest to `typed`, ignoring
+/// case and punctuation, if it is close enough to be a typo.
 #[tracing::instrument]
-pub fn generate_license_text(license: &Licenses) -> texts::LicenseTexts {
+pub fn closest_license(typed: &str) -> Option<&'static Licenses> {
+    let key = |id: &str| -> String {
+        id.chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_lowercase())
+            .collect()
+    };
+    let typed = key(typed);
+    let max_distance = (typed.len() / 4).max(1);
+    Licenses::value_variants()
+        .iter()
+        .map(|license| {
+            (
+                strsim::levenshtein(&typed, &key(&license.to_string())),
+                license,
+            )
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, license)| license)
+}
+
+impl VersionAmmendment {
+    fn describe(&self) -> &'static str {
+        match self {
+            VersionAmmendment::None => "",
+            VersionAmmendment::Only => " only",
+            VersionAmmendment::OrLater => " or later",
+        }
+    }
+}
+
+impl Licenses {
+    /// A one-line, human readable description of the license.
+    #[tracing::instrument]
+    pub fn description(&self) -> String {
+        match self {
+            Licenses::Mit => "MIT License".to_string(),
+            Licenses::Agpl3(a) => {
+                format!("GNU Affero General Public License v3.0{}", a.describe())
+            }
+            Licenses::Gpl3(a) => format!("GNU General Public License v3.0{}", a.describe()),
+            Licenses::Lgpl3(a) => {
+                format!("GNU Lesser General Public License v3.0{}", a.describe())
+            }
+            Licenses::Gpl2(a) => format!("GNU General Public License v2.0{}", a.describe()),
+            Licenses::Lgpl21(a) => {
+                format!("GNU Lesser General Public License v2.1{}", a.describe())
+            }
+            Licenses::Apache2 => "Apache License 2.0".to_string(),
+            Licenses::Bsl1 => "Boost Software License 1.0".to_string(),
+            Licenses::Unlicense => "The Unlicense".to_string(),
+            Licenses::Cddl1 => "Common Development and Distribution License 1.0".to_string(),
+            Licenses::Epl2 => "Eclipse Public License 2.0".to_string(),
+            Licenses::Mpl2 => "Mozilla Public License 2.0".to_string(),
+            Licenses::Bsd2Clause => "BSD 2-Clause \"Simplified\" License".to_string(),
+            Licenses::Bsd2ClausePatent => "BSD-2-Clause Plus Patent License".to_string(),
+            Licenses::Bsd3Clause(BsdAmmendment::None) => {
+                "BSD 3-Clause \"New\" or \"Revised\" License".to_string()
+            }
+            Licenses::Bsd3Clause(BsdAmmendment::Attribution) => "BSD with Attribution".to_string(),
+            Licenses::Bsd3Clause(BsdAmmendment::Modification) => {
+                "BSD 3-Clause Modification".to_string()
+            }
+            Licenses::Bsd3Clause(BsdAmmendment::NoMilitary) => {
+                "BSD 3-Clause No Military License".to_string()
+            }
+            Licenses::Zlib => "zlib License".to_string(),
+            Licenses::ZeroBsd => "BSD Zero Clause License".to_string(),
+            Licenses::Cc0 => "Creative Commons Zero v1.0 Universal".to_string(),
+            Licenses::CcBy4 => "Creative Commons Attribution 4.0 International".to_string(),
+            Licenses::Cecill21 => "CeCILL Free Software License Agreement v2.1".to_string(),
+            Licenses::Wtfpl => "Do What The F*ck You Want To Public License".to_string(),
+            Licenses::Eupl12 => "European Union Public License 1.2".to_string(),
+        }
+    }
+
+    /// The name of the file the license is written to when generating
+    /// several licenses at once, e.g. `LICENSE-MIT` or `LICENSE-APACHE`.
+    #[tracing::instrument]
+    pub fn file_name(&self) -> String {
+        match self {
+            Licenses::Apache2 => "LICENSE-APACHE".to_string(),
+            license => format!("LICENSE-{}", license.to_string().to_uppercase()),
+        }
+    }
+
+    /// Whether generating the license asks any questions.
+    #[tracing::instrument]
+    pub fn is_interactive(&self) -> bool {
+        !matches!(
+            self,
+            Licenses::Bsl1 | Licenses::Unlicense | Licenses::Cddl1 | Licenses::Mpl2 | Licenses::Cc0
+        )
+    }
+}
+
+/// Prints every supported license, one per line, as tab-separated SPDX
+/// identifier, `interactive` or `static`, and description.
+#[tracing::instrument]
+pub fn print_licenses() {
+    for license in Licenses::value_variants() {
+        let kind = if license.is_interactive() {
+            "interactive"
+        } else {
+            "static"
+        };
+        println!("{license}\t{kind}\t{}", license.description());
+    }
+}
+
+/// Asks which single license to generate, listing every supported one.
+#[tracing::instrument]
+pub fn pick_license() -> Licenses {
+    let variants = Licenses::value_variants();
+    let options: Vec<String> = variants
+        .iter()
+        .map(|license| cformat!("{license} <dim>({})</>", license.description()))
+        .collect();
+    variants[io::prompt_select("Select a license", &options)].clone()
+}
+
+/// Asks which licenses to offer and whether they apply together, and
+/// builds the SPDX expression for them (e.g., `MIT OR Apache-2.0`).
+#[tracing::instrument]
+pub fn select_expression() -> String {
+    let variants = Licenses::value_variants();
+    let options: Vec<String> = variants
+        .iter()
+        .map(|license| cformat!("{license} <dim>({})</>", license.description()))
+        .collect();
+    let picked: Vec<String> = io::prompt_multi_select("Select the licenses to offer", &options)
+        .into_iter()
+        .map(|index| variants[index].to_string())
+        .collect();
+    let operator = if picked.len() > 1
+        && io::prompt_bool(
+            "Must users comply with all of the selected licenses at once? (AND instead of OR)",
+        ) {
+        " AND "
+    } else {
+        " OR "
+    };
+    picked.join(operator)
+}
+
+/// Renders `license` from `answers` and their options alone, without
+/// asking, printing, or reading anything. Fails if an answer the license
+/// needs is missing or invalid, or if its template doesn't render.
+#[tracing::instrument(skip(answers))]
+pub fn render(
+    license: &Licenses,
+    answers: &texts::LicenseAnswers,
+) -> Result<texts::LicenseTexts, texts::RenderError> {
+    let texts = match license {
+        Licenses::Mit => texts::generate_mit_license(answers),
+        Licenses::Agpl3(a) => texts::generate_agpl_license(a.clone(), answers),
+        Licenses::Gpl3(a) => texts::generate_gpl_license(a.clone(), answers),
+        Licenses::Lgpl3(a) => texts::generate_lgpl_license(a.clone(), answers),
+        Licenses::Gpl2(a) => texts::generate_gpl2_license(a.clone(), answers),
+        Licenses::Lgpl21(a) => texts::generate_lgpl21_license(a.clone(), answers),
+        Licenses::Apache2 => texts::generate_apache_license(answers),
+        Licenses::Bsl1 => texts::generate_bsl_license(answers),
+        Licenses::Unlicense => texts::generate_unlicense_license(answers),
+        Licenses::Cddl1 => texts::generate_cddl_license(answers),
+        Licenses::Epl2 => texts::generate_epl_license(answers),
+        Licenses::Mpl2 => texts::generate_mpl_license(answers),
+        Licenses::Bsd2Clause => texts::generate_bsd2_license(answers),
+        Licenses::Bsd2ClausePatent => texts::generate_bsd2_patent_license(answers),
+        Licenses::Bsd3Clause(a) => texts::generate_bsd_license(a.clone(), answers),
+        Licenses::Zlib => texts::generate_zlib_license(answers),
+        Licenses::ZeroBsd => texts::generate_0bsd_license(answers),
+        Licenses::Cc0 => texts::generate_cc0_license(answers),
+        Licenses::CcBy4 => texts::generate_cc_by_license(answers),
+        Licenses::Cecill21 => texts::generate_cecill_license(answers),
+        Licenses::Wtfpl => texts::generate_wtfpl_license(answers),
+        Licenses::Eupl12 => texts::generate_eupl_license(answers),
+    }?;
+    Ok(texts::apply_quote_style(texts, &answers.options))
+}
+
+/// Asks for the answers `license` is rendered with that `answers` doesn't
+/// have yet. Its options decide which are needed.
+#[tracing::instrument(skip(answers))]
+pub fn prompt_answers(license: &Licenses, answers: &mut texts::LicenseAnswers) {
     match license {
-        Licenses::Mit => texts::generate_mit_license(),
-        Licenses::Agpl3(a) => texts::generate_agpl_license(a.clone()),
-        Licenses::Gpl3(a) => texts::generate_gpl_license(a.clone()),
-        Licenses::Lgpl3(a) => texts::generate_lgpl_license(a.clone()),
-        Licenses::Apache2 => texts::generate_apache_license(),
-        Licenses::Bsl1 => texts::generate_bsl_license(),
-        Licenses::Unlicense => texts::generate_unlicense_license(),
-        Licenses::Cddl1 => texts::generate_cddl_license(),
-        Licenses::Epl2 => texts::generate_epl_license(),
-        Licenses::Mpl2 => texts::generate_mpl_license(),
-        Licenses::Bsd3Clause(a) => texts::generate_bsd_license(a.clone()),
+        Licenses::Agpl3(_) | Licenses::Gpl3(_) | Licenses::Gpl2(_) => {
+            texts::gnu::prompt_answers(true, answers)
+        }
+        Licenses::Lgpl3(_) | Licenses::Lgpl21(_) => texts::gnu::prompt_answers(false, answers),
+        Licenses::Apache2 => texts::apache::prompt_answers(answers),
+        Licenses::Bsd3Clause(a) => texts::bsd::prompt_answers(a, answers),
+        Licenses::Cecill21 => texts::cecill::prompt_answers(answers),
+        Licenses::Epl2 => texts::epl::prompt_answers(answers),
+        Licenses::Eupl12 => texts::eupl::prompt_answers(answers),
+        Licenses::Bsl1 | Licenses::Unlicense | Licenses::Cddl1 | Licenses::Mpl2 | Licenses::Cc0 => {
+            texts::prompt_reuse_copyright(answers)
+        }
+        Licenses::Mit
+        | Licenses::Bsd2Clause
+        | Licenses::Bsd2ClausePatent
+        | Licenses::Zlib
+        | Licenses::ZeroBsd
+        | Licenses::CcBy4
+        | Licenses::Wtfpl => texts::prompt_copyright(answers),
     }
 }
+
+/// Renders `license` with the options and EUPL language set for this run
+/// and the other answers asked for. Exits if it can't be rendered.
+#[tracing::instrument]
+pub fn generate_license_text(license: &Licenses) -> texts::LicenseTexts {
+    let mut answers = texts::LicenseAnswers {
+        eupl_language: texts::eupl_language(),
+        options: texts::RenderOptions::from_settings(&license.to_string()),
+        ..texts::LicenseAnswers::default()
+    };
+    prompt_answers(license, &mut answers);
+    match render(license, &answers) {
+        Ok(texts) => texts,
+        Err(e @ texts::RenderError::Variable(_)) => {
+            ceprintln!("<bold><red>Invalid template variable</></>: {}", e);
+            process::exit(1);
+        }
+        Err(e) => {
+            ceprintln!("<bold><red>Failed to render {}</></>: {}", license, e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Uses a license text that is not built in, read verbatim from a file
+/// or, with the `network` feature, a URL. Source files get an
+/// `SPDX-License-Identifier: <expression>` header.
+#[tracing::instrument]
+pub fn custom_license_text(source: &str, expression: &str) -> Result<texts::LicenseTexts, String> {
+    let text = if source.starts_with("https://") || source.starts_with("http://") {
+        fetch_license_text(source)?
+    } else {
+        fs::read_to_string(source).map_err(|e| format!("Failed to read {source}: {e}"))?
+    };
+    if text.trim().is_empty() {
+        return Err(format!("The license text in {source} is empty"));
+    }
+    Ok(texts::LicenseTexts {
+        text,
+        comment: texts::reuse_comment_prompted(format!("{} {expression}", io::SPDX_MARKER)),
+        alt: None,
+        interactive: None,
+        notice: None,
+    })
+}
+
+#[cfg(feature = "network")]
+#[tracing::instrument]
+fn fetch_license_text(url: &str) -> Result<String, String> {
+    let mut curl = devx_cmd::Cmd::lookup_in_path("curl")
+        .ok_or("curl is needed to fetch license texts from URLs")?;
+    curl.args(["--fail", "--silent", "--show-error", "--location", url])
+        .log_cmd(None);
+    curl.read()
+        .map_err(|e| format!("Failed to fetch {url}: {e}"))
+}
+
+#[cfg(not(feature = "network"))]
+#[tracing::instrument]
+fn fetch_license_text(url: &str) -> Result<String, String> {
+    Err(format!(
+        "Fetching {url} needs the 'network' feature. Download the license text and pass its path instead."
+    ))
+}
+
+/// Parses an SPDX license expression (e.g., `MIT OR Apache-2.0`) into the
+/// licenses it references.
+///
+/// The `OR`/`AND` operators and parentheses are accepted but not
+/// evaluated, and the legacy `MIT/Apache-2.0` form used by older crates is
+/// treated as `OR`. Duplicate licenses are only returned once.
+#[tracing::instrument]
+pub fn parse_expression(expression: &str) -> Result<Vec<Licenses>, String> {
+    let mut licenses: Vec<Licenses> = Vec::new();
+    for token in expression
+        .split(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | '/'))
+        .filter(|t| !t.is_empty())
+    {
+        match token {
+            "OR" | "AND" => continue,
+            "WITH" => {
+                return Err(format!(
+                    "License exceptions ('WITH') are not supported: {expression}"
+                ));
+            }
+            id => {
+                let license = id
+                    .parse::<Licenses>()
+                    .map_err(|_| format!("Unsupported license identifier: {id}"))?;
+                if !licenses.contains(&license) {
+                    licenses.push(license);
+                }
+            }
+        }
+    }
+    if licenses.is_empty() {
+        return Err(format!("No licenses found in expression: '{expression}'"));
+    }
+    Ok(licenses)
+}
+
+static EXPRESSION_TOKEN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[^\s()]+").unwrap());
+
+/// Normalizes a license expression for use in an SPDX header, rewriting
+/// the legacy `/` separator as `OR` and the license identifiers in their
+/// canonical case (e.g., `mit` as `MIT`).
+#[tracing::instrument]
+pub fn normalize_expression(expression: &str) -> String {
+    let expression = expression
+        .split('/')
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join(" OR ");
+    EXPRESSION_TOKEN
+        .replace_all(&expression, |token: &regex::Captures| {
+            token[0]
+                .parse::<Licenses>()
+                .map_or_else(|_| token[0].to_string(), |license| license.to_string())
+        })
+        .into_owned()
+}
@@ -1,22 +1,633 @@
-use clap::Parser;
-use license_gen_bin::{cli::Cli, io, license};
+use clap::FromArgMatches;
+use clap::parser::ValueSource;
+use license_gen_bin::ceprintln;
+use license_gen_bin::cli::{self, Cli, Commands};
+use license_gen_bin::comment::{self, CommentMap};
+use license_gen_bin::exclude::Excludes;
+use license_gen_bin::summary::{OutputFormat, RunSummary};
+use license_gen_bin::{
+    answers as saved_answers, cargo, color, config, git, guess, index, io, license, modernize,
+    remember, remove, selftest, texts,
+};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process;
 
 fn main() {
-    let cli = Cli::parse();
+    let matches = cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    color::set_color(cli.color);
+    match cli.command {
+        Some(Commands::Completions { shell }) => {
+            cli::print_completions(shell);
+            return;
+        }
+        Some(Commands::Man) => {
+            if let Err(e) = cli::print_man() {
+                ceprintln!("<bold><red>Failed to render the manpage</></>: {}", e);
+                process::exit(1);
+            }
+            return;
+        }
+        _ => {}
+    }
     let Cli {
+        command,
         add_comment,
+        spdx_only,
         comment,
+        default_comment,
+        comment_map,
         source_path,
+        max_depth,
+        exclude,
+        no_ignore,
+        files_from,
+        content_match,
+        replace_match,
+        position,
+        check,
+        verify,
+        force,
+        warn_clean,
+        only_dirty,
+        append,
+        backup,
+        jobs,
+        verify_comments,
+        strip_existing_headers,
+        dry_run,
+        as_patch,
         output,
-        license,
+        output_dir,
+        license_filename,
+        format,
+        git_add,
+        write_index,
+        copyright_format,
+        year,
+        all_rights_reserved,
+        reuse,
+        notice,
+        amendment_output,
+        final_newline,
+        wrap,
+        quote_style,
+        mpl_header,
+        language,
+        template_dir,
+        mut vars,
+        title,
+        no_remember,
+        deterministic,
+        prompt_retries,
+        config: config_path,
+        save_answers,
+        answers,
+        log_answers,
         verbosity,
+        color: _,
+        from_cargo,
+        from_manifest,
+        spdx_expression,
+        license_text,
+        select,
+        license,
     } = cli;
 
-    tracing_subscriber::fmt().with_max_level(verbosity).init();
+    tracing_subscriber::fmt()
+        .with_max_level(verbosity)
+        .with_ansi(color::enabled(&std::io::stdout()))
+        .init();
+    saved_answers::set_log_answers(log_answers);
+
+    if let Some(path) = &answers
+        && let Err(e) = saved_answers::load(path)
+    {
+        ceprintln!("<bold><red>Failed to load answers</></>: {}", e);
+        process::exit(1);
+    }
+
+    saved_answers::set_deterministic(deterministic);
+    saved_answers::set_prompt_retries(prompt_retries);
+    let mut config = match config::load(config_path.as_deref(), !deterministic) {
+        Ok(config) => config,
+        Err(e) => {
+            ceprintln!("<bold><red>Failed to load config</></>: {}", e);
+            process::exit(1);
+        }
+    };
+    if let Some(year) = year {
+        config.year = Some(config::ConfigValue::String(year.to_string()));
+    } else if matches!(command, Some(Commands::New { .. }))
+        && config.year.is_none()
+        && !deterministic
+    {
+        let year = texts::CopyrightYear::current();
+        config.year = Some(config::ConfigValue::String(year.to_string()));
+    }
+    let comment = comment.or(config.comment.take()).map(|token| {
+        comment::parse_comment_token(&token).unwrap_or_else(|e| {
+            ceprintln!("<bold><red>Invalid comment in config</></>: {}", e);
+            process::exit(1);
+        })
+    });
+    for token in comment.iter().chain(&default_comment) {
+        if !comment::is_known_comment(token) {
+            ceprintln!(
+                "<bold><yellow>Unusual comment token</></>: `{}` does not start with a known line comment marker (e.g., `//`, `#`, or `--`)",
+                token,
+            );
+        }
+    }
+    let output_defaulted = matches.value_source("output") == Some(ValueSource::DefaultValue);
+    // '--license-filename' names the license file unless '--output' did.
+    let license_filename = license_filename.filter(|_| output_defaulted || output_dir.is_some());
+    let output = if output_defaulted && license_filename.is_none() {
+        config.output.take().unwrap_or(output)
+    } else {
+        output
+    };
+    let (output, amendment_output) = match output_dir {
+        Some(dir) => {
+            if !dry_run
+                && as_patch.is_none()
+                && let Err(e) = std::fs::create_dir_all(&dir)
+            {
+                ceprintln!(
+                    "<bold><red>Failed to create output directory {}</></>: {}",
+                    dir.display(),
+                    e
+                );
+                process::exit(1);
+            }
+            (
+                dir.join(io::OUTPUT_DIR_LICENSE),
+                Some(dir.join(io::OUTPUT_DIR_AMENDMENT)),
+            )
+        }
+        None => (output, amendment_output),
+    };
+    config::install(config);
+
+    if !no_remember
+        && !deterministic
+        && let Err(e) = remember::load()
+    {
+        ceprintln!("<bold><yellow>Ignoring remembered values</></>: {}", e);
+    }
+
+    if let Some(manifest) = &from_manifest {
+        match cargo::read_package(manifest) {
+            Ok(package) => cargo::set_package(package),
+            Err(e) => {
+                ceprintln!(
+                    "<bold><red>Failed to read package from Cargo.toml</></>: {}",
+                    e
+                );
+                process::exit(1);
+            }
+        }
+    }
+
+    if let Some(format) = copyright_format {
+        texts::set_copyright_format(format);
+    }
+    texts::set_all_rights_reserved(all_rights_reserved);
+    texts::set_reuse(reuse);
+    texts::set_notice(notice);
+    if let Some(header) = mpl_header {
+        texts::set_mpl_header(header);
+    }
+    if let Some(language) = language {
+        texts::set_eupl_language(language);
+    }
+    texts::set_quote_style(quote_style);
+    if let Some(dir) = template_dir {
+        texts::set_template_dir(dir);
+    }
+    if let Some(title) = title {
+        vars.push(texts::TemplateVar {
+            key: texts::TITLE_VAR.to_string(),
+            value: title,
+        });
+    }
+    texts::set_template_vars(vars);
+
+    if format == OutputFormat::Json && io::is_stdout(&output) {
+        ceprintln!(
+            "<bold><red>'<italics>--format json</>' needs stdout for the summary</></>: write the license to a file instead of '<italics>--output -</>'."
+        );
+        process::exit(1);
+    }
+    if format == OutputFormat::Json && dry_run {
+        ceprintln!(
+            "<bold><red>'<italics>--format json</>' can't be used with '<italics>--dry-run</>'</></>: the summary would describe files that weren't written."
+        );
+        process::exit(1);
+    }
+
+    // Read before any prompt, which would otherwise consume a list piped
+    // into stdin.
+    let files = files_from.map(|path| {
+        io::read_file_list(&path).unwrap_or_else(|e| {
+            ceprintln!(
+                "<bold><red>Failed to read the list of files {}</></>: {}",
+                path.display(),
+                e
+            );
+            process::exit(1);
+        })
+    });
+
+    let comment_map = comment_map.map(|path| {
+        CommentMap::load(&path).unwrap_or_else(|e| {
+            ceprintln!("<bold><red>Failed to load comment map</></>: {}", e);
+            process::exit(1);
+        })
+    });
+
+    let exclude = Excludes::new(&exclude).unwrap_or_else(|e| {
+        ceprintln!("<bold><red>Invalid exclude pattern</></>: {}", e);
+        process::exit(1);
+    });
+
+    let header = io::HeaderOptions {
+        add_comment: add_comment || spdx_only,
+        spdx_only,
+        comment,
+        default_comment,
+        comment_map,
+        source_path,
+        max_depth,
+        exclude,
+        no_ignore,
+        files,
+        license_file: output.clone(),
+        force,
+        warn_clean,
+        only_dirty,
+        backup,
+        verify_comments,
+        strip_existing: strip_existing_headers,
+        content_match,
+        replace_match,
+        position,
+        dry_run: dry_run || as_patch.is_some(),
+        json: format == OutputFormat::Json,
+        markdown: format == OutputFormat::Md,
+        append,
+        quiet: verbosity.is_silent() || format == OutputFormat::Json,
+        amendment_output,
+        final_newline,
+        wrap: wrap.map(usize::from),
+        reuse,
+        progress: !verbosity.is_silent(),
+        jobs: jobs.map(usize::from),
+    };
+    if as_patch.is_some() {
+        io::start_patch();
+    }
+
+    let mut failed = false;
+    match command {
+        Some(Commands::List) => license::print_licenses(),
+        Some(Commands::Modernize) => {
+            if !modernize::modernize(&header) {
+                process::exit(1);
+            }
+        }
+        Some(Commands::RemoveHeader) => {
+            if !remove::remove_headers(&header) {
+                process::exit(1);
+            }
+        }
+        Some(Commands::New { license }) => {
+            let source_path = match matches.value_source("source_path") {
+                Some(ValueSource::DefaultValue) | None => PathBuf::from("."),
+                _ => header.source_path.clone(),
+            };
+            let output = PathBuf::from(
+                license_filename.map_or(io::OUTPUT_DIR_LICENSE, |convention| {
+                    convention.file_name(Some(&license))
+                }),
+            );
+            let header = io::HeaderOptions {
+                add_comment: true,
+                source_path,
+                license_file: output.clone(),
+                ..header.clone()
+            };
+            let manifest = Path::new("Cargo.toml");
+            if manifest.is_file() {
+                match cargo::read_package(manifest) {
+                    Ok(package) => cargo::set_package(package),
+                    Err(e) => tracing::debug!("Not using Cargo.toml for defaults: {e}"),
+                }
+            }
+            let Some(summary) = run(&header, output, None, None, Some(license), false, None) else {
+                process::exit(1);
+            };
+            summary.print_files(header.dry_run);
+            failed = !summary.failed.is_empty();
+        }
+        Some(Commands::Guess { path }) => {
+            if !guess::run(&path) {
+                process::exit(1);
+            }
+        }
+        Some(Commands::SelfTest {
+            only: Some(license),
+        }) => {
+            if let Err(e) = selftest::run_one(&license) {
+                ceprintln!("<bold><red>{} failed to render</></>: {}", license, e);
+                process::exit(1);
+            }
+        }
+        // Handled before any config is loaded.
+        Some(Commands::Completions { .. } | Commands::Man) => {}
+        Some(Commands::SelfTest { only: None }) => {
+            if !selftest::run_all() {
+                process::exit(1);
+            }
+        }
+        None => {
+            let spdx_expression =
+                spdx_expression.or_else(|| select.then(license::select_expression));
+            let license = match license {
+                None if spdx_expression.is_none() && from_cargo.is_none() => {
+                    if !std::io::stdin().is_terminal() {
+                        ceprintln!(
+                            "<bold><red>No license given</></>: pass an SPDX identifier (see '<italics>license list</>'), or run in a terminal to pick one."
+                        );
+                        process::exit(2);
+                    }
+                    Some(license::pick_license())
+                }
+                license => license,
+            };
+            let (output, header) = match license_filename {
+                Some(convention) => {
+                    let single = match license_text {
+                        Some(_) => None,
+                        None => single_license(
+                            license.as_ref(),
+                            spdx_expression.as_deref(),
+                            from_cargo.as_deref(),
+                        ),
+                    };
+                    let output = output.with_file_name(convention.file_name(single.as_ref()));
+                    let header = io::HeaderOptions {
+                        license_file: output.clone(),
+                        ..header.clone()
+                    };
+                    (output, header)
+                }
+                None => (output, header.clone()),
+            };
+            let summary = match license_text {
+                Some(source) => run_custom(
+                    &header,
+                    output.clone(),
+                    &source,
+                    &spdx_expression.unwrap_or_default(),
+                    check,
+                    verify.as_deref(),
+                ),
+                None => run(
+                    &header,
+                    output.clone(),
+                    from_cargo,
+                    spdx_expression,
+                    license,
+                    check,
+                    verify.as_deref(),
+                ),
+            };
+            let Some(mut summary) = summary else {
+                process::exit(1);
+            };
+            if write_index && !header.dry_run {
+                add_index(&mut summary, &output);
+            }
+            if git_add && !header.dry_run {
+                stage(&summary.written());
+            }
+            if format == OutputFormat::Json
+                && let Err(e) = summary.print_json()
+            {
+                ceprintln!("<bold><red>Failed to print summary</></>: {}", e);
+                process::exit(1);
+            }
+            failed = !summary.failed.is_empty();
+        }
+    }
+
+    if let Some(path) = &as_patch
+        && !io::write_patch(path, &header)
+    {
+        process::exit(1);
+    }
+
+    if let Err(e) = remember::save() {
+        ceprintln!(
+            "<bold><yellow>Failed to remember this run's answers</></>: {}",
+            e
+        );
+    }
+
+    if let Some(path) = &save_answers
+        && let Err(e) = saved_answers::save(path)
+    {
+        ceprintln!("<bold><red>Failed to save answers</></>: {}", e);
+        process::exit(1);
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+/// The one license a run generates, if it generates a single built-in
+/// license, for '--license-filename' to name its file after.
+fn single_license(
+    license: Option<&license::Licenses>,
+    spdx_expression: Option<&str>,
+    from_cargo: Option<&Path>,
+) -> Option<license::Licenses> {
+    let expression = match (from_cargo, spdx_expression) {
+        (Some(manifest), _) => cargo::read_license_expression(manifest).ok()?,
+        (None, Some(expression)) => expression.to_string(),
+        (None, None) => return license.cloned(),
+    };
+    match license::parse_expression(&expression).ok()?.as_slice() {
+        [license] => Some(license.clone()),
+        _ => None,
+    }
+}
+
+fn run(
+    header: &io::HeaderOptions,
+    output: PathBuf,
+    from_cargo: Option<PathBuf>,
+    spdx_expression: Option<String>,
+    license: Option<license::Licenses>,
+    check: bool,
+    verify: Option<&Path>,
+) -> Option<RunSummary> {
+    if let Some(manifest) = from_cargo {
+        let expression = match cargo::read_license_expression(&manifest) {
+            Ok(expression) => expression,
+            Err(e) => {
+                ceprintln!(
+                    "<bold><red>Failed to read license from Cargo.toml</></>: {}",
+                    e
+                );
+                process::exit(1);
+            }
+        };
+        return run_expression(header, output, &expression, check, verify);
+    }
+
+    if let Some(expression) = spdx_expression {
+        return run_expression(header, output, &expression, check, verify);
+    }
 
     // Here you would typically call a function to handle the CLI arguments,
     // such as generating the license text based on the provided options.
     // For example:
-    let text = license::generate_license_text(&license);
-    io::output(&text, add_comment, &comment, source_path, output);
+    if let Some(license) = license {
+        if check {
+            check_headers(&license.to_string(), header);
+        }
+        let text = license::generate_license_text(&license);
+        if let Some(path) = verify {
+            verify_licenses(header, &[(path.to_path_buf(), license.to_string(), text)]);
+        }
+        return io::output(&license.to_string(), &text, header, output);
+    }
+    None
+}
+
+fn run_expression(
+    header: &io::HeaderOptions,
+    output: PathBuf,
+    expression: &str,
+    check: bool,
+    verify: Option<&Path>,
+) -> Option<RunSummary> {
+    let licenses = match license::parse_expression(expression) {
+        Ok(licenses) => licenses,
+        Err(e) => {
+            ceprintln!("<bold><red>Failed to parse license expression</></>: {}", e);
+            process::exit(1);
+        }
+    };
+    let expression = license::normalize_expression(expression);
+    if check {
+        check_headers(&expression, header);
+    }
+    if let [license] = licenses.as_slice() {
+        let text = license::generate_license_text(license);
+        if let Some(path) = verify {
+            verify_licenses(header, &[(path.to_path_buf(), license.to_string(), text)]);
+        }
+        let mut summary = io::output(&license.to_string(), &text, header, output)?;
+        summary.expression = expression;
+        return Some(summary);
+    }
+    let texts: Vec<_> = licenses
+        .into_iter()
+        .map(|l| {
+            let text = license::generate_license_text(&l);
+            (l, text)
+        })
+        .collect();
+    if let Some(path) = verify {
+        let files: Vec<_> = texts
+            .iter()
+            .map(|(l, text)| {
+                (
+                    path.with_file_name(l.file_name()),
+                    l.to_string(),
+                    text.clone(),
+                )
+            })
+            .collect();
+        verify_licenses(header, &files);
+    }
+    io::output_all(&expression, &texts, header, &output)
+}
+
+fn run_custom(
+    header: &io::HeaderOptions,
+    output: PathBuf,
+    source: &str,
+    expression: &str,
+    check: bool,
+    verify: Option<&Path>,
+) -> Option<RunSummary> {
+    if check {
+        check_headers(expression, header);
+    }
+    let text = match license::custom_license_text(source, expression) {
+        Ok(text) => text,
+        Err(e) => {
+            ceprintln!("<bold><red>Failed to read license text</></>: {}", e);
+            process::exit(1);
+        }
+    };
+    if let Some(path) = verify {
+        verify_licenses(
+            header,
+            &[(path.to_path_buf(), expression.to_string(), text)],
+        );
+    }
+    io::output(expression, &text, header, output)
+}
+
+fn add_index(summary: &mut RunSummary, output: &Path) {
+    let licenses: Vec<_> = summary
+        .licenses
+        .iter()
+        .map(|l| (l.id.clone(), l.output.clone()))
+        .collect();
+    match index::write_index(&summary.expression, &licenses, output) {
+        Ok(path) => summary.index = Some(path),
+        Err(e) => {
+            ceprintln!("<bold><red>Failed to write license index</></>: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn stage(written: &[PathBuf]) {
+    if !git::is_repo() {
+        ceprintln!(
+            "<bold><yellow>Not in a git repository</></>: skipping '<italics>--git-add</>'."
+        );
+        return;
+    }
+    if let Err(e) = git::stage(written) {
+        ceprintln!("<bold><red>Failed to stage files with git</></>: {}", e);
+        process::exit(1);
+    }
+}
+
+fn verify_licenses(
+    header: &io::HeaderOptions,
+    files: &[(PathBuf, String, texts::LicenseTexts)],
+) -> ! {
+    let mut matches = true;
+    for (path, id, text) in files {
+        matches &= io::verify_license(id, text, path, header);
+    }
+    process::exit(if matches { 0 } else { 1 });
+}
+
+fn check_headers(expected: &str, header: &io::HeaderOptions) -> ! {
+    if io::check_headers(expected, header) {
+        process::exit(0);
+    }
+    process::exit(1);
 }
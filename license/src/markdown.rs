@@ -0,0 +1,82 @@
+use crate::license::Licenses;
+use crate::wrap::{is_underline, starts_block};
+use std::str::FromStr;
+
+/// The heading of a license rendered as Markdown: the description of a
+/// built-in license, or the SPDX expression itself.
+#[tracing::instrument]
+pub fn title(id: &str) -> String {
+    Licenses::from_str(id).map_or_else(|_| id.to_string(), |license| license.description())
+}
+
+/// Renders the license `text` as Markdown under a `# title` heading.
+/// Paragraphs are separated by blank lines, and copyright lines and list
+/// items start a paragraph of their own. Underlined lines and lines alone
+/// in capitals become `##` headings, and a first line repeating the title
+/// is dropped.
+#[tracing::instrument(skip(text))]
+pub fn render(text: &str, title: &str) -> String {
+    let mut blocks = vec![format!("# {title}")];
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut lines = text.lines().map(str::trim).peekable();
+    if lines
+        .peek()
+        .is_some_and(|first| first.eq_ignore_ascii_case(title))
+    {
+        lines.next();
+    }
+    while let Some(line) = lines.next() {
+        let underlined = lines.peek().is_some_and(|next| is_underline(next));
+        if line.is_empty() || underlined || starts_block(line) {
+            flush(&mut paragraph, &mut blocks);
+        }
+        if underlined {
+            lines.next();
+            blocks.push(format!("## {}", escape(line)));
+        } else if !line.is_empty() && !is_underline(line) {
+            paragraph.push(escape(line));
+        }
+    }
+    flush(&mut paragraph, &mut blocks);
+    let mut out = blocks.join("\n\n");
+    out.push('\n');
+    out
+}
+
+/// Moves `paragraph` to `blocks`, as a heading if it is a single line in
+/// capitals.
+#[tracing::instrument(skip(blocks))]
+fn flush(paragraph: &mut Vec<String>, blocks: &mut Vec<String>) {
+    let block = match paragraph.as_slice() {
+        [] => return,
+        [line] if is_capitalized(line) => format!("## {line}"),
+        lines => lines.join("\n"),
+    };
+    blocks.push(block);
+    paragraph.clear();
+}
+
+/// Whether `line` is a heading in capitals, e.g. `TERMS AND CONDITIONS`.
+#[tracing::instrument]
+fn is_capitalized(line: &str) -> bool {
+    line.len() <= 60
+        && line.chars().any(char::is_alphabetic)
+        && !line.chars().any(char::is_lowercase)
+        && !line.ends_with(['.', ',', ':', ';'])
+}
+
+/// `line` with the characters Markdown would read as formatting escaped.
+#[tracing::instrument]
+fn escape(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    if line.starts_with(['#', '>', '+', '|']) {
+        out.push('\\');
+    }
+    for c in line.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '<' | '[' | ']') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
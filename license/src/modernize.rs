@@ -0,0 +1,229 @@
+use crate::io::{self, HeaderOptions, SPDX_MARKER};
+use crate::{ceprintln, cprintln};
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// Phrases found in legacy license headers and the SPDX identifier they
+/// map to. More specific phrasings come first so that, e.g., the LGPL is
+/// not mistaken for the GPL.
+pub const LEGACY_PATTERNS: &[(&str, &str)] = &[
+    (
+        r"GNU Lesser General Public License.*version 3.*any later version",
+        "LGPL-3.0-or-later",
+    ),
+    (
+        r"GNU Lesser General Public License.*version 3",
+        "LGPL-3.0-only",
+    ),
+    (
+        r"GNU Affero General Public License.*version 3.*any later version",
+        "AGPL-3.0-or-later",
+    ),
+    (
+        r"GNU Affero General Public License.*version 3",
+        "AGPL-3.0-only",
+    ),
+    (
+        r"GNU General Public License.*version 3.*any later version",
+        "GPL-3.0-or-later",
+    ),
+    (r"GNU General Public License.*version 3", "GPL-3.0-only"),
+    (
+        r"GNU Lesser General Public License.*version 2\.1.*any later version",
+        "LGPL-2.1-or-later",
+    ),
+    (
+        r"GNU Lesser General Public License.*version 2\.1",
+        "LGPL-2.1-only",
+    ),
+    (
+        r"GNU General Public License.*version 2.*any later version",
+        "GPL-2.0-or-later",
+    ),
+    (r"GNU General Public License.*version 2", "GPL-2.0-only"),
+    (
+        r"Licensed under the Apache License,? Version 2\.0",
+        "Apache-2.0",
+    ),
+    (r"Mozilla Public License,? v(ersion|\.) ?2\.0", "MPL-2.0"),
+    (
+        r"Eclipse Public License,? (- )?v(ersion|\.)? ?2\.0",
+        "EPL-2.0",
+    ),
+    (r"Common Development and Distribution License", "CDDL-1.0"),
+    (r"Boost Software License", "BSL-1.0"),
+    (
+        r"free and unencumbered software released into the public domain",
+        "Unlicense",
+    ),
+    (
+        r"Redistribution and use in source and binary forms.*Neither the name",
+        "BSD-3-Clause",
+    ),
+    (
+        r"hereby grants to those receiving rights under this license a perpetual",
+        "BSD-2-Clause-Patent",
+    ),
+    (
+        r"Redistribution and use in source and binary forms",
+        "BSD-2-Clause",
+    ),
+    (
+        r"This software is provided 'as-is', without any express or implied warranty",
+        "Zlib",
+    ),
+    (
+        r"distribute this software for any purpose with or without fee is hereby granted\.",
+        "0BSD",
+    ),
+    (r"DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE", "WTFPL"),
+    (r"EUROPEAN UNION PUBLIC LICENCE v\. 1\.2", "EUPL-1.2"),
+    (r"Permission is hereby granted, free of charge", "MIT"),
+    (r"Licensed under the MIT license", "MIT"),
+];
+
+static LEGACY_REGEXES: LazyLock<Vec<(Regex, &'static str)>> = LazyLock::new(|| {
+    LEGACY_PATTERNS
+        .iter()
+        .map(|(pattern, id)| {
+            let regex = Regex::new(&format!("(?i){pattern}"))
+                .unwrap_or_else(|e| panic!("invalid legacy pattern '{pattern}': {e}"));
+            (regex, *id)
+        })
+        .collect()
+});
+
+static LICENSE_WORDS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)licen[cs]e|copyright").unwrap());
+
+static COPYRIGHT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^(copyright|\(c\)|©)").unwrap());
+
+/// Maps the text of a legacy license header to an SPDX identifier.
+#[tracing::instrument]
+pub fn detect_legacy_license(text: &str) -> Option<&'static str> {
+    LEGACY_REGEXES
+        .iter()
+        .find(|(regex, _)| regex.is_match(text))
+        .map(|(_, id)| *id)
+}
+
+/// What happened to a file during [`modernize`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Outcome {
+    Modernized(&'static str),
+    Unmapped,
+    Unchanged,
+}
+
+/// Replaces legacy freeform license headers under the source path with
+/// SPDX headers, keeping any copyright lines. Files whose header mentions
+/// a license but cannot be mapped are reported for manual review. Returns
+/// `false` if any file could not be read or written.
+#[tracing::instrument]
+pub fn modernize(header: &HeaderOptions) -> bool {
+    let source_path = &header.source_path;
+    let mut modernized = 0usize;
+    let mut unmapped: Vec<PathBuf> = Vec::new();
+    let mut failed = false;
+    let mut visit = |path: &Path| {
+        let Some(comment) = io::resolve_comment(header, path) else {
+            io::warn_unknown_comment(path);
+            return true;
+        };
+        match modernize_file(path, comment, header.dry_run) {
+            Ok(Outcome::Modernized(id)) => {
+                if !header.dry_run {
+                    cprintln!("<green>Modernized</> {} <dim>({})</>", path.display(), id);
+                }
+                modernized += 1;
+            }
+            Ok(Outcome::Unmapped) => unmapped.push(path.to_path_buf()),
+            Ok(Outcome::Unchanged) => {}
+            Err(e) => {
+                ceprintln!(
+                    "<bold><red>Failed to modernize file {}</></>: {}",
+                    path.display(),
+                    e,
+                );
+                failed = true;
+            }
+        }
+        true
+    };
+    match (source_path.is_dir(), source_path.is_file()) {
+        (true, _) => {
+            if !io::iterate_dir(source_path, header, &mut visit)
+                .failed
+                .is_empty()
+            {
+                failed = true;
+            }
+        }
+        (_, true) => {
+            visit(source_path);
+        }
+        _ => {
+            ceprintln!(
+                "<bold><red>Source path does not exist</></>: {}",
+                source_path.display()
+            );
+            return false;
+        }
+    }
+
+    let verb = if header.dry_run {
+        "Would modernize"
+    } else {
+        "Modernized"
+    };
+    cprintln!("<bold><green>{} {} file(s)</></>", verb, modernized);
+    if !unmapped.is_empty() {
+        ceprintln!(
+            "<bold><yellow>Could not map the license header of {} file(s). Please review them manually:</></>",
+            unmapped.len()
+        );
+        for path in &unmapped {
+            ceprintln!("  {}", path.display());
+        }
+    }
+    !failed
+}
+
+#[tracing::instrument]
+fn modernize_file(path: &Path, comment: &str, dry_run: bool) -> std::io::Result<Outcome> {
+    let src = fs::read_to_string(path)?;
+    let lines: Vec<&str> = src.lines().collect();
+    let start = usize::from(lines.first().is_some_and(|l| l.starts_with("#!")));
+    let end = lines[start..]
+        .iter()
+        .position(|line| !io::is_header_line(line, comment))
+        .map_or(lines.len(), |offset| start + offset);
+    let block: Vec<&str> = lines[start..end]
+        .iter()
+        .map(|line| line[comment.len()..].trim())
+        .collect();
+    let text = block.join(" ");
+    if text.contains(SPDX_MARKER) || !LICENSE_WORDS.is_match(&text) {
+        return Ok(Outcome::Unchanged);
+    }
+    let Some(id) = detect_legacy_license(&text) else {
+        return Ok(Outcome::Unmapped);
+    };
+
+    let mut contents = String::with_capacity(src.len());
+    for line in &lines[..start] {
+        contents.push_str(&format!("{line}\n"));
+    }
+    contents.push_str(&format!("{comment} {SPDX_MARKER} {id}\n"));
+    for line in block.iter().filter(|line| COPYRIGHT.is_match(line)) {
+        contents.push_str(&format!("{comment} {line}\n"));
+    }
+    for line in &lines[end..] {
+        contents.push_str(&format!("{line}\n"));
+    }
+    io::rewrite_or_diff(path, &src, &contents, dry_run)?;
+    Ok(Outcome::Modernized(id))
+}
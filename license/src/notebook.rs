@@ -0,0 +1,142 @@
+use crate::io::{HeaderOptions, SPDX_MARKER, rewrite_or_diff};
+use serde::Serialize;
+use serde_json::ser::PrettyFormatter;
+use serde_json::{Map, Value, json};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// File extensions of Jupyter notebooks, which get the license notice as
+/// a leading Markdown cell instead of a comment header.
+pub const NOTEBOOK_EXTENSIONS: &[&str] = &["ipynb"];
+
+/// The id of the license cell, for notebooks whose cells have ids.
+pub const LICENSE_CELL_ID: &str = "license-header";
+
+/// The outcome of adding the license cell to a notebook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotebookOutcome {
+    Added,
+    Replaced,
+    /// The first cell already has an SPDX identifier.
+    Skipped,
+    NoMatch,
+    /// The notebook could not be parsed, with the reason.
+    Malformed(String),
+}
+
+/// Whether `path` is a Jupyter notebook.
+#[tracing::instrument]
+pub fn is_notebook(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| NOTEBOOK_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// Inserts a Markdown cell with `comment_block` at the start of the
+/// notebook at `path`, or replaces the first cell with '--force' if it
+/// already has an SPDX identifier. The rest of the notebook is written
+/// back with its own indentation and key order.
+#[tracing::instrument(skip(comment_block))]
+pub fn add_license_cell(
+    path: &Path,
+    comment_block: &str,
+    header: &HeaderOptions,
+) -> io::Result<NotebookOutcome> {
+    let src = fs::read_to_string(path)?;
+    if header
+        .content_match
+        .as_ref()
+        .is_some_and(|pattern| !pattern.is_match(&src))
+    {
+        return Ok(NotebookOutcome::NoMatch);
+    }
+    let mut notebook: Value = match serde_json::from_str(&src) {
+        Ok(notebook) => notebook,
+        Err(e) => return Ok(NotebookOutcome::Malformed(e.to_string())),
+    };
+    let Some(cells) = notebook.get_mut("cells").and_then(Value::as_array_mut) else {
+        return Ok(NotebookOutcome::Malformed(
+            "no 'cells' list at the top level".to_string(),
+        ));
+    };
+    let with_ids = cells.iter().any(|cell| cell.get("id").is_some());
+    let cell = license_cell(comment_block, with_ids);
+    let outcome = if cells.first().is_some_and(has_spdx_marker) {
+        if !header.force {
+            return Ok(NotebookOutcome::Skipped);
+        }
+        cells[0] = cell;
+        NotebookOutcome::Replaced
+    } else {
+        cells.insert(0, cell);
+        NotebookOutcome::Added
+    };
+    let contents = to_notebook_json(&notebook, indent(&src))?;
+    rewrite_or_diff(path, &src, &contents, header.dry_run)?;
+    Ok(outcome)
+}
+
+/// A Markdown cell holding the lines of `comment_block`.
+#[tracing::instrument(skip(comment_block))]
+fn license_cell(comment_block: &str, with_id: bool) -> Value {
+    let lines: Vec<&str> = comment_block.lines().collect();
+    let source: Vec<String> = lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            if index + 1 < lines.len() {
+                format!("{line}\n")
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    let mut cell = Map::new();
+    cell.insert("cell_type".to_string(), json!("markdown"));
+    if with_id {
+        cell.insert("id".to_string(), json!(LICENSE_CELL_ID));
+    }
+    cell.insert("metadata".to_string(), json!({}));
+    cell.insert("source".to_string(), json!(source));
+    Value::Object(cell)
+}
+
+/// Whether the source of `cell`, a string or a list of lines, has an
+/// SPDX identifier.
+#[tracing::instrument]
+fn has_spdx_marker(cell: &Value) -> bool {
+    match cell.get("source") {
+        Some(Value::String(source)) => source.contains(SPDX_MARKER),
+        Some(Value::Array(lines)) => lines
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|line| line.contains(SPDX_MARKER)),
+        _ => false,
+    }
+}
+
+/// The indentation of the notebook's JSON, read from its second line.
+/// Jupyter uses one space.
+#[tracing::instrument(skip(src))]
+fn indent(src: &str) -> String {
+    src.lines()
+        .nth(1)
+        .map(|line| line[..line.len() - line.trim_start().len()].to_string())
+        .filter(|indent| !indent.is_empty())
+        .unwrap_or_else(|| " ".to_string())
+}
+
+/// Serializes `notebook` the way Jupyter does, pretty-printed with
+/// `indent` and ending with a newline.
+#[tracing::instrument(skip(notebook))]
+fn to_notebook_json(notebook: &Value, indent: String) -> io::Result<String> {
+    let mut out = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(
+        &mut out,
+        PrettyFormatter::with_indent(indent.as_bytes()),
+    );
+    notebook.serialize(&mut serializer)?;
+    out.push(b'\n');
+    String::from_utf8(out).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
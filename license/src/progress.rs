@@ -0,0 +1,60 @@
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// How many status lines a run without a terminal prints at most.
+const LOG_STEPS: usize = 10;
+
+/// Runs with fewer files than this print no status lines without a
+/// terminal, since they finish quickly anyway.
+const LOG_MIN_FILES: usize = 100;
+
+/// Reports how many of a known number of files have been processed: a
+/// progress bar when stderr is a terminal, otherwise a status line every
+/// tenth of the way.
+#[derive(Debug)]
+pub struct Progress {
+    bar: Option<ProgressBar>,
+    total: usize,
+    done: AtomicUsize,
+    log: bool,
+}
+
+impl Progress {
+    /// Starts reporting progress over `total` files, unless `enabled` is
+    /// `false`.
+    #[tracing::instrument]
+    pub fn new(total: usize, enabled: bool) -> Self {
+        let bar = (enabled && io::stderr().is_terminal()).then(|| {
+            let style = ProgressStyle::with_template("{bar:40.cyan/blue} {pos}/{len} files")
+                .unwrap()
+                .progress_chars("=> ");
+            ProgressBar::new(total as u64).with_style(style)
+        });
+        Progress {
+            log: enabled && bar.is_none() && total >= LOG_MIN_FILES,
+            bar,
+            total,
+            done: AtomicUsize::new(0),
+        }
+    }
+
+    /// Records that one more file was processed.
+    #[tracing::instrument]
+    pub fn inc(&self) {
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(bar) = &self.bar {
+            bar.inc(1);
+        } else if self.log && (done.is_multiple_of(self.total / LOG_STEPS) || done == self.total) {
+            eprintln!("Processed {done}/{} files", self.total);
+        }
+    }
+
+    /// Removes the progress bar.
+    #[tracing::instrument]
+    pub fn finish(&self) {
+        if let Some(bar) = &self.bar {
+            bar.finish_and_clear();
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::config;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+
+/// Prompts whose answers are remembered between runs, and the key they
+/// are stored under. Only non-sensitive convenience values belong here.
+pub const REMEMBERED_PROMPTS: &[(&str, &str)] = &[
+    ("Enter the copyright year", "year"),
+    ("Enter the full name of the copyright holder", "name"),
+    ("Enter the name of the organization", "organization"),
+];
+
+#[derive(Debug, Default)]
+struct State {
+    values: BTreeMap<String, String>,
+    changed: bool,
+}
+
+/// `None` until [`load`] is called, so nothing is remembered with
+/// '--no-remember'.
+static STATE: LazyLock<Mutex<Option<State>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Where the last-used values are stored:
+/// `$XDG_CONFIG_HOME/license-gen/last.toml`, falling back to
+/// `~/.config/license-gen/last.toml`.
+#[tracing::instrument]
+pub fn state_path() -> Option<PathBuf> {
+    Some(config::config_dir()?.join("last.toml"))
+}
+
+#[tracing::instrument]
+fn key_for(prompt: &str) -> Option<&'static str> {
+    REMEMBERED_PROMPTS
+        .iter()
+        .find(|(p, _)| *p == prompt)
+        .map(|(_, key)| *key)
+}
+
+/// Loads the values remembered from the previous run and starts
+/// remembering the answers given in this one. A missing state file is not
+/// an error.
+#[tracing::instrument]
+pub fn load() -> Result<(), String> {
+    let mut state = State::default();
+    let result = match state_path() {
+        Some(path) if path.exists() => fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))
+            .and_then(|contents| {
+                toml::from_str(&contents)
+                    .map_err(|e| format!("Failed to parse {}: {e}", path.display()))
+            })
+            .map(|values| state.values = values),
+        _ => Ok(()),
+    };
+    *STATE.lock().unwrap() = Some(state);
+    result
+}
+
+/// The value given to `prompt` in the previous run, if it is remembered.
+#[tracing::instrument]
+pub fn last(prompt: &str) -> Option<String> {
+    let key = key_for(prompt)?;
+    STATE.lock().unwrap().as_ref()?.values.get(key).cloned()
+}
+
+/// Remembers the answer given to `prompt` for the next run.
+#[tracing::instrument(skip(value))]
+pub fn update(prompt: &str, value: &str) {
+    let Some(key) = key_for(prompt) else {
+        return;
+    };
+    if let Some(state) = STATE.lock().unwrap().as_mut()
+        && state.values.get(key).is_none_or(|v| v != value)
+    {
+        state.values.insert(key.to_string(), value.to_string());
+        state.changed = true;
+    }
+}
+
+/// Writes the remembered values to [`state_path`] if any changed.
+#[tracing::instrument]
+pub fn save() -> Result<(), String> {
+    let guard = STATE.lock().unwrap();
+    let Some(state) = guard.as_ref().filter(|s| s.changed) else {
+        return Ok(());
+    };
+    let Some(path) = state_path() else {
+        return Err("Could not determine the config directory".to_string());
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    }
+    let contents = toml::to_string(&state.values)
+        .map_err(|e| format!("Failed to serialize remembered values: {e}"))?;
+    fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
@@ -0,0 +1,101 @@
+use crate::io::{self, HeaderOptions, SPDX_MARKER};
+use crate::{ceprintln, cprintln};
+use std::fs;
+use std::path::Path;
+
+/// Removes the SPDX header block at the top of every file under the source
+/// path, reversing '--add-comment'. Only a block of comment lines that
+/// contains an SPDX identifier is removed, along with the blank line after
+/// it. Returns `false` if any file could not be read or written.
+#[tracing::instrument]
+pub fn remove_headers(header: &HeaderOptions) -> bool {
+    let source_path = &header.source_path;
+    let mut removed = 0usize;
+    let mut failed = false;
+    let mut visit = |path: &Path| {
+        let Some(comment) = io::resolve_comment(header, path) else {
+            io::warn_unknown_comment(path);
+            return true;
+        };
+        match remove_header(path, comment, header.dry_run) {
+            Ok(true) => {
+                if !header.dry_run {
+                    cprintln!("<green>Removed header from</> {}", path.display());
+                }
+                removed += 1;
+            }
+            Ok(false) => {}
+            Err(e) => {
+                ceprintln!(
+                    "<bold><red>Failed to remove header from {}</></>: {}",
+                    path.display(),
+                    e,
+                );
+                failed = true;
+            }
+        }
+        true
+    };
+    match (source_path.is_dir(), source_path.is_file()) {
+        (true, _) => {
+            if !io::iterate_dir(source_path, header, &mut visit)
+                .failed
+                .is_empty()
+            {
+                failed = true;
+            }
+        }
+        (_, true) => {
+            visit(source_path);
+        }
+        _ => {
+            ceprintln!(
+                "<bold><red>Source path does not exist</></>: {}",
+                source_path.display()
+            );
+            return false;
+        }
+    }
+
+    let verb = if header.dry_run {
+        "Would remove"
+    } else {
+        "Removed"
+    };
+    cprintln!(
+        "<bold><green>{} license headers from {} file(s)</></>",
+        verb,
+        removed
+    );
+    !failed
+}
+
+/// Removes the header from a single file. Returns whether there was one.
+#[tracing::instrument]
+fn remove_header(path: &Path, comment: &str, dry_run: bool) -> std::io::Result<bool> {
+    let src = fs::read_to_string(path)?;
+    let lines: Vec<&str> = src.lines().collect();
+    let start = usize::from(lines.first().is_some_and(|l| l.starts_with("#!")));
+    let end = lines[start..]
+        .iter()
+        .position(|line| !io::is_header_line(line, comment))
+        .map_or(lines.len(), |offset| start + offset);
+    if !lines[start..end]
+        .iter()
+        .any(|line| line.contains(SPDX_MARKER))
+    {
+        return Ok(false);
+    }
+    let end = if lines.get(end).is_some_and(|line| line.trim().is_empty()) {
+        end + 1
+    } else {
+        end
+    };
+
+    let mut contents = String::with_capacity(src.len());
+    for line in lines[..start].iter().chain(&lines[end..]) {
+        contents.push_str(&format!("{line}\n"));
+    }
+    io::rewrite_or_diff(path, &src, &contents, dry_run)?;
+    Ok(true)
+}
@@ -0,0 +1,135 @@
+use crate::io::COPYRIGHT_TEXT_MARKER;
+use std::fs;
+use std::path::{self, Path, PathBuf};
+use toml::{Table, Value};
+
+/// The file '--reuse' annotates the stamped files in, as the REUSE
+/// specification describes.
+pub const REUSE_FILE_NAME: &str = "REUSE.toml";
+
+/// The `REUSE.toml` format version written.
+pub const REUSE_VERSION: i64 = 1;
+
+/// Where '--reuse' writes `REUSE.toml` for the license written to
+/// `output`: next to it, or in the current directory for stdout.
+#[tracing::instrument]
+pub fn reuse_path(output: &Path) -> PathBuf {
+    if crate::io::is_stdout(output) {
+        PathBuf::from(REUSE_FILE_NAME)
+    } else {
+        output.with_file_name(REUSE_FILE_NAME)
+    }
+}
+
+/// Renders `REUSE.toml` at `path` with `files` annotated with `expression`
+/// and the `SPDX-FileCopyrightText` lines of `comment_block`. An existing
+/// file keeps its other annotations, and the files of one with the same
+/// license and copyright are kept alongside `files`.
+#[tracing::instrument(skip(comment_block, files))]
+pub fn render(
+    path: &Path,
+    expression: &str,
+    comment_block: &str,
+    files: &[PathBuf],
+) -> Result<String, String> {
+    let mut doc = match fs::read_to_string(path) {
+        Ok(contents) => contents
+            .parse::<Table>()
+            .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?,
+        Err(_) => Table::new(),
+    };
+    doc.entry("version")
+        .or_insert(Value::Integer(REUSE_VERSION));
+    let copyright: Vec<Value> = comment_block
+        .lines()
+        .filter_map(|line| line.strip_prefix(COPYRIGHT_TEXT_MARKER))
+        .map(|text| Value::String(text.trim().to_string()))
+        .collect();
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut paths: Vec<String> = files.iter().map(|file| relative_to(file, dir)).collect();
+
+    let Value::Array(annotations) = doc.entry("annotations").or_insert(Value::Array(Vec::new()))
+    else {
+        return Err(format!(
+            "Failed to update {}: 'annotations' is not a list",
+            path.display()
+        ));
+    };
+    let same = annotations.iter_mut().find_map(|annotation| {
+        let table = annotation.as_table_mut()?;
+        let matches = table.get("SPDX-License-Identifier")?.as_str() == Some(expression)
+            && copyright_values(table) == copyright;
+        matches.then_some(table)
+    });
+    match same {
+        Some(table) => {
+            match table.get("path") {
+                Some(Value::String(existing)) => paths.push(existing.clone()),
+                Some(Value::Array(existing)) => paths.extend(
+                    existing
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(str::to_string),
+                ),
+                _ => {}
+            }
+            paths.sort();
+            paths.dedup();
+            table.insert("path".to_string(), string_array(paths));
+        }
+        None => {
+            paths.sort();
+            paths.dedup();
+            let mut table = Table::new();
+            table.insert("path".to_string(), string_array(paths));
+            if !copyright.is_empty() {
+                table.insert(
+                    "SPDX-FileCopyrightText".to_string(),
+                    Value::Array(copyright),
+                );
+            }
+            table.insert(
+                "SPDX-License-Identifier".to_string(),
+                Value::String(expression.to_string()),
+            );
+            annotations.push(Value::Table(table));
+        }
+    }
+    toml::to_string(&doc).map_err(|e| format!("Failed to serialize {}: {e}", path.display()))
+}
+
+/// The copyright texts of an annotation, a string or a list of them.
+#[tracing::instrument(skip(table))]
+fn copyright_values(table: &Table) -> Vec<Value> {
+    match table.get("SPDX-FileCopyrightText") {
+        Some(Value::Array(values)) => values.clone(),
+        Some(value) => vec![value.clone()],
+        None => Vec::new(),
+    }
+}
+
+#[tracing::instrument(skip(values))]
+fn string_array(values: Vec<String>) -> Value {
+    Value::Array(values.into_iter().map(Value::String).collect())
+}
+
+/// `file` relative to `dir` with `/` separators, as `REUSE.toml` expects.
+/// A file outside `dir` is kept as it is.
+#[tracing::instrument]
+fn relative_to(file: &Path, dir: &Path) -> String {
+    let relative = match (path::absolute(file), path::absolute(dir)) {
+        (Ok(file), Ok(dir)) => file
+            .strip_prefix(&dir)
+            .map(Path::to_path_buf)
+            .unwrap_or(file),
+        _ => file.to_path_buf(),
+    };
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
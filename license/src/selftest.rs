@@ -0,0 +1,73 @@
+use crate::answers;
+use crate::io::SPDX_MARKER;
+use crate::license::{self, Licenses};
+use crate::{ceprintln, cprintln};
+use clap::ValueEnum;
+use std::env;
+use std::process::Command;
+
+/// The hidden argument that makes `self-test` render a single license.
+pub const ONLY_ARG: &str = "--only";
+
+/// Renders every license in its own child process so that a generator
+/// that panics or exits is reported instead of ending the run. Prints one
+/// line per license and returns `false` if any failed.
+#[tracing::instrument]
+pub fn run_all() -> bool {
+    let exe = match env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            ceprintln!(
+                "<bold><red>Failed to locate the current executable</></>: {}",
+                e
+            );
+            return false;
+        }
+    };
+    let mut failed = 0usize;
+    for license in Licenses::value_variants() {
+        let result = Command::new(&exe)
+            .arg("self-test")
+            .arg(ONLY_ARG)
+            .arg(license.to_string())
+            .output();
+        match result {
+            Ok(output) if output.status.success() => cprintln!("<green>ok</>\t{}", license),
+            Ok(output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let reason = stderr.lines().rfind(|l| !l.trim().is_empty()).unwrap_or("");
+                cprintln!("<red>FAIL</>\t{}\t{}", license, reason.trim());
+                failed += 1;
+            }
+            Err(e) => {
+                cprintln!("<red>FAIL</>\t{}\tfailed to run: {}", license, e);
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        ceprintln!("<bold><red>{} license(s) failed to render</></>", failed);
+        return false;
+    }
+    cprintln!("<bold><green>All licenses rendered</></>");
+    true
+}
+
+/// Renders `license` with stub answers and checks that it produced a
+/// license text and a header with the right SPDX identifier.
+#[tracing::instrument]
+pub fn run_one(license: &Licenses) -> Result<(), String> {
+    answers::use_stubs();
+    let texts = license::generate_license_text(license);
+    if texts.text.trim().is_empty() {
+        return Err("rendered an empty license text".to_string());
+    }
+    let expected = format!("{SPDX_MARKER} {license}");
+    match texts.comment.lines().next() {
+        Some(line) if line.trim_end() == expected => Ok(()),
+        line => Err(format!(
+            "expected the header to start with '{expected}', found '{}'",
+            line.unwrap_or_default()
+        )),
+    }
+}
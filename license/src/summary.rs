@@ -0,0 +1,130 @@
+use crate::cprintln;
+use crate::io;
+use crate::texts::LicenseTexts;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// How the result of a run is reported on stdout.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Colored status messages for people.
+    #[default]
+    Human,
+    /// A single JSON object describing what was generated. Status messages
+    /// go to stderr.
+    Json,
+    /// Status messages like `human`, with the license file written as
+    /// Markdown under a heading for the license.
+    Md,
+}
+
+/// A license that was generated and where its text went.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct LicenseSummary {
+    /// The SPDX identifier of the license.
+    pub id: String,
+    /// The file the license text was written to, or `-` for stdout.
+    pub output: PathBuf,
+    /// Whether the license has an alternative text, such as a notice for
+    /// files that can't hold a header.
+    pub alt: bool,
+    /// Whether the license has a notice for interactive programs.
+    pub interactive: bool,
+    /// The `NOTICE` file written next to the license, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notice: Option<PathBuf>,
+}
+
+impl LicenseSummary {
+    #[tracing::instrument(skip(texts))]
+    pub fn new(id: String, output: PathBuf, texts: &LicenseTexts) -> Self {
+        LicenseSummary {
+            id,
+            notice: texts.notice.as_ref().map(|_| io::notice_path(&output)),
+            output,
+            alt: texts.alt.is_some(),
+            interactive: texts.interactive.is_some(),
+        }
+    }
+}
+
+/// What a run generated, printed with '--format json'.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunSummary {
+    /// The SPDX expression the source files were stamped with.
+    pub expression: String,
+    pub licenses: Vec<LicenseSummary>,
+    /// The source files that got a license header.
+    pub modified: Vec<PathBuf>,
+    /// The source files that could not be read or written.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failed: Vec<PathBuf>,
+    /// The license index, if '--write-index' wrote one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<PathBuf>,
+    /// The `REUSE.toml` '--reuse' wrote, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reuse: Option<PathBuf>,
+}
+
+impl RunSummary {
+    /// Every file written: the modified source files, the license files,
+    /// the index, and `REUSE.toml`.
+    #[tracing::instrument]
+    pub fn written(&self) -> Vec<PathBuf> {
+        let licenses = self
+            .licenses
+            .iter()
+            .map(|license| license.output.clone())
+            .filter(|output| output.as_os_str() != crate::io::STDOUT_OUTPUT);
+        self.modified
+            .iter()
+            .cloned()
+            .chain(licenses)
+            .chain(self.index.clone())
+            .chain(self.reuse.clone())
+            .collect()
+    }
+
+    /// Prints every file written, or that would be with `dry_run`, and
+    /// every file that failed.
+    #[tracing::instrument]
+    pub fn print_files(&self, dry_run: bool) {
+        let verb = if dry_run { "Would write" } else { "Wrote" };
+        for license in &self.licenses {
+            cprintln!(
+                "<green>{}</> {} <dim>({})</>",
+                verb,
+                license.output.display(),
+                license.id
+            );
+            if let Some(notice) = &license.notice {
+                cprintln!("<green>{}</> {}", verb, notice.display());
+            }
+        }
+        if let Some(reuse) = &self.reuse {
+            cprintln!("<green>{}</> {}", verb, reuse.display());
+        }
+        let verb = if dry_run {
+            "Would add a header to"
+        } else {
+            "Added a header to"
+        };
+        for path in &self.modified {
+            cprintln!("<green>{}</> {}", verb, path.display());
+        }
+        for path in &self.failed {
+            cprintln!("<bold><red>Failed</></> {}", path.display());
+        }
+    }
+
+    /// Prints the summary as a JSON object on stdout.
+    #[tracing::instrument]
+    pub fn print_json(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize summary: {e}"))?;
+        println!("{json}");
+        Ok(())
+    }
+}
@@ -1,57 +1,104 @@
-use super::LicenseTexts;
+use super::{
+    CopyrightYear, LicenseAnswers, LicenseTexts, RenderError, RenderOptions, copyright_line,
+    prompt_copyright, reuse_comment, static_text,
+};
 use crate::io::prompt;
-use color_print::ceprintln;
 use handlebars::Handlebars;
 use serde::Serialize;
-use std::process;
 
-#[tracing::instrument]
-pub fn generate_apache_license_comment(year: u16, fullname: String) -> String {
+#[tracing::instrument(skip_all)]
+pub fn generate_apache_license_comment(
+    year: &CopyrightYear,
+    fullname: &str,
+    options: &RenderOptions,
+) -> Result<String, RenderError> {
     let mut handlebars = Handlebars::new();
-    match handlebars.register_template_string("apache_comment", APACHE_COMMENT) {
-        Ok(_) => {}
-        Err(e) => {
-            ceprintln!("<bold><red>Error registering template</></>: {}", e);
-            process::exit(1);
-        }
-    }
-    match handlebars.render(
-        "apache_comment",
-        &ApacheLicenseCommentTemplate { year, fullname },
-    ) {
-        Ok(comment) => comment,
-        Err(e) => {
-            ceprintln!("<bold><red>Error rendering template</></>: {}", e);
-            process::exit(1);
-        }
-    }
+    handlebars.register_template_string("apache_comment", APACHE_COMMENT)?;
+    let template = ApacheLicenseCommentTemplate {
+        copyright: copyright_line(COPYRIGHT, year, fullname, options),
+        year: year.clone(),
+        fullname: fullname.to_string(),
+    };
+    let comment = handlebars.render("apache_comment", &template)?;
+    Ok(reuse_comment(comment, year, fullname, options))
+}
+
+#[tracing::instrument(skip_all)]
+pub fn generate_apache_notice(
+    project: &str,
+    year: &CopyrightYear,
+    fullname: &str,
+    options: &RenderOptions,
+) -> Result<String, RenderError> {
+    let mut handlebars = Handlebars::new();
+    handlebars.register_template_string("apache_notice", APACHE_NOTICE)?;
+    Ok(handlebars.render(
+        "apache_notice",
+        &ApacheNoticeTemplate {
+            project: project.to_string(),
+            copyright: copyright_line(COPYRIGHT, year, fullname, options),
+            fullname: fullname.to_string(),
+        },
+    )?)
 }
 
-#[tracing::instrument]
-pub fn generate_apache_license_text() -> String {
-    APACHE_TEXT.to_string()
+#[tracing::instrument(skip(options))]
+pub fn generate_apache_license_text(options: &RenderOptions) -> Result<String, RenderError> {
+    static_text(APACHE_TITLE, APACHE_TEXT, options)
 }
 
-#[tracing::instrument]
-pub fn generate_apache_license() -> LicenseTexts {
-    let year: u16 = prompt("Enter the copyright year");
-    let fullname: String = prompt("Enter the full name of the copyright holder");
-    LicenseTexts {
-        text: generate_apache_license_text(),
-        comment: generate_apache_license_comment(year, fullname),
+/// Asks for the answers Apache-2.0 is rendered with. The project is only
+/// asked for if its options ask for a `NOTICE` file.
+#[tracing::instrument(skip(answers))]
+pub fn prompt_answers(answers: &mut LicenseAnswers) {
+    prompt_copyright(answers);
+    if answers.options.notice {
+        answers.project = Some(prompt("Enter the name of the project"));
+    }
+}
+
+#[tracing::instrument(skip(answers))]
+pub fn generate_apache_license(answers: &LicenseAnswers) -> Result<LicenseTexts, RenderError> {
+    let options = &answers.options;
+    let (year, fullname) = answers.copyright()?;
+    let notice = match (options.notice, &answers.project) {
+        (false, _) => None,
+        (true, Some(project)) => Some(generate_apache_notice(project, year, fullname, options)?),
+        (true, None) => return Err(RenderError::Missing(vec!["project"])),
+    };
+    Ok(LicenseTexts {
+        text: generate_apache_license_text(options)?,
+        comment: generate_apache_license_comment(year, fullname, options)?,
         alt: None,
         interactive: None,
-    }
+        notice,
+    })
 }
 
+#[derive(Serialize)]
+pub struct ApacheNoticeTemplate {
+    pub project: String,
+    pub copyright: String,
+    pub fullname: String,
+}
+
+pub const APACHE_NOTICE: &str = r#"{{{project}}}
+{{{copyright}}}
+
+This product includes software developed by {{{fullname}}}.
+"#;
+
 #[derive(Serialize)]
 pub struct ApacheLicenseCommentTemplate {
-    pub year: u16,
+    pub year: CopyrightYear,
     pub fullname: String,
+    pub copyright: String,
 }
 
+pub const COPYRIGHT: &str = "Copyright {year} {name}";
+
 pub const APACHE_COMMENT: &str = r#"SPDX-License-Identifier: Apache-2.0
-Copyright {{year}} {{fullname}}
+{{{copyright}}}
 
 Licensed under the Apache License, Version 2.0 (the "License");
 you may not use this file except in compliance with the License.
@@ -66,6 +113,8 @@ See the License for the specific language governing permissions and
 limitations under the License.
 "#;
 
+pub const APACHE_TITLE: &str = "Apache License";
+
 pub const APACHE_TEXT: &str = r#"                                 Apache License
                            Version 2.0, January 2004
                         http://www.apache.org/licenses/
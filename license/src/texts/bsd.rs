@@ -1,149 +1,191 @@
-use super::LicenseTexts;
-use crate::io::{prompt, prompt_optional};
+use super::{
+    CopyrightYear, LicenseAnswers, LicenseTexts, RenderError, RenderOptions, copyright_line,
+    prompt_copyright, reuse_comment, text_template, with_vars,
+};
+use crate::io::prompt_optional;
 use crate::license::BsdAmmendment;
-use color_print::ceprintln;
 use handlebars::Handlebars;
 use serde::Serialize;
-use std::process;
 
-#[tracing::instrument]
-fn register_templ(partial: BsdLicenseText, license: &BsdLicenseTemplate) -> String {
+#[tracing::instrument(skip(license, options))]
+fn register_templ(
+    partial: BsdLicenseText,
+    license: &BsdLicenseTemplate,
+    options: &RenderOptions,
+) -> Result<String, RenderError> {
     let mut handlebars = Handlebars::new();
-    match handlebars.register_template_string("bsd_license", TEXT) {
-        Ok(_) => {}
-        Err(e) => {
-            ceprintln!("<bold><red>Error registering template</></>: {}", e);
-            process::exit(1);
-        }
-    }
-    match handlebars.register_partial("fourth_partial", partial.fourth) {
-        Ok(_) => {}
-        Err(e) => {
-            ceprintln!("<bold><red>Error registering partial</></>: {}", e);
-            process::exit(1);
-        }
-    }
-    match handlebars.render("bsd_license", &license) {
-        Ok(t) => t,
-        Err(e) => {
-            ceprintln!("<bold><red>Error rendering template</></>: {}", e);
-            process::exit(1);
-        }
-    }
+    handlebars.register_template_string("bsd_license", text_template(TEXT, options))?;
+    handlebars.register_partial("third_partial", partial.third)?;
+    handlebars.register_partial("fourth_partial", partial.fourth)?;
+    Ok(handlebars.render("bsd_license", &with_vars(license, options)?)?)
 }
 
-#[tracing::instrument]
-pub fn generate_bsd_license(sublicense: BsdAmmendment) -> LicenseTexts {
-    let year: u16 = prompt("Enter the copyright year");
-    let fullname: String = prompt("Enter the full name of the copyright holder");
-    match sublicense {
-        BsdAmmendment::None => generate_base_license(year, fullname),
-        BsdAmmendment::Attribution => generate_attribution_license(year, fullname),
-        BsdAmmendment::Modification => generate_modification_license(year, fullname),
-        BsdAmmendment::NoMilitary => generate_no_military_license(year, fullname),
-    }
-}
-
-#[tracing::instrument]
-pub fn generate_base_license(year: u16, fullname: String) -> LicenseTexts {
+/// Renders the BSD license `spdx` from its clauses in `partial`. Only
+/// BSD-3-Clause-Attribution credits an `organization` and its `website`.
+#[tracing::instrument(skip(answers))]
+fn generate(
+    spdx: &str,
+    partial: BsdLicenseText,
+    answers: &LicenseAnswers,
+) -> Result<LicenseTexts, RenderError> {
+    let options = &answers.options;
+    let (year, fullname) = answers.copyright()?;
+    let attribution = partial == ATTRIBUTION;
     let license = BsdLicenseTemplate {
-        year,
-        fullname,
-        organization: None,
-        website: None,
-        license: NONE,
+        copyright: copyright_line(COPYRIGHT, year, fullname, options),
+        year: year.clone(),
+        fullname: fullname.to_string(),
+        organization: answers.organization.clone().filter(|_| attribution),
+        website: answers.website.clone().filter(|_| attribution),
+        license: partial,
     };
-    let text = register_templ(NONE, &license);
+    let text = register_templ(partial, &license, options)?;
 
-    LicenseTexts {
+    Ok(LicenseTexts {
         text,
-        comment: "SPDX-License-Identifier: BSD-3-Clause".to_string(),
+        comment: reuse_comment(
+            format!("SPDX-License-Identifier: {spdx}"),
+            year,
+            fullname,
+            options,
+        ),
         alt: None,
         interactive: None,
+        notice: None,
+    })
+}
+
+/// Asks for the answers the BSD-3-Clause `sublicense` is rendered with.
+#[tracing::instrument(skip(answers))]
+pub fn prompt_answers(sublicense: &BsdAmmendment, answers: &mut LicenseAnswers) {
+    prompt_copyright(answers);
+    if let BsdAmmendment::Attribution = sublicense {
+        answers.organization = prompt_optional("Enter the name of the organization");
+        answers.website = prompt_optional("Enter the website of the organization");
     }
 }
 
-#[tracing::instrument]
-pub fn generate_attribution_license(year: u16, fullname: String) -> LicenseTexts {
-    let organization: Option<String> = prompt_optional("Enter the name of the organization");
-    let website: Option<String> = prompt_optional("Enter the website of the organization");
+#[tracing::instrument(skip(answers))]
+pub fn generate_bsd_license(
+    sublicense: BsdAmmendment,
+    answers: &LicenseAnswers,
+) -> Result<LicenseTexts, RenderError> {
+    match sublicense {
+        BsdAmmendment::None => generate_base_license(answers),
+        BsdAmmendment::Attribution => generate_attribution_license(answers),
+        BsdAmmendment::Modification => generate_modification_license(answers),
+        BsdAmmendment::NoMilitary => generate_no_military_license(answers),
+    }
+}
 
-    let license = BsdLicenseTemplate {
-        year,
-        fullname,
-        organization,
-        website,
-        license: ATTRIBUTION,
-    };
-    let text = register_templ(ATTRIBUTION, &license);
+/// BSD-2-Clause: the base license without the third clause, which
+/// forbids using the copyright holder's name to endorse derived products.
+#[tracing::instrument(skip(answers))]
+pub fn generate_bsd2_license(answers: &LicenseAnswers) -> Result<LicenseTexts, RenderError> {
+    generate("BSD-2-Clause", TWO_CLAUSE, answers)
+}
 
-    LicenseTexts {
-        text,
-        comment: "SPDX-License-Identifier: BSD-3-Clause-Attribution".to_string(),
-        alt: None,
-        interactive: None,
-    }
+/// BSD-2-Clause-Patent: BSD-2-Clause with an express patent grant from
+/// every contributor.
+#[tracing::instrument(skip(answers))]
+pub fn generate_bsd2_patent_license(answers: &LicenseAnswers) -> Result<LicenseTexts, RenderError> {
+    generate("BSD-2-Clause-Patent", PATENT, answers)
 }
 
-#[tracing::instrument]
-pub fn generate_modification_license(year: u16, fullname: String) -> LicenseTexts {
-    let license = BsdLicenseTemplate {
-        year,
-        fullname,
-        organization: None,
-        website: None,
-        license: MODIFICATION,
-    };
-    let text = register_templ(MODIFICATION, &license);
+#[tracing::instrument(skip(answers))]
+pub fn generate_base_license(answers: &LicenseAnswers) -> Result<LicenseTexts, RenderError> {
+    generate("BSD-3-Clause", NONE, answers)
+}
 
-    LicenseTexts {
-        text,
-        comment: "SPDX-License-Identifier: BSD-3-Clause-Modification".to_string(),
-        alt: None,
-        interactive: None,
-    }
+#[tracing::instrument(skip(answers))]
+pub fn generate_attribution_license(answers: &LicenseAnswers) -> Result<LicenseTexts, RenderError> {
+    generate("BSD-3-Clause-Attribution", ATTRIBUTION, answers)
 }
 
-#[tracing::instrument]
-pub fn generate_no_military_license(year: u16, fullname: String) -> LicenseTexts {
-    let license = BsdLicenseTemplate {
-        year,
-        fullname,
-        organization: None,
-        website: None,
-        license: NO_MILITARY,
-    };
-    let text = register_templ(NO_MILITARY, &license);
+#[tracing::instrument(skip(answers))]
+pub fn generate_modification_license(
+    answers: &LicenseAnswers,
+) -> Result<LicenseTexts, RenderError> {
+    generate("BSD-3-Clause-Modification", MODIFICATION, answers)
+}
 
-    LicenseTexts {
-        text,
-        comment: "SPDX-License-Identifier: BSD-3-Clause-No-Military-License".to_string(),
-        alt: None,
-        interactive: None,
-    }
+#[tracing::instrument(skip(answers))]
+pub fn generate_no_military_license(answers: &LicenseAnswers) -> Result<LicenseTexts, RenderError> {
+    generate("BSD-3-Clause-No-Military-License", NO_MILITARY, answers)
 }
 
+/// The variables the license text is rendered with, including a
+/// `BSD-3-Clause*.hbs` from '--template-dir'.
 #[derive(Serialize, Debug)]
 pub struct BsdLicenseTemplate {
-    pub year: u16,
+    pub year: CopyrightYear,
     pub fullname: String,
+    pub copyright: String,
     pub organization: Option<String>,
     pub website: Option<String>,
     pub license: BsdLicenseText,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct BsdLicenseText {
+    pub third: &'static str,
     pub fourth: &'static str,
     pub postamble: &'static str,
 }
 
+/// The clause BSD-2-Clause leaves out.
+pub const NO_ENDORSEMENT: &str = r#"3. Neither the name of the copyright holder nor the names of its 
+   contributors may be used to endorse or promote products derived from 
+   this software without specific prior written permission.
+"#;
+
+pub const TWO_CLAUSE: BsdLicenseText = BsdLicenseText {
+    third: "",
+    fourth: "",
+    postamble: "",
+};
+
+pub const PATENT: BsdLicenseText = BsdLicenseText {
+    third: "",
+    fourth: r#"
+Subject to the terms and conditions of this license, each copyright
+holder and contributor hereby grants to those receiving rights under
+this license a perpetual, worldwide, non-exclusive, no-charge,
+royalty-free, irrevocable (except for failure to satisfy the conditions
+of this license) patent license to make, have made, use, offer to sell,
+sell, import, and otherwise transfer this software, where such license
+applies only to those patent claims, already acquired or hereafter
+acquired, licensable by such copyright holder or contributor that are
+necessarily infringed by:
+
+(a) their Contribution(s) (the licensed copyrights of copyright holders
+    and non-copyrightable additions of contributors, in source or binary
+    form) alone; or
+
+(b) combination of their Contribution(s) with the work of authorship to
+    which such Contribution(s) was added by such copyright holder or
+    contributor, if, at the time the Contribution is added, such
+    addition causes such combination to be necessarily infringed. The
+    patent license shall not apply to any other combinations which
+    include the Contribution.
+
+Except as expressly stated above, no rights or licenses from any
+copyright holder or contributor is granted under this license, whether
+expressly, by implication, estoppel or otherwise.
+
+DISCLAIMER
+"#,
+    postamble: "",
+};
+
 pub const NONE: BsdLicenseText = BsdLicenseText {
+    third: NO_ENDORSEMENT,
     fourth: "",
     postamble: "",
 };
 
 pub const NO_MILITARY: BsdLicenseText = BsdLicenseText {
+    third: NO_ENDORSEMENT,
     fourth: "",
     postamble: r#"
 YOU ACKNOWLEDGE THAT THIS SOFTWARE IS NOT DESIGNED, LICENSED OR INTENDED 
@@ -153,6 +195,7 @@ FACILITY.
 };
 
 pub const MODIFICATION: BsdLicenseText = BsdLicenseText {
+    third: NO_ENDORSEMENT,
     fourth: r#"4. If any files are modified, you must cause the modified files to 
 carry prominent notices stating that you changed the files and the 
 date of any change."#,
@@ -160,13 +203,18 @@ date of any change."#,
 };
 
 pub const ATTRIBUTION: BsdLicenseText = BsdLicenseText {
+    third: NO_ENDORSEMENT,
     fourth: r#"4. Redistributions of any form whatsoever must retain the following 
 acknowledgment: 'This product includes software developed by the 
 "{{#if organization}}{{organization}}{{else}}{{fullname}}{{/if}}"{{#if website}} ({{website}}){{/if}}.'"#,
     postamble: "",
 };
 
-pub const TEXT: &str = r#"Copyright (c) {{year}} {{fullname}}.
+pub const COPYRIGHT: &str = "Copyright (c) {year} {name}.";
+
+pub const TEXT: &str = r#"{{#if title}}{{{title}}}
+
+{{/if}}{{{copyright}}}
 
 Redistribution and use in source and binary forms, with or without 
 modification, are permitted provided that the following conditions are 
@@ -177,9 +225,7 @@ met:
 2. Redistributions in binary form must reproduce the above copyright 
    notice, this list of conditions and the following disclaimer in the 
    documentation and/or other materials provided with the distribution.
-3. Neither the name of the copyright holder nor the names of its 
-   contributors may be used to endorse or promote products derived from 
-   this software without specific prior written permission.
+{{> third_partial}}
 {{> fourth_partial}}
 
 THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS 
@@ -1,13 +1,15 @@
-use super::LicenseTexts;
+use super::{LicenseAnswers, LicenseTexts, RenderError, reuse_comment_answered, static_text};
 
-#[tracing::instrument]
-pub fn generate_bsl_license() -> LicenseTexts {
-    LicenseTexts {
-        text: BSL_TEXT.to_string(),
-        comment: BSL_COMMENT.to_string(),
+#[tracing::instrument(skip(answers))]
+pub fn generate_bsl_license(answers: &LicenseAnswers) -> Result<LicenseTexts, RenderError> {
+    let options = &answers.options;
+    Ok(LicenseTexts {
+        text: static_text(BSL_TITLE, BSL_TEXT, options)?,
+        comment: reuse_comment_answered(BSL_COMMENT.to_string(), answers)?,
         alt: None,
         interactive: None,
-    }
+        notice: None,
+    })
 }
 
 pub const BSL_COMMENT: &str = r#"SPDX-License-Identifier: BSL-1.0
@@ -38,6 +40,8 @@ OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
 THE SOFTWARE.
 "#;
 
+pub const BSL_TITLE: &str = "Boost Software License - Version 1.0 - August 17th, 2003";
+
 pub const BSL_TEXT: &str = r#"Boost Software License - Version 1.0 - August 17th, 2003
 
 Permission is hereby granted, free of charge, to any person or organization
@@ -1,15 +1,19 @@
-use super::LicenseTexts;
-
-#[tracing::instrument]
-pub fn generate_cddl_license() -> LicenseTexts {
-    LicenseTexts {
-        text: CDDL.to_string(),
-        comment: "SPDX-License-Identifier: CDDL-1.0".to_string(),
+use super::{LicenseAnswers, LicenseTexts, RenderError, reuse_comment_answered, static_text};
+
+#[tracing::instrument(skip(answers))]
+pub fn generate_cddl_license(answers: &LicenseAnswers) -> Result<LicenseTexts, RenderError> {
+    let options = &answers.options;
+    Ok(LicenseTexts {
+        text: static_text(CDDL_TITLE, CDDL, options)?,
+        comment: reuse_comment_answered("SPDX-License-Identifier: CDDL-1.0".to_string(), answers)?,
         alt: None,
         interactive: None,
-    }
+        notice: None,
+    })
 }
 
+pub const CDDL_TITLE: &str = "COMMON DEVELOPMENT AND DISTRIBUTION LICENSE (CDDL) Version 1.0";
+
 pub const CDDL: &str = r#"COMMON DEVELOPMENT AND DISTRIBUTION LICENSE (CDDL) Version 1.0
 
 1. Definitions.
@@ -1,55 +1,122 @@
-use super::LicenseTexts;
+use super::{
+    LicenseAnswers, LicenseTexts, RenderError, prompt_reuse_copyright, reuse_comment_answered,
+    static_text,
+};
+use crate::answers;
+use crate::ceprintln;
 use crate::io::prompt_optional;
-use color_print::ceprintln;
+use crate::license::Licenses;
 use handlebars::Handlebars;
 use serde::Serialize;
-use std::process;
 
-#[tracing::instrument]
-pub fn generate_epl_license() -> LicenseTexts {
+/// Asks for the answers EPL-2.0 is rendered with.
+#[tracing::instrument(skip(answers))]
+pub fn prompt_answers(answers: &mut LicenseAnswers) {
+    answers.secondary_licenses = get_licenses().unwrap_or_default();
+    prompt_reuse_copyright(answers);
+}
+
+#[tracing::instrument(skip(answers))]
+pub fn generate_epl_license(answers: &LicenseAnswers) -> Result<LicenseTexts, RenderError> {
+    let options = &answers.options;
+    let licenses: Vec<String> = answers
+        .secondary_licenses
+        .iter()
+        .map(Licenses::to_string)
+        .collect();
+    if answers.secondary_licenses.contains(&Licenses::Epl2) {
+        // EPL-2.0 can't be its own secondary license.
+        return Err(RenderError::Invalid {
+            field: "secondary_licenses",
+            value: licenses.join(", "),
+        });
+    }
     let mut alt: Option<String> = None;
-    if let Some(licenses) = get_licenses() {
+    if !licenses.is_empty() {
         let license = EplLicenseSecondaryTemplate { licenses };
         let mut handlebars = Handlebars::new();
-        match handlebars.register_template_string("epl_alt", EPL_SECONDARY) {
-            Ok(_) => {}
-            Err(e) => {
-                ceprintln!("<bold><red>Error registering template</></>: {}", e);
-                process::exit(1);
-            }
-        }
-        alt = match handlebars.render("epl_alt", &license) {
-            Ok(t) => Some(t),
-            Err(e) => {
-                ceprintln!("<bold><red>Error rendering template</></>: {}", e);
-                process::exit(1);
-            }
-        };
+        handlebars.register_template_string("epl_alt", EPL_SECONDARY)?;
+        alt = Some(handlebars.render("epl_alt", &license)?);
     }
-
-    LicenseTexts {
-        text: EPL_TEXT.to_string(),
-        comment: "SPDX-License-Identifier: EPL-2.0".to_string(),
+    Ok(LicenseTexts {
+        text: static_text(EPL_TITLE, EPL_TEXT, options)?,
+        comment: reuse_comment_answered(format!("SPDX-License-Identifier: {EPL_SPDX}"), answers)?,
         alt,
         interactive: None,
-    }
+        notice: None,
+    })
 }
 
+const SECONDARY_LICENSES: &str =
+    "Enter the secondary licenses that are permitted (comma separated)";
+
 #[tracing::instrument]
-pub fn get_licenses() -> Option<Vec<String>> {
-    let licenses: Option<String> =
-        prompt_optional("Enter the secondary licenses that are permitted (comma separated)");
-    if let Some(licenses) = &licenses {
-        if licenses.is_empty() {
-            None
-        } else {
-            Some(licenses.split(',').map(|s| s.trim().to_string()).collect())
+pub fn get_licenses() -> Option<Vec<Licenses>> {
+    loop {
+        let licenses: Option<String> = prompt_optional(SECONDARY_LICENSES);
+        let licenses: Vec<String> = match &licenses {
+            Some(licenses) if !licenses.is_empty() => licenses
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            _ => return None,
+        };
+        let unknown: Vec<&str> = licenses
+            .iter()
+            .filter(|l| l.parse::<Licenses>().is_err())
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() && answers::stubbed() {
+            // Stub answers can't be corrected, so asking again would loop.
+            return None;
+        }
+        if !unknown.is_empty() {
+            ceprintln!(
+                "<bold><yellow>Unknown SPDX license identifier(s)</></>: {}",
+                unknown.join(", ")
+            );
+            ceprintln!("<bold><yellow>Please try again.</></>");
+            continue;
+        }
+        let licenses: Vec<Licenses> = licenses
+            .iter()
+            .filter_map(|l| l.parse::<Licenses>().ok())
+            .collect();
+        if licenses.contains(&Licenses::Epl2) {
+            ceprintln!(
+                "<bold><yellow>{} cannot be listed as its own secondary license.</></>",
+                EPL_SPDX,
+            );
+            ceprintln!("<bold><yellow>Please try again.</></>");
+            continue;
         }
-    } else {
-        None
+        for license in licenses
+            .iter()
+            .filter(|l| !is_secondary_license(&l.to_string()))
+        {
+            ceprintln!(
+                "<bold><yellow>Warning</></>: {} is not a version of the GNU General Public License, \
+which is what {} permits as a Secondary License.",
+                license,
+                EPL_SPDX,
+            );
+        }
+        return Some(licenses);
     }
 }
 
+/// Whether `license` is a GNU GPL version 2.0 or later, which is what
+/// EPL-2.0 accepts as a "Secondary License".
+#[tracing::instrument]
+pub fn is_secondary_license(license: &str) -> bool {
+    ["GPL-2.0", "GPL-3.0"]
+        .iter()
+        .any(|gpl| license.starts_with(gpl))
+}
+
+pub const EPL_SPDX: &str = "EPL-2.0";
+
 #[derive(Serialize)]
 pub struct EplLicenseSecondaryTemplate {
     pub licenses: Vec<String>,
@@ -63,6 +130,8 @@ in the Eclipse Public License, v. 2.0 are satisfied:
 {{/each}}
 "#;
 
+pub const EPL_TITLE: &str = "Eclipse Public License - v 2.0";
+
 pub const EPL_TEXT: &str = r#"Eclipse Public License - v 2.0
 
     THE ACCOMPANYING PROGRAM IS PROVIDED UNDER THE TERMS OF THIS ECLIPSE
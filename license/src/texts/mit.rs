@@ -1,51 +1,57 @@
-use super::LicenseTexts;
-use crate::io::prompt;
-use color_print::ceprintln;
+use super::{
+    CopyrightYear, LicenseAnswers, LicenseTexts, RenderError, copyright_line, reuse_comment,
+    text_template, with_vars,
+};
 use handlebars::Handlebars;
 use serde::Serialize;
-use std::process;
 
-#[tracing::instrument]
-pub fn generate_mit_license() -> LicenseTexts {
-    let year: u16 = prompt("Enter the copyright year");
-    let fullname: String = prompt("Enter the full name of the copyright holder");
+#[tracing::instrument(skip(answers))]
+pub fn generate_mit_license(answers: &LicenseAnswers) -> Result<LicenseTexts, RenderError> {
+    let options = &answers.options;
+    let (year, fullname) = answers.copyright()?;
 
-    let license = MitLicenseTemplate { year, fullname };
+    let license = MitLicenseTemplate {
+        title: MIT_TITLE,
+        year: year.clone(),
+        fullname: fullname.to_string(),
+        copyright: copyright_line(COPYRIGHT, year, fullname, options),
+    };
 
     let mut handlebars = Handlebars::new();
-    match handlebars.register_template_string("mit_license", MIT) {
-        Ok(_) => {}
-        Err(e) => {
-            ceprintln!("Error registering template: {}", e);
-            process::exit(1);
-        }
-    }
-
-    let text = match handlebars.render("mit_license", &license) {
-        Ok(rendered) => rendered,
-        Err(e) => {
-            ceprintln!("Error rendering template: {}", e);
-            process::exit(1);
-        }
-    };
+    handlebars.register_template_string("mit_license", text_template(MIT, options))?;
+    let text = handlebars.render("mit_license", &with_vars(&license, options)?)?;
 
-    LicenseTexts {
+    Ok(LicenseTexts {
         text,
-        comment: "SPDX-License-Identifier: MIT".to_string(),
+        comment: reuse_comment(
+            "SPDX-License-Identifier: MIT".to_string(),
+            year,
+            fullname,
+            options,
+        ),
         alt: None,
         interactive: None,
-    }
+        notice: None,
+    })
 }
 
+/// The variables the license text is rendered with, including a
+/// `MIT.hbs` from '--template-dir'.
 #[derive(Serialize)]
 pub struct MitLicenseTemplate {
-    pub year: u16,
+    pub title: &'static str,
+    pub year: CopyrightYear,
     pub fullname: String,
+    pub copyright: String,
 }
 
-pub const MIT: &str = r#"MIT License
+pub const COPYRIGHT: &str = "Copyright (c) {year} {name}";
+
+pub const MIT_TITLE: &str = "MIT License";
+
+pub const MIT: &str = r#"{{{title}}}
 
-Copyright (c) {{year}} {{fullname}}
+{{{copyright}}}
 
 Permission is hereby granted, free of charge, to any person obtaining a copy
 of this software and associated documentation files (the "Software"), to deal
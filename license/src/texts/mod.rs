@@ -1,57 +1,298 @@
 pub mod apache;
 pub mod bsd;
 pub mod bsl;
+pub mod cc;
 pub mod cddl;
+pub mod cecill;
 pub mod epl;
+pub mod eupl;
 pub mod gnu;
 pub mod mit;
 pub mod mpl;
+pub mod options;
+pub mod quotes;
+pub mod templates;
 pub mod unlicense;
+pub mod wtfpl;
+pub mod zero_bsd;
+pub mod zlib;
 
 pub use apache::generate_apache_license;
-pub use bsd::generate_bsd_license;
+pub use bsd::{generate_bsd_license, generate_bsd2_license, generate_bsd2_patent_license};
 pub use bsl::generate_bsl_license;
+pub use cc::generate_cc_by_license;
+pub use cc::generate_cc0_license;
 pub use cddl::generate_cddl_license;
+pub use cecill::generate_cecill_license;
 pub use epl::generate_epl_license;
+pub use eupl::{EuplLanguage, eupl_language, generate_eupl_license, set_eupl_language};
 pub use gnu::generate_agpl_license;
 pub use gnu::generate_gpl_license;
+pub use gnu::generate_gpl2_license;
 pub use gnu::generate_lgpl_license;
+pub use gnu::generate_lgpl21_license;
 pub use mit::generate_mit_license;
-pub use mpl::generate_mpl_license;
+pub use mpl::{MplHeader, generate_mpl_license, set_mpl_header};
+pub use options::{LicenseAnswers, RenderError, RenderOptions, SignedRelease};
+pub use quotes::{QuoteStyle, apply_quote_style, set_quote_style};
+pub use templates::{
+    TITLE_VAR, TemplateVar, retitle, set_template_dir, set_template_vars, static_text,
+    text_template, with_vars,
+};
 pub use unlicense::generate_unlicense_license;
+pub use wtfpl::generate_wtfpl_license;
+pub use zero_bsd::generate_0bsd_license;
+pub use zlib::generate_zlib_license;
 
+use crate::io::{COPYRIGHT_TEXT_MARKER, prompt};
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-#[derive(Debug)]
+static COPYRIGHT_FORMAT: OnceLock<String> = OnceLock::new();
+static ALL_RIGHTS_RESERVED: AtomicBool = AtomicBool::new(false);
+static NOTICE: AtomicBool = AtomicBool::new(false);
+static REUSE: AtomicBool = AtomicBool::new(false);
+
+const RIGHTS_RESERVED: &str = "All rights reserved.";
+
+/// Overrides the copyright line used by every license. The format may
+/// contain `{year}` and `{name}` placeholders.
+#[tracing::instrument]
+pub fn set_copyright_format(format: String) {
+    if COPYRIGHT_FORMAT.set(format).is_err() {
+        tracing::warn!("Copyright format was already set");
+    }
+}
+
+/// Appends "All rights reserved." to every copyright line.
+#[tracing::instrument]
+pub fn set_all_rights_reserved(enabled: bool) {
+    ALL_RIGHTS_RESERVED.store(enabled, Ordering::Relaxed);
+}
+
+/// Starts every header with an `SPDX-FileCopyrightText` line, as the
+/// REUSE specification asks.
+#[tracing::instrument]
+pub fn set_reuse(enabled: bool) {
+    REUSE.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether [`set_reuse`] enabled `SPDX-FileCopyrightText` lines.
+#[tracing::instrument]
+pub fn reuse() -> bool {
+    REUSE.load(Ordering::Relaxed)
+}
+
+/// `comment` with an `SPDX-FileCopyrightText` line for `year` and
+/// `fullname` before it, if the options ask for one.
+#[tracing::instrument(skip_all)]
+pub fn reuse_comment(
+    comment: String,
+    year: &CopyrightYear,
+    fullname: &str,
+    options: &RenderOptions,
+) -> String {
+    if options.reuse {
+        format!("{COPYRIGHT_TEXT_MARKER} {year} {fullname}\n{comment}")
+    } else {
+        comment
+    }
+}
+
+/// Like [`reuse_comment`], for licenses that don't need the year and
+/// copyright holder otherwise. They are only needed if the options ask
+/// for an `SPDX-FileCopyrightText` line.
+#[tracing::instrument(skip(comment, answers))]
+pub fn reuse_comment_answered(
+    comment: String,
+    answers: &LicenseAnswers,
+) -> Result<String, RenderError> {
+    let options = &answers.options;
+    if !options.reuse {
+        return Ok(comment);
+    }
+    let (year, fullname) = answers.copyright()?;
+    Ok(reuse_comment(comment, year, fullname, options))
+}
+
+/// Like [`reuse_comment_answered`], asking for the year and copyright
+/// holder if [`set_reuse`] enabled it, for license texts that aren't
+/// built in.
+#[tracing::instrument(skip(comment))]
+pub fn reuse_comment_prompted(comment: String) -> String {
+    let mut answers = LicenseAnswers {
+        options: RenderOptions {
+            reuse: reuse(),
+            ..RenderOptions::default()
+        },
+        ..LicenseAnswers::default()
+    };
+    prompt_reuse_copyright(&mut answers);
+    match answers.copyright() {
+        Ok((year, fullname)) => reuse_comment(comment, year, fullname, &answers.options),
+        Err(_) => comment,
+    }
+}
+
+/// Asks for the copyright year and holder.
+#[tracing::instrument(skip(answers))]
+pub fn prompt_copyright(answers: &mut LicenseAnswers) {
+    answers.year = Some(prompt("Enter the copyright year"));
+    answers.fullname = Some(prompt("Enter the full name of the copyright holder"));
+}
+
+/// Asks for the copyright year and holder of a license that only needs
+/// them for an `SPDX-FileCopyrightText` line, if its options ask for one.
+#[tracing::instrument(skip(answers))]
+pub fn prompt_reuse_copyright(answers: &mut LicenseAnswers) {
+    if answers.options.reuse {
+        prompt_copyright(answers);
+    }
+}
+
+/// Generates a `NOTICE` file for licenses that expect one.
+#[tracing::instrument]
+pub fn set_notice(enabled: bool) {
+    NOTICE.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether a `NOTICE` file was asked for with [`set_notice`].
+#[tracing::instrument]
+pub fn notice() -> bool {
+    NOTICE.load(Ordering::Relaxed)
+}
+
+impl RenderOptions {
+    /// The options set for this run with the `set_*` functions, with the
+    /// template for the license `id` from [`set_template_dir`], if any.
+    #[tracing::instrument]
+    pub fn from_settings(id: &str) -> Self {
+        RenderOptions {
+            copyright_format: COPYRIGHT_FORMAT.get().cloned(),
+            all_rights_reserved: ALL_RIGHTS_RESERVED.load(Ordering::Relaxed),
+            notice: notice(),
+            reuse: reuse(),
+            template: templates::user_template(id),
+            vars: templates::template_vars(),
+            quote_style: quotes::quote_style(),
+            mpl_header: mpl::mpl_header(),
+        }
+    }
+}
+
+/// A copyright year (`2025`) or an inclusive range of years
+/// (`2019-2025`), rendered into the templates as written.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct CopyrightYear(String);
+
+impl FromStr for CopyrightYear {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let year = |part: &str| {
+            let part = part.trim();
+            if part.bytes().all(|b| b.is_ascii_digit()) {
+                part.parse::<u16>().ok()
+            } else {
+                None
+            }
+        };
+        match s.split_once('-') {
+            None => year(s).map(|year| CopyrightYear(year.to_string())),
+            Some((start, end)) => match (year(start), year(end)) {
+                (Some(start), Some(end)) if start <= end => {
+                    Some(CopyrightYear(format!("{start}-{end}")))
+                }
+                _ => None,
+            },
+        }
+        .ok_or_else(|| {
+            format!(
+                "expected a year (e.g., 2025) or a range of years (e.g., 2019-2025), found '{s}'"
+            )
+        })
+    }
+}
+
+impl CopyrightYear {
+    /// The current year in UTC, from the system clock.
+    #[tracing::instrument]
+    pub fn current() -> Self {
+        let days = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() / 86_400) as i64;
+        // Howard Hinnant's `civil_from_days`, keeping only the year.
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let march_based_month = (5 * doy + 2) / 153;
+        let year = yoe + era * 400 + i64::from(march_based_month >= 10);
+        CopyrightYear(year.to_string())
+    }
+}
+
+impl fmt::Display for CopyrightYear {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Renders a copyright line from the format in the options, falling back
+/// to the license's `canonical` format.
+#[tracing::instrument(skip_all)]
+pub fn copyright_line(
+    canonical: &str,
+    year: &CopyrightYear,
+    fullname: &str,
+    options: &RenderOptions,
+) -> String {
+    let line = options
+        .copyright_format
+        .as_deref()
+        .unwrap_or(canonical)
+        .replace("{year}", &year.to_string())
+        .replace("{name}", fullname);
+    if !options.all_rights_reserved || line.ends_with(RIGHTS_RESERVED) {
+        return line;
+    }
+    if line.ends_with('.') {
+        format!("{line} {RIGHTS_RESERVED}")
+    } else {
+        format!("{line}. {RIGHTS_RESERVED}")
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct LicenseTexts {
     pub text: String,
     pub comment: String,
     pub alt: Option<String>,
     pub interactive: Option<String>,
+    /// A `NOTICE` file to write next to the license, for licenses that
+    /// expect one.
+    pub notice: Option<String>,
+}
+
+/// The first ten characters of `text`, cut on a character boundary.
+fn preview(text: &str) -> &str {
+    text.char_indices()
+        .nth(10)
+        .map_or(text, |(end, _)| &text[..end])
 }
 
 impl fmt::Display for LicenseTexts {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let text = if self.text.len() > 10 {
-            &self.text[0..10]
-        } else {
-            &self.text
-        };
-        let comment = if self.comment.len() > 10 {
-            &self.comment[0..10]
-        } else {
-            &self.comment
-        };
-        let alt = match &self.alt {
-            Some(alt) if alt.len() > 10 => &alt[0..10],
-            Some(alt) => alt,
-            None => "N/A",
-        };
-        let interactive = match &self.interactive {
-            Some(interactive) if interactive.len() > 10 => &interactive[0..10],
-            Some(interactive) => interactive,
-            None => "N/A",
-        };
+        let text = preview(&self.text);
+        let comment = preview(&self.comment);
+        let alt = self.alt.as_deref().map_or("N/A", preview);
+        let interactive = self.interactive.as_deref().map_or("N/A", preview);
 
         write!(
             f,
@@ -1,21 +1,60 @@
-use super::LicenseTexts;
+use super::{LicenseAnswers, LicenseTexts, RenderError, reuse_comment_answered, static_text};
+use clap::ValueEnum;
+use std::sync::OnceLock;
+
+static MPL_HEADER: OnceLock<MplHeader> = OnceLock::new();
+
+/// The header MPL-2.0 adds to source files.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MplHeader {
+    /// The SPDX line followed by the notice from Exhibit A of the license,
+    /// as the MPL recommends.
+    #[default]
+    Exhibit,
+    /// Only the SPDX line.
+    Spdx,
+}
+
+/// Picks the header MPL-2.0 adds to source files.
+#[tracing::instrument]
+pub fn set_mpl_header(header: MplHeader) {
+    if MPL_HEADER.set(header).is_err() {
+        tracing::warn!("MPL header was already set");
+    }
+}
 
+/// The header set with [`set_mpl_header`], or the default.
 #[tracing::instrument]
-pub fn generate_mpl_license() -> LicenseTexts {
-    LicenseTexts {
-        text: MPL_TEXT.to_string(),
-        comment: MPL_COMMENT.to_string(),
+pub fn mpl_header() -> MplHeader {
+    MPL_HEADER.get().copied().unwrap_or_default()
+}
+
+#[tracing::instrument(skip(answers))]
+pub fn generate_mpl_license(answers: &LicenseAnswers) -> Result<LicenseTexts, RenderError> {
+    let options = &answers.options;
+    let comment = match options.mpl_header {
+        MplHeader::Exhibit => MPL_COMMENT,
+        MplHeader::Spdx => MPL_SPDX_COMMENT,
+    };
+    Ok(LicenseTexts {
+        text: static_text(MPL_TITLE, MPL_TEXT, options)?,
+        comment: reuse_comment_answered(comment.to_string(), answers)?,
         alt: None,
         interactive: None,
-    }
+        notice: None,
+    })
 }
 
+pub const MPL_SPDX_COMMENT: &str = "SPDX-License-Identifier: MPL-2.0";
+
 pub const MPL_COMMENT: &str = r#"SPDX-License-Identifier: MPL-2.0
 This Source Code Form is subject to the terms of the Mozilla Public
 License, v. 2.0. If a copy of the MPL was not distributed with this
 file, You can obtain one at https://mozilla.org/MPL/2.0/.
 "#;
 
+pub const MPL_TITLE: &str = "Mozilla Public License Version 2.0";
+
 pub const MPL_TEXT: &str = r#"Mozilla Public License Version 2.0
 ==================================
 
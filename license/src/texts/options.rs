@@ -0,0 +1,142 @@
+use super::{CopyrightYear, EuplLanguage, MplHeader, QuoteStyle, TemplateVar};
+use crate::license::Licenses;
+use std::fmt;
+
+/// Everything a license is rendered from: the answers and the `options`.
+/// Most licenses need the copyright `year` and `fullname`, and the other
+/// answers are only read by the licenses that ask for them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LicenseAnswers {
+    pub year: Option<CopyrightYear>,
+    pub fullname: Option<String>,
+    /// The project named in the `NOTICE` of Apache-2.0.
+    pub project: Option<String>,
+    /// The organization BSD-3-Clause-Attribution credits, and its website.
+    pub organization: Option<String>,
+    pub website: Option<String>,
+    /// What the program does, for CECILL-2.1.
+    pub purpose: Option<String>,
+    /// The program a GNU license is applied to, its version, and a short
+    /// description of it.
+    pub program: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    /// Whether the program is interactive, which adds the notice it should
+    /// print to the GPL and AGPL.
+    pub interactive: bool,
+    /// The copyright disclaimer of an organization for a GNU license.
+    pub signed_release: Option<SignedRelease>,
+    /// The secondary licenses EPL-2.0 may be distributed under.
+    pub secondary_licenses: Vec<Licenses>,
+    /// The linguistic version of the EUPL-1.2 noted in the header.
+    pub eupl_language: Option<EuplLanguage>,
+    /// The settings the license is rendered with.
+    pub options: RenderOptions,
+}
+
+impl LicenseAnswers {
+    /// The copyright year and holder, or the error naming the ones missing.
+    #[tracing::instrument(skip(self))]
+    pub fn copyright(&self) -> Result<(&CopyrightYear, &str), RenderError> {
+        match (&self.year, &self.fullname) {
+            (Some(year), Some(fullname)) => Ok((year, fullname)),
+            (year, fullname) => Err(RenderError::missing([
+                ("year", year.is_none()),
+                ("fullname", fullname.is_none()),
+            ])),
+        }
+    }
+}
+
+/// The copyright disclaimer an organization signs for a program released
+/// under a GNU license.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedRelease {
+    pub organization: String,
+    pub signer: String,
+    /// The signer's position within the organization.
+    pub position: String,
+    pub day: u8,
+    pub month: String,
+    pub year: u16,
+}
+
+/// The settings a license is rendered with, the same for every license.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Overrides the copyright line. It may contain `{year}` and `{name}`
+    /// placeholders.
+    pub copyright_format: Option<String>,
+    /// Appends "All rights reserved." to the copyright line.
+    pub all_rights_reserved: bool,
+    /// Renders a `NOTICE` file for licenses that expect one.
+    pub notice: bool,
+    /// Starts the header with an `SPDX-FileCopyrightText` line, as the
+    /// REUSE specification asks.
+    pub reuse: bool,
+    /// A handlebars template the license text is rendered from instead of
+    /// the built-in one.
+    pub template: Option<String>,
+    /// Extra variables for the license text template.
+    pub vars: Vec<TemplateVar>,
+    /// The quotes of the license texts.
+    pub quote_style: QuoteStyle,
+    /// The header MPL-2.0 adds to source files.
+    pub mpl_header: MplHeader,
+}
+
+/// Why a license could not be rendered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenderError {
+    /// The [`LicenseAnswers`] fields the license needs but had no answer.
+    Missing(Vec<&'static str>),
+    /// A [`LicenseAnswers`] field whose answer the license can't be
+    /// rendered with.
+    Invalid { field: &'static str, value: String },
+    /// A template variable that would shadow one of the license's own.
+    Variable(String),
+    /// A template that failed to compile or render.
+    Template(String),
+}
+
+impl RenderError {
+    /// The error for the `fields` flagged as missing.
+    #[tracing::instrument]
+    pub(crate) fn missing<const N: usize>(fields: [(&'static str, bool); N]) -> Self {
+        RenderError::Missing(
+            fields
+                .into_iter()
+                .filter_map(|(field, missing)| missing.then_some(field))
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderError::Missing(fields) => write!(f, "no answer for '{}'", fields.join("', '")),
+            RenderError::Invalid { field, value } => {
+                write!(f, "invalid answer for '{field}': {value}")
+            }
+            RenderError::Variable(key) => {
+                write!(f, "'{key}' is a built-in variable of this license")
+            }
+            RenderError::Template(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+impl From<handlebars::TemplateError> for RenderError {
+    fn from(e: handlebars::TemplateError) -> Self {
+        RenderError::Template(e.to_string())
+    }
+}
+
+impl From<handlebars::RenderError> for RenderError {
+    fn from(e: handlebars::RenderError) -> Self {
+        RenderError::Template(e.to_string())
+    }
+}
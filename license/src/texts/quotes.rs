@@ -0,0 +1,79 @@
+use super::{LicenseTexts, RenderOptions};
+use clap::ValueEnum;
+use std::sync::OnceLock;
+
+static QUOTE_STYLE: OnceLock<QuoteStyle> = OnceLock::new();
+
+/// The quote characters used in rendered license texts.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QuoteStyle {
+    /// Straight quotes (`"AS IS"`), as most licenses are published.
+    #[default]
+    Straight,
+    /// Typographic quotes (`“AS IS”`).
+    Curly,
+}
+
+/// Normalizes the quotes in every license text rendered from now on.
+#[tracing::instrument]
+pub fn set_quote_style(style: QuoteStyle) {
+    if QUOTE_STYLE.set(style).is_err() {
+        tracing::warn!("Quote style was already set");
+    }
+}
+
+/// The style set with [`set_quote_style`], straight by default.
+#[tracing::instrument]
+pub fn quote_style() -> QuoteStyle {
+    QUOTE_STYLE.get().copied().unwrap_or_default()
+}
+
+/// Applies the quote style in the options to the license texts. The
+/// comment header is left alone since it goes into source files.
+#[tracing::instrument(skip(texts, options))]
+pub fn apply_quote_style(texts: LicenseTexts, options: &RenderOptions) -> LicenseTexts {
+    let style = options.quote_style;
+    LicenseTexts {
+        text: normalize_quotes(&texts.text, style),
+        alt: texts.alt.map(|alt| normalize_quotes(&alt, style)),
+        interactive: texts
+            .interactive
+            .map(|interactive| normalize_quotes(&interactive, style)),
+        ..texts
+    }
+}
+
+/// Rewrites the quotes in `text` in the given style. A straight quote
+/// opens when it follows whitespace, an opening bracket, or another
+/// opening quote, and closes otherwise, so apostrophes become `’`.
+#[tracing::instrument(skip(text))]
+pub fn normalize_quotes(text: &str, style: QuoteStyle) -> String {
+    match style {
+        QuoteStyle::Straight => text
+            .chars()
+            .map(|c| match c {
+                '“' | '”' | '„' => '"',
+                '‘' | '’' => '\'',
+                c => c,
+            })
+            .collect(),
+        QuoteStyle::Curly => {
+            let mut curly = String::with_capacity(text.len());
+            let mut previous: Option<char> = None;
+            for c in text.chars() {
+                let opens = previous
+                    .is_none_or(|p| p.is_whitespace() || matches!(p, '(' | '[' | '{' | '“' | '‘'));
+                let c = match (c, opens) {
+                    ('"', true) => '“',
+                    ('"', false) => '”',
+                    ('\'', true) => '‘',
+                    ('\'', false) => '’',
+                    (c, _) => c,
+                };
+                curly.push(c);
+                previous = Some(c);
+            }
+            curly
+        }
+    }
+}
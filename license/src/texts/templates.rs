@@ -0,0 +1,149 @@
+use super::{RenderError, RenderOptions};
+use crate::ceprintln;
+use handlebars::Handlebars;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+static TEMPLATE_DIR: OnceLock<PathBuf> = OnceLock::new();
+static TEMPLATE_VARS: OnceLock<Vec<TemplateVar>> = OnceLock::new();
+
+/// The variable holding a license's title line, also set with '--title'.
+/// Unlike the other built-in variables, it may be overridden.
+pub const TITLE_VAR: &str = "title";
+
+/// An extra variable for the license templates, given as `key=value`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateVar {
+    pub key: String,
+    pub value: String,
+}
+
+impl FromStr for TemplateVar {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => Ok(TemplateVar {
+                key: key.trim().to_string(),
+                value: value.to_string(),
+            }),
+            _ => Err(format!("expected 'key=value', found '{s}'")),
+        }
+    }
+}
+
+/// Makes every license prefer a `<SPDX id>.hbs` template in `dir` over
+/// its built-in text.
+#[tracing::instrument]
+pub fn set_template_dir(dir: PathBuf) {
+    if TEMPLATE_DIR.set(dir).is_err() {
+        tracing::warn!("Template directory was already set");
+    }
+}
+
+/// Adds `vars` to the variables of every license text template.
+#[tracing::instrument]
+pub fn set_template_vars(vars: Vec<TemplateVar>) {
+    if TEMPLATE_VARS.set(vars).is_err() {
+        tracing::warn!("Template variables were already set");
+    }
+}
+
+/// The variables set with [`set_template_vars`].
+#[tracing::instrument]
+pub fn template_vars() -> Vec<TemplateVar> {
+    TEMPLATE_VARS.get().cloned().unwrap_or_default()
+}
+
+/// The handlebars context for a license text: the fields of `data` plus
+/// the variables in the options. Fails if a variable would shadow one of
+/// the fields other than [`TITLE_VAR`].
+#[tracing::instrument(skip(data, options))]
+pub fn with_vars<T: Serialize>(data: &T, options: &RenderOptions) -> Result<Value, RenderError> {
+    let mut context =
+        serde_json::to_value(data).map_err(|e| RenderError::Template(e.to_string()))?;
+    if let Some(fields) = context.as_object_mut() {
+        for var in &options.vars {
+            if var.key != TITLE_VAR && fields.contains_key(&var.key) {
+                return Err(RenderError::Variable(var.key.clone()));
+            }
+            fields.insert(var.key.clone(), Value::String(var.value.clone()));
+        }
+    }
+    Ok(context)
+}
+
+/// Reads the user's template for the license `id` from the directory set
+/// with [`set_template_dir`], if there is one.
+#[tracing::instrument]
+pub fn user_template(id: &str) -> Option<String> {
+    let path = TEMPLATE_DIR.get()?.join(format!("{id}.hbs"));
+    match fs::read_to_string(&path) {
+        Ok(template) => {
+            tracing::info!("Using template {}", path.display());
+            Some(template)
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => {
+            ceprintln!(
+                "<bold><red>Failed to read template {}</></>: {}",
+                path.display(),
+                e,
+            );
+            process::exit(1);
+        }
+    }
+}
+
+/// The template for the text of a license: the one in the options, or
+/// `builtin`.
+#[tracing::instrument(skip(builtin, options))]
+pub fn text_template<'a>(builtin: &'a str, options: &'a RenderOptions) -> &'a str {
+    options.template.as_deref().unwrap_or(builtin)
+}
+
+/// The title set with a [`TITLE_VAR`] variable, if any.
+#[tracing::instrument(skip(options))]
+fn title_override(options: &RenderOptions) -> Option<&str> {
+    options
+        .vars
+        .iter()
+        .rev()
+        .find(|var| var.key == TITLE_VAR)
+        .map(|var| var.value.as_str())
+}
+
+/// A built-in `text` with its `default` title replaced by the
+/// [`TITLE_VAR`] variable, for texts that don't render it themselves. A
+/// text without a title gets it on a line of its own before it.
+#[tracing::instrument(skip(text, options))]
+pub fn retitle(text: String, default: &str, options: &RenderOptions) -> String {
+    match title_override(options) {
+        None => text,
+        Some(title) if default.is_empty() => format!("{title}\n\n{text}"),
+        Some(title) => text.replacen(default, title, 1),
+    }
+}
+
+/// The text of a license whose built-in text has no placeholders: the
+/// template in the options rendered with its `title` and variables, or
+/// `builtin` with [`retitle`].
+#[tracing::instrument(skip(builtin, options))]
+pub fn static_text(
+    title: &str,
+    builtin: &str,
+    options: &RenderOptions,
+) -> Result<String, RenderError> {
+    let Some(template) = &options.template else {
+        return Ok(retitle(builtin.to_string(), title, options));
+    };
+    let mut context = Map::new();
+    context.insert(TITLE_VAR.to_string(), Value::String(title.to_string()));
+    Ok(Handlebars::new().render_template(template, &with_vars(&context, options)?)?)
+}
@@ -1,15 +1,19 @@
-use super::LicenseTexts;
+use super::{LicenseAnswers, LicenseTexts, RenderError, reuse_comment_answered, static_text};
 
-#[tracing::instrument]
-pub fn generate_unlicense_license() -> LicenseTexts {
-    LicenseTexts {
-        text: UNLICENSE.to_string(),
-        comment: "SPDX-License-Identifier: Unlicense".to_string(),
+#[tracing::instrument(skip(answers))]
+pub fn generate_unlicense_license(answers: &LicenseAnswers) -> Result<LicenseTexts, RenderError> {
+    let options = &answers.options;
+    Ok(LicenseTexts {
+        text: static_text(UNLICENSE_TITLE, UNLICENSE, options)?,
+        comment: reuse_comment_answered("SPDX-License-Identifier: Unlicense".to_string(), answers)?,
         alt: None,
         interactive: None,
-    }
+        notice: None,
+    })
 }
 
+pub const UNLICENSE_TITLE: &str = "";
+
 pub const UNLICENSE: &str = r#"This is free and unencumbered software released into the public domain.
 
 Anyone is free to copy, modify, publish, use, compile, sell, or
@@ -0,0 +1,69 @@
+use super::{
+    CopyrightYear, LicenseAnswers, LicenseTexts, RenderError, copyright_line, reuse_comment,
+    text_template, with_vars,
+};
+use handlebars::Handlebars;
+use serde::Serialize;
+
+#[tracing::instrument(skip(answers))]
+pub fn generate_wtfpl_license(answers: &LicenseAnswers) -> Result<LicenseTexts, RenderError> {
+    let options = &answers.options;
+    let (year, fullname) = answers.copyright()?;
+
+    let license = WtfplLicenseTemplate {
+        title: WTFPL_TITLE,
+        year: year.clone(),
+        fullname: fullname.to_string(),
+        copyright: copyright_line(COPYRIGHT, year, fullname, options),
+    };
+
+    let mut handlebars = Handlebars::new();
+    handlebars.register_template_string("wtfpl_license", text_template(WTFPL, options))?;
+    let text = handlebars.render("wtfpl_license", &with_vars(&license, options)?)?;
+
+    Ok(LicenseTexts {
+        text,
+        comment: reuse_comment(
+            "SPDX-License-Identifier: WTFPL".to_string(),
+            year,
+            fullname,
+            options,
+        ),
+        alt: None,
+        interactive: None,
+        notice: None,
+    })
+}
+
+/// The variables the license text is rendered with, including a
+/// `WTFPL.hbs` from '--template-dir'.
+#[derive(Serialize)]
+pub struct WtfplLicenseTemplate {
+    pub title: &'static str,
+    pub year: CopyrightYear,
+    pub fullname: String,
+    pub copyright: String,
+}
+
+pub const COPYRIGHT: &str = "Copyright (c) {year} {name}";
+
+/// The license must be copied verbatim, including its own copyright line,
+/// so the project's copyright goes above it.
+pub const WTFPL_TITLE: &str = "DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE";
+
+pub const WTFPL: &str = r#"{{{copyright}}}
+
+            {{{title}}}
+                    Version 2, December 2004
+
+ Copyright (C) 2004 Sam Hocevar <sam@hocevar.net>
+
+ Everyone is permitted to copy and distribute verbatim or modified
+ copies of this license document, and changing it is allowed as long
+ as the name is changed.
+
+            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+
+  0. You just DO WHAT THE FUCK YOU WANT TO.
+"#;
@@ -0,0 +1,62 @@
+use super::{
+    CopyrightYear, LicenseAnswers, LicenseTexts, RenderError, copyright_line, reuse_comment,
+    text_template, with_vars,
+};
+use handlebars::Handlebars;
+use serde::Serialize;
+
+#[tracing::instrument(skip(answers))]
+pub fn generate_0bsd_license(answers: &LicenseAnswers) -> Result<LicenseTexts, RenderError> {
+    let options = &answers.options;
+    let (year, fullname) = answers.copyright()?;
+
+    let license = ZeroBsdLicenseTemplate {
+        year: year.clone(),
+        fullname: fullname.to_string(),
+        copyright: copyright_line(COPYRIGHT, year, fullname, options),
+    };
+
+    let mut handlebars = Handlebars::new();
+    handlebars.register_template_string("0bsd_license", text_template(ZERO_BSD, options))?;
+    let text = handlebars.render("0bsd_license", &with_vars(&license, options)?)?;
+
+    Ok(LicenseTexts {
+        text,
+        comment: reuse_comment(
+            "SPDX-License-Identifier: 0BSD".to_string(),
+            year,
+            fullname,
+            options,
+        ),
+        alt: None,
+        interactive: None,
+        notice: None,
+    })
+}
+
+/// The variables the license text is rendered with, including a
+/// `0BSD.hbs` from '--template-dir'.
+#[derive(Serialize)]
+pub struct ZeroBsdLicenseTemplate {
+    pub year: CopyrightYear,
+    pub fullname: String,
+    pub copyright: String,
+}
+
+pub const COPYRIGHT: &str = "Copyright (C) {year} by {name}";
+
+pub const ZERO_BSD: &str = r#"{{#if title}}{{{title}}}
+
+{{/if}}{{{copyright}}}
+
+Permission to use, copy, modify, and/or distribute this software for any
+purpose with or without fee is hereby granted.
+
+THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+"#;
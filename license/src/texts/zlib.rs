@@ -0,0 +1,71 @@
+use super::{
+    CopyrightYear, LicenseAnswers, LicenseTexts, RenderError, copyright_line, reuse_comment,
+    text_template, with_vars,
+};
+use handlebars::Handlebars;
+use serde::Serialize;
+
+#[tracing::instrument(skip(answers))]
+pub fn generate_zlib_license(answers: &LicenseAnswers) -> Result<LicenseTexts, RenderError> {
+    let options = &answers.options;
+    let (year, fullname) = answers.copyright()?;
+
+    let license = ZlibLicenseTemplate {
+        title: ZLIB_TITLE,
+        year: year.clone(),
+        fullname: fullname.to_string(),
+        copyright: copyright_line(COPYRIGHT, year, fullname, options),
+    };
+
+    let mut handlebars = Handlebars::new();
+    handlebars.register_template_string("zlib_license", text_template(ZLIB, options))?;
+    let text = handlebars.render("zlib_license", &with_vars(&license, options)?)?;
+
+    Ok(LicenseTexts {
+        text,
+        comment: reuse_comment(
+            "SPDX-License-Identifier: Zlib".to_string(),
+            year,
+            fullname,
+            options,
+        ),
+        alt: None,
+        interactive: None,
+        notice: None,
+    })
+}
+
+/// The variables the license text is rendered with, including a
+/// `Zlib.hbs` from '--template-dir'.
+#[derive(Serialize)]
+pub struct ZlibLicenseTemplate {
+    pub title: &'static str,
+    pub year: CopyrightYear,
+    pub fullname: String,
+    pub copyright: String,
+}
+
+pub const COPYRIGHT: &str = "Copyright (c) {year} {name}";
+
+pub const ZLIB_TITLE: &str = "zlib License";
+
+pub const ZLIB: &str = r#"{{{title}}}
+
+{{{copyright}}}
+
+This software is provided 'as-is', without any express or implied
+warranty. In no event will the authors be held liable for any damages
+arising from the use of this software.
+
+Permission is granted to anyone to use this software for any purpose,
+including commercial applications, and to alter it and redistribute it
+freely, subject to the following restrictions:
+
+1. The origin of this software must not be misrepresented; you must not
+   claim that you wrote the original software. If you use this software
+   in a product, an acknowledgment in the product documentation would be
+   appreciated but is not required.
+2. Altered source versions must be plainly marked as such, and must not be
+   misrepresented as being the original software.
+3. This notice may not be removed or altered from any source distribution.
+"#;
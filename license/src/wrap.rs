@@ -0,0 +1,116 @@
+use crate::io::{COPYRIGHT_TEXT_MARKER, SPDX_MARKER};
+
+/// Re-flows `text` so no line is longer than `width` columns, unless a
+/// single word is. Paragraphs stay separated by their blank lines,
+/// copyright lines and list items start a new line, and SPDX tags and
+/// underlines keep a line of their own.
+#[tracing::instrument(skip(text))]
+pub fn reflow(text: &str, width: usize) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut paragraph: Vec<&str> = Vec::new();
+    for line in text.lines() {
+        let blank = line.trim().is_empty();
+        let alone = is_own_line(line);
+        if blank || alone || starts_block(line) {
+            fill(&paragraph, width, &mut out);
+            paragraph.clear();
+        }
+        match (blank, alone) {
+            (true, _) => out.push('\n'),
+            (false, true) => {
+                out.push_str(line.trim_end());
+                out.push('\n');
+            }
+            (false, false) => paragraph.push(line),
+        }
+    }
+    fill(&paragraph, width, &mut out);
+    if !text.ends_with('\n') {
+        out.pop();
+    }
+    out
+}
+
+/// Whether `line` is kept as it is, on a line of its own: an SPDX tag or
+/// an underline (e.g., `-----` or `=====`).
+#[tracing::instrument]
+fn is_own_line(line: &str) -> bool {
+    let line = line.trim();
+    line.contains(SPDX_MARKER) || line.starts_with(COPYRIGHT_TEXT_MARKER) || is_underline(line)
+}
+
+/// Whether `line` underlines the one before it (e.g., `-----` or `=====`).
+#[tracing::instrument]
+pub(crate) fn is_underline(line: &str) -> bool {
+    let line = line.trim();
+    line.len() >= 3
+        && line
+            .chars()
+            .all(|c| matches!(c, '-' | '=' | '*' | '_' | '~'))
+}
+
+/// Whether `line` must start a new line rather than continue the one
+/// before it.
+#[tracing::instrument]
+pub(crate) fn starts_block(line: &str) -> bool {
+    let line = line.trim_start();
+    if line.starts_with("Copyright") {
+        return true;
+    }
+    let Some(marker) = line.split_whitespace().next() else {
+        return false;
+    };
+    let item = marker
+        .strip_prefix('(')
+        .and_then(|item| item.strip_suffix(')'))
+        .or_else(|| marker.strip_suffix(['.', ')']));
+    matches!(marker, "-" | "*" | "•") || item.is_some_and(is_list_item)
+}
+
+/// Whether `item` numbers a list item: a section number, a single letter,
+/// or a lowercase Roman numeral (e.g., `1`, `3.2`, `b`, or `iv`).
+#[tracing::instrument]
+fn is_list_item(item: &str) -> bool {
+    let numbered = item.starts_with(|c: char| c.is_ascii_digit())
+        && item.len() <= 6
+        && item.chars().all(|c| c.is_ascii_digit() || c == '.');
+    let lettered = item.chars().count() == 1 && item.chars().all(char::is_alphabetic);
+    let roman =
+        !item.is_empty() && item.len() <= 4 && item.chars().all(|c| matches!(c, 'i' | 'v' | 'x'));
+    numbered || lettered || roman
+}
+
+/// Appends the words of `lines` to `out`, filled to `width` columns. The
+/// first line keeps its indentation, and the rest take the indentation
+/// of the paragraph's second line.
+#[tracing::instrument(skip(out))]
+fn fill(lines: &[&str], width: usize, out: &mut String) {
+    let Some(first) = lines.first() else {
+        return;
+    };
+    let first_indent = indentation(first);
+    let rest_indent = lines.get(1).map_or(first_indent, |line| indentation(line));
+    let mut line = first_indent.to_string();
+    let mut empty = true;
+    for word in lines.iter().flat_map(|line| line.split_whitespace()) {
+        if !empty && line.chars().count() + 1 + word.chars().count() > width {
+            out.push_str(&line);
+            out.push('\n');
+            line = rest_indent.to_string();
+            empty = true;
+        }
+        if !empty {
+            line.push(' ');
+        }
+        line.push_str(word);
+        empty = false;
+    }
+    out.push_str(&line);
+    out.push('\n');
+}
+
+/// The leading whitespace of `line`.
+#[tracing::instrument]
+fn indentation(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
@@ -11,6 +11,8 @@ use std::process::Command as StdCommand;
 // TODO: Write tests for all license types:
 // - [x] Apache-2.0
 // - [x] MIT
+// - [x] BSD-2-Clause
+// - [x] BSD-2-Clause-Patent
 // - [x] BSD-3-Clause
 // - [x] BSD-3-Clause-Modification
 // - [x] BSD-3-Clause-No-Military-License
@@ -23,6 +25,11 @@ use std::process::Command as StdCommand;
 // - [x] CDDL-1.0
 // - [ ] EPL-2.0
 // - [x] BSL-1.0
+// - [x] Zlib
+// - [x] 0BSD
+// - [x] CECILL-2.1
+// - [x] WTFPL
+// - [x] EUPL-1.2
 
 #[test]
 fn test_cli_help() {
@@ -43,6 +50,24 @@ fn test_cli_version() {
         .stdout(predicate::str::contains(env!("CARGO_PKG_VERSION")));
 }
 
+#[test]
+fn test_cli_renamed_binary() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let renamed = temp.child(format!("licensegen{}", std::env::consts::EXE_SUFFIX));
+    std::fs::copy(cargo_bin!("license"), renamed.path()).unwrap();
+    AssertCommand::new(renamed.path())
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("licensegen "));
+    AssertCommand::new(renamed.path())
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Usage: licensegen"));
+    temp.close().unwrap();
+}
+
 fn setup_test_env() -> assert_fs::TempDir {
     let temp = assert_fs::TempDir::new().unwrap();
     let py_file = temp.child("python/__init__.py");
@@ -54,12 +79,17 @@ fn setup_test_env() -> assert_fs::TempDir {
     temp
 }
 
-const BASIC_LICENSES: [&str; 5] = [
+const BASIC_LICENSES: [&str; 10] = [
+    "BSD-2-Clause",
     "BSD-3-Clause",
     "BSD-3-Clause-Modification",
     "BSD-3-Clause-No-Military-License",
     "Apache-2.0",
     "MIT",
+    "Zlib",
+    "0BSD",
+    "CC-BY-4.0",
+    "WTFPL",
 ];
 
 #[derive(Debug, Clone, Copy)]
@@ -70,7 +100,8 @@ enum Lang {
 
 fn gen_cmd<P: AsRef<std::path::Path>>(lang: Lang, path: P, license: &str) -> StdCommand {
     let mut cmd = StdCommand::new(cargo_bin!("license"));
-    cmd.current_dir(path)
+    cmd.current_dir(&path)
+        .env("XDG_CONFIG_HOME", path.as_ref().join(".config"))
         .arg("--source-path")
         .arg(match lang {
             Lang::Python => "python/",
@@ -93,7 +124,8 @@ fn gen_cmd<P: AsRef<std::path::Path>>(lang: Lang, path: P, license: &str) -> Std
 
 fn gen_assert_cmd<P: AsRef<std::path::Path>>(lang: Lang, path: P, license: &str) -> AssertCommand {
     let mut cmd = AssertCommand::new(cargo_bin!("license"));
-    cmd.current_dir(path)
+    cmd.current_dir(&path)
+        .env("XDG_CONFIG_HOME", path.as_ref().join(".config"))
         .arg("--source-path")
         .arg(match lang {
             Lang::Python => "python/",
@@ -115,15 +147,14 @@ fn gen_assert_cmd<P: AsRef<std::path::Path>>(lang: Lang, path: P, license: &str)
 }
 
 fn spawn_session(cmd: StdCommand) -> rexpect::session::PtySession {
-    let session = spawn_with_options(
+    spawn_with_options(
         cmd,
         Options {
-            timeout_ms: Some(115),
+            timeout_ms: Some(5_000),
             strip_ansi_escape_codes: true,
         },
     )
-    .unwrap();
-    session
+    .unwrap()
 }
 
 fn basic_interact(session: &mut rexpect::session::PtySession) {
@@ -193,15 +224,53 @@ fn assert_files(
 fn test_basic_licenses(lang: Lang) {
     for license in BASIC_LICENSES.iter() {
         let temp = run_basic_gen(lang, license);
-        let copyright = if license == &"Apache-2.0" {
-            Location::Source("Copyright 2025 Your Name")
-        } else {
-            Location::License("Copyright (c) 2025 Your Name")
+        let copyright = match *license {
+            "Apache-2.0" => Location::Source("Copyright 2025 Your Name"),
+            "0BSD" => Location::License("Copyright (C) 2025 by Your Name"),
+            _ => Location::License("Copyright (c) 2025 Your Name"),
         };
         let (license_file, _) = assert_files(&temp, lang, license, Some(copyright));
         if license == &"Apache-2.0" {
             license_file.assert(predicate::str::contains("Apache License"));
         }
+        if license == &"BSD-2-Clause" {
+            license_file.assert(predicate::str::contains("Neither the name").not());
+        }
+        if license == &"Zlib" {
+            license_file.assert(predicate::str::contains("zlib License"));
+        }
+        if license == &"WTFPL" {
+            license_file.assert(predicate::str::contains(
+                "0. You just DO WHAT THE FUCK YOU WANT TO.",
+            ));
+        }
+        if license == &"CC-BY-4.0" {
+            license_file.assert(predicate::str::contains(
+                "Attribution 4.0 International Public License",
+            ));
+        }
+        temp.close().unwrap();
+    }
+}
+
+#[test]
+fn test_bsd2_patent_license() {
+    for lang in [Lang::Python, Lang::Rust] {
+        let temp = run_basic_gen(lang, "BSD-2-Clause-Patent");
+        let (license_file, _) = assert_files(
+            &temp,
+            lang,
+            "BSD-2-Clause-Patent",
+            Some(Location::License("Copyright (c) 2025 Your Name")),
+        );
+        license_file
+            .assert(predicate::str::contains(
+                "each copyright\nholder and contributor hereby grants to those receiving rights under\nthis license a perpetual",
+            ))
+            .assert(predicate::str::contains(
+                "expressly, by implication, estoppel or otherwise.\n\nDISCLAIMER\n\nTHIS SOFTWARE",
+            ))
+            .assert(predicate::str::contains("Neither the name").not());
         temp.close().unwrap();
     }
 }
@@ -216,7 +285,7 @@ fn test_basic_licenses_rust() {
     test_basic_licenses(Lang::Rust);
 }
 
-const GNU_LICENSES: [&str; 9] = [
+const GNU_LICENSES: [&str; 15] = [
     "AGPL-3.0",
     "AGPL-3.0-only",
     "AGPL-3.0-or-later",
@@ -226,6 +295,12 @@ const GNU_LICENSES: [&str; 9] = [
     "LGPL-3.0",
     "LGPL-3.0-only",
     "LGPL-3.0-or-later",
+    "GPL-2.0",
+    "GPL-2.0-only",
+    "GPL-2.0-or-later",
+    "LGPL-2.1",
+    "LGPL-2.1-only",
+    "LGPL-2.1-or-later",
 ];
 
 fn run_gnu_gen(lang: Lang, license: &str) -> assert_fs::TempDir {
@@ -333,7 +408,7 @@ fn test_gnu_licenses_rust() {
     }
 }
 
-const PLAIN_LICENSES: [&str; 4] = ["MPL-2.0", "CDDL-1.0", "Unlicense", "BSL-1.0"];
+const PLAIN_LICENSES: [&str; 5] = ["MPL-2.0", "CDDL-1.0", "Unlicense", "BSL-1.0", "CC0-1.0"];
 
 fn test_plain(lang: Lang, license: &str) {
     let temp = setup_test_env();
@@ -359,6 +434,104 @@ fn test_plain_licenses_rust() {
     }
 }
 
+fn run_cecill_gen(lang: Lang) -> assert_fs::TempDir {
+    let temp = setup_test_env();
+    let cmd = gen_cmd(lang, temp.path(), "CECILL-2.1");
+    let mut session = spawn_session(cmd);
+    session.exp_string("Enter the copyright year:").unwrap();
+    session.send_line("2025").unwrap();
+    session
+        .exp_string("Enter the full name of the copyright holder:")
+        .unwrap();
+    session.send_line("Your Name").unwrap();
+    session
+        .exp_string("Enter the purpose of the program (e.g., manage license files):")
+        .unwrap();
+    session.send_line("manage license files").unwrap();
+    session.exp_eof().unwrap();
+
+    temp
+}
+
+fn test_cecill(lang: Lang) {
+    let temp = run_cecill_gen(lang);
+    let (license_file, source_file) = assert_files(
+        &temp,
+        lang,
+        "CECILL-2.1",
+        Some(Location::Source("Copyright or © or Copr. Your Name (2025)")),
+    );
+    license_file.assert(predicate::str::starts_with(
+        "CeCILL FREE SOFTWARE LICENSE AGREEMENT",
+    ));
+    source_file.assert(predicate::str::contains(
+        "whose purpose is to manage license files.",
+    ));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_cecill_license_python() {
+    test_cecill(Lang::Python);
+}
+
+#[test]
+fn test_cecill_license_rust() {
+    test_cecill(Lang::Rust);
+}
+
+fn run_eupl_gen(lang: Lang) -> assert_fs::TempDir {
+    let temp = setup_test_env();
+    let cmd = gen_cmd(lang, temp.path(), "EUPL-1.2");
+    let mut session = spawn_session(cmd);
+    session
+        .exp_string(
+            "Enter the linguistic version of the EUPL to note (e.g., EN, FR, or DE) (optional):",
+        )
+        .unwrap();
+    session.send_line("fr").unwrap();
+    session.exp_eof().unwrap();
+
+    temp
+}
+
+fn test_eupl(lang: Lang) {
+    let temp = run_eupl_gen(lang);
+    let (license_file, _) = assert_files(
+        &temp,
+        lang,
+        "EUPL-1.2",
+        Some(Location::Source(
+            "Licensed under the EUPL-1.2, in its French linguistic version.",
+        )),
+    );
+    license_file.assert(predicate::str::starts_with(
+        "EUROPEAN UNION PUBLIC LICENCE v. 1.2",
+    ));
+    temp.close().unwrap();
+
+    let temp = setup_test_env();
+    gen_assert_cmd(lang, temp.path(), "EUPL-1.2")
+        .args(["--language", "DE"])
+        .assert()
+        .success();
+    let (_, source_file) = assert_files(&temp, lang, "EUPL-1.2", None);
+    source_file.assert(predicate::str::contains(
+        "in its German linguistic version.",
+    ));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_eupl_license_python() {
+    test_eupl(Lang::Python);
+}
+
+#[test]
+fn test_eupl_license_rust() {
+    test_eupl(Lang::Rust);
+}
+
 fn run_attribution_gen(lang: Lang) -> assert_fs::TempDir {
     let temp = setup_test_env();
     let cmd = gen_cmd(lang, temp.path(), "BSD-3-Clause-Attribution");
@@ -451,3 +624,2889 @@ fn test_epl_license_python() {
 fn test_epl_license_rust() {
     test_epl(Lang::Rust);
 }
+
+fn setup_mixed_env() -> assert_fs::TempDir {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/__init__.py")
+        .write_file(Path::new("tests/data/__init__.py"))
+        .unwrap();
+    temp.child("src/main.rs")
+        .write_file(Path::new("tests/data/main.rs"))
+        .unwrap();
+    temp.child("src/notes.xyz").write_str("notes\n").unwrap();
+    temp
+}
+
+#[test]
+fn test_detect_comment_from_extension() {
+    let temp = setup_mixed_env();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--add-comment")
+        .arg("MPL-2.0")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Skipping"));
+    temp.child("src/__init__.py")
+        .assert(predicate::str::starts_with(
+            "# SPDX-License-Identifier: MPL-2.0",
+        ));
+    temp.child("src/main.rs")
+        .assert(predicate::str::starts_with(
+            "// SPDX-License-Identifier: MPL-2.0",
+        ));
+    temp.child("src/notes.xyz").assert("notes\n");
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_comment_map() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/app.conf").write_str("key = 1\n").unwrap();
+    temp.child("src/init.lua").write_str("print(1)\n").unwrap();
+    temp.child("src/vendor/old.lua")
+        .write_str("print(2)\n")
+        .unwrap();
+    temp.child("src/notes.txt").write_str("Notes\n").unwrap();
+    temp.child("comments.map")
+        .write_str(
+            "# Comment tokens by path\n\"*.conf\" = \"#\"\nvendor/*.lua = \";;\"\n*.lua = \"--\"\n",
+        )
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--add-comment")
+        .arg("--comment")
+        .arg("//")
+        .arg("--comment-map")
+        .arg("comments.map")
+        .arg("Unlicense")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Added license headers to 4 file(s)",
+        ));
+    temp.child("src/app.conf")
+        .assert("# SPDX-License-Identifier: Unlicense\nkey = 1\n");
+    temp.child("src/init.lua")
+        .assert("-- SPDX-License-Identifier: Unlicense\nprint(1)\n");
+    temp.child("src/vendor/old.lua")
+        .assert(";; SPDX-License-Identifier: Unlicense\nprint(2)\n");
+    temp.child("src/notes.txt")
+        .assert("// SPDX-License-Identifier: Unlicense\nNotes\n");
+
+    temp.child("bad.map").write_str("*.lua\n").unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--comment-map")
+        .arg("bad.map")
+        .arg("Unlicense")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("bad.map: line 1"));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_exclude() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/main.rs")
+        .write_str("fn main() {}\n")
+        .unwrap();
+    temp.child("src/vendor/dep.rs")
+        .write_str("fn dep() {}\n")
+        .unwrap();
+    temp.child("src/gen.generated.rs")
+        .write_str("fn generated() {}\n")
+        .unwrap();
+    temp.child("src/.hidden.rs")
+        .write_str("fn hidden() {}\n")
+        .unwrap();
+    temp.child("src/logo.png").write_str("PNG\n").unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--add-comment")
+        .arg("--exclude")
+        .arg("vendor")
+        .arg("--exclude")
+        .arg("*.generated.rs")
+        .arg("Unlicense")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Added license headers to 1 file(s)",
+        ));
+    temp.child("src/main.rs")
+        .assert("// SPDX-License-Identifier: Unlicense\nfn main() {}\n");
+    temp.child("src/vendor/dep.rs").assert("fn dep() {}\n");
+    temp.child("src/gen.generated.rs")
+        .assert("fn generated() {}\n");
+    temp.child("src/.hidden.rs").assert("fn hidden() {}\n");
+    temp.child("src/logo.png").assert("PNG\n");
+
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--exclude")
+        .arg("[")
+        .arg("Unlicense")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid exclude pattern"));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_gitignore() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    StdCommand::new("git")
+        .arg("init")
+        .arg("--quiet")
+        .current_dir(temp.path())
+        .status()
+        .unwrap();
+    temp.child(".gitignore")
+        .write_str("target/\n*.log\n")
+        .unwrap();
+    temp.child("src/.gitignore")
+        .write_str("generated.rs\n")
+        .unwrap();
+    temp.child("src/main.rs")
+        .write_str("fn main() {}\n")
+        .unwrap();
+    temp.child("src/generated.rs")
+        .write_str("fn generated() {}\n")
+        .unwrap();
+    temp.child("src/target/out.rs")
+        .write_str("fn out() {}\n")
+        .unwrap();
+    temp.child("src/debug.log").write_str("debug\n").unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--add-comment")
+        .arg("Unlicense")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Added license headers to 1 file(s)",
+        ))
+        .stdout(predicate::str::contains(
+            "Ignored 3 path(s) listed in .gitignore.",
+        ));
+    temp.child("src/generated.rs").assert("fn generated() {}\n");
+    temp.child("src/target/out.rs").assert("fn out() {}\n");
+
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--add-comment")
+        .arg("--no-ignore")
+        .arg("--exclude")
+        .arg("*.log")
+        .arg("Unlicense")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Added license headers to 2 file(s)",
+        ))
+        .stdout(predicate::str::contains("Ignored").not());
+    temp.child("src/target/out.rs")
+        .assert("// SPDX-License-Identifier: Unlicense\nfn out() {}\n");
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_skip_binary_files() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/main.rs")
+        .write_str("fn main() {}\n")
+        .unwrap();
+    let blob = b"\x7fELF\x00\x01\x02\nfn\n";
+    temp.child("src/blob.rs").write_binary(blob).unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--add-comment")
+        .arg("Unlicense")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Added license headers to 1 file(s)",
+        ))
+        .stderr(predicate::str::contains("Skipping src/blob.rs"))
+        .stderr(predicate::str::contains("binary file"));
+    temp.child("src/blob.rs").assert(blob.as_slice());
+    temp.child("src/main.rs")
+        .assert("// SPDX-License-Identifier: Unlicense\nfn main() {}\n");
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_verify_comments() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/main.rs")
+        .write_str("fn main() {}\n")
+        .unwrap();
+    temp.child("src/config.yaml").write_str("key: 1\n").unwrap();
+    temp.child("src/data.json").write_str("{}\n").unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--add-comment")
+        .arg("--comment")
+        .arg("//")
+        .arg("--verify-comments")
+        .arg("Unlicense")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Header may break src/config.yaml"))
+        .stderr(predicate::str::contains(
+            "`//` does not start a comment in .yaml files (expected `#`)",
+        ))
+        .stderr(predicate::str::contains("Header may break src/data.json"))
+        .stderr(predicate::str::contains(
+            ".json files do not support comments",
+        ))
+        .stderr(predicate::str::contains("main.rs").not());
+    temp.child("src/config.yaml")
+        .assert("// SPDX-License-Identifier: Unlicense\nkey: 1\n");
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_default_comment_fallback() {
+    let temp = setup_mixed_env();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--add-comment")
+        .arg("--default-comment")
+        .arg(";;")
+        .arg("MPL-2.0")
+        .assert()
+        .success();
+    temp.child("src/__init__.py")
+        .assert(predicate::str::starts_with(
+            "# SPDX-License-Identifier: MPL-2.0",
+        ));
+    temp.child("src/notes.xyz")
+        .assert(predicate::str::starts_with(
+            ";; SPDX-License-Identifier: MPL-2.0",
+        ));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_from_cargo_dual_license() {
+    let temp = setup_test_env();
+    temp.child("Cargo.toml")
+        .write_str("[package]\nname = \"demo\"\nlicense = \"MPL-2.0 OR Unlicense\"\n")
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--source-path")
+        .arg("rust/")
+        .arg("--add-comment")
+        .arg("--from-cargo")
+        .assert()
+        .success();
+    temp.child("LICENSE-MPL-2.0")
+        .assert(predicate::str::contains(
+            "Mozilla Public License Version 2.0",
+        ));
+    temp.child("LICENSE-UNLICENSE")
+        .assert(predicate::str::contains(
+            "This is free and unencumbered software",
+        ));
+    temp.child("rust/main.rs")
+        .assert(predicate::str::starts_with(
+            "// SPDX-License-Identifier: MPL-2.0 OR Unlicense",
+        ));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_license_text() {
+    let temp = setup_test_env();
+    temp.child("custom.txt")
+        .write_str("The Custom License\n\nDo what you want.\n")
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--source-path")
+        .arg("rust/")
+        .arg("--add-comment")
+        .arg("--license-text")
+        .arg("custom.txt")
+        .arg("--spdx")
+        .arg("LicenseRef-Custom")
+        .assert()
+        .success();
+    temp.child("LICENSE.txt")
+        .assert("The Custom License\n\nDo what you want.\n");
+    temp.child("rust/main.rs")
+        .assert(predicate::str::starts_with(
+            "// SPDX-License-Identifier: LicenseRef-Custom\n",
+        ));
+
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--license-text")
+        .arg("custom.txt")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--spdx-expression"));
+    temp.close().unwrap();
+}
+
+#[cfg(not(feature = "network"))]
+#[test]
+fn test_license_text_url_needs_network_feature() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--license-text")
+        .arg("https://example.com/LICENSE")
+        .arg("--spdx")
+        .arg("LicenseRef-Custom")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("needs the 'network' feature"));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_spdx_expression_dual_license() {
+    let temp = setup_test_env();
+    let mut cmd = StdCommand::new(cargo_bin!("license"));
+    cmd.current_dir(temp.path())
+        .env("XDG_CONFIG_HOME", temp.path().join(".config"))
+        .arg("--source-path")
+        .arg("rust/")
+        .arg("--add-comment")
+        .arg("--spdx-expression")
+        .arg("MIT OR Apache-2.0");
+    let mut session = spawn_session(cmd);
+    session.exp_string("Enter the copyright year:").unwrap();
+    session.send_line("2025").unwrap();
+    session
+        .exp_string("Enter the full name of the copyright holder:")
+        .unwrap();
+    session.send_line("Your Name").unwrap();
+    session
+        .exp_string("Enter the copyright year [2025]:")
+        .unwrap();
+    session.send_line("").unwrap();
+    session
+        .exp_string("Enter the full name of the copyright holder [Your Name]:")
+        .unwrap();
+    session.send_line("").unwrap();
+    session.exp_eof().unwrap();
+    temp.child("LICENSE-MIT")
+        .assert(predicate::str::contains("Copyright (c) 2025 Your Name"));
+    temp.child("LICENSE-APACHE")
+        .assert(predicate::str::contains("Apache License"));
+    temp.child("LICENSE.txt").assert(predicate::path::missing());
+    temp.child("rust/main.rs")
+        .assert(predicate::str::starts_with(
+            "// SPDX-License-Identifier: MIT OR Apache-2.0\n",
+        ));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_select_licenses() {
+    let temp = setup_test_env();
+    let mut cmd = StdCommand::new(cargo_bin!("license"));
+    cmd.current_dir(temp.path())
+        .env("XDG_CONFIG_HOME", temp.path().join(".config"))
+        .arg("--source-path")
+        .arg("rust/")
+        .arg("--add-comment")
+        .arg("--select");
+    let mut session = spawn_session(cmd);
+    session.exp_string("Unlicense (The Unlicense)").unwrap();
+    session.exp_string("Select the licenses to offer").unwrap();
+    session.send_line("0").unwrap();
+    session
+        .exp_string("Please enter one or more numbers between 1 and")
+        .unwrap();
+    session.exp_string("Select the licenses to offer").unwrap();
+    session.send_line("18, 19").unwrap();
+    session
+        .exp_string("Must users comply with all of the selected licenses at once?")
+        .unwrap();
+    session.send_line("n").unwrap();
+    session.exp_eof().unwrap();
+    temp.child("LICENSE-BSL-1.0")
+        .assert(predicate::str::contains("Boost Software License"));
+    temp.child("LICENSE-UNLICENSE")
+        .assert(predicate::str::contains("unencumbered"));
+    temp.child("rust/main.rs")
+        .assert(predicate::str::starts_with(
+            "// SPDX-License-Identifier: BSL-1.0 OR Unlicense\n",
+        ));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_json_summary() {
+    let temp = setup_test_env();
+    let assert = AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .env("XDG_CONFIG_HOME", temp.path().join(".config"))
+        .arg("--source-path")
+        .arg("rust/")
+        .arg("--add-comment")
+        .arg("--write-index")
+        .arg("--format")
+        .arg("json")
+        .arg("--spdx-expression")
+        .arg("BSL-1.0 OR Unlicense")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Added license headers to 1 file(s)",
+        ));
+    let summary: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+    assert_eq!(
+        summary,
+        serde_json::json!({
+            "expression": "BSL-1.0 OR Unlicense",
+            "licenses": [
+                {"id": "BSL-1.0", "output": "LICENSE-BSL-1.0", "alt": false, "interactive": false},
+                {"id": "Unlicense", "output": "LICENSE-UNLICENSE", "alt": false, "interactive": false},
+            ],
+            "modified": ["rust/main.rs"],
+            "index": "LICENSES.toml",
+        })
+    );
+
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--format")
+        .arg("json")
+        .arg("--output")
+        .arg("-")
+        .arg("Unlicense")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("needs stdout for the summary"));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_write_index() {
+    let temp = setup_test_env();
+    temp.child("Cargo.toml")
+        .write_str("[package]\nname = \"demo\"\nlicense = \"MPL-2.0/Unlicense\"\n")
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--source-path")
+        .arg("rust/")
+        .arg("--add-comment")
+        .arg("--write-index")
+        .arg("--from-cargo")
+        .assert()
+        .success();
+    temp.child("LICENSES.toml").assert(
+        "expression = \"MPL-2.0 OR Unlicense\"\n\n[[licenses]]\nid = \"MPL-2.0\"\nfile = \"LICENSE-MPL-2.0\"\n\n[[licenses]]\nid = \"Unlicense\"\nfile = \"LICENSE-UNLICENSE\"\n",
+    );
+
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--source-path")
+        .arg(".")
+        .arg("--add-comment")
+        .arg("--write-index")
+        .arg("MPL-2.0")
+        .assert()
+        .success();
+    temp.child("LICENSES.toml")
+        .assert(predicate::str::contains("file = \"LICENSE.txt\""))
+        .assert(predicate::str::contains("SPDX").not());
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_from_cargo_missing_license() {
+    let temp = setup_test_env();
+    temp.child("Cargo.toml")
+        .write_str("[package]\nname = \"demo\"\n")
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--from-cargo")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No license field found"));
+    temp.close().unwrap();
+}
+
+fn setup_nested_env() -> assert_fs::TempDir {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/main.rs")
+        .write_file(Path::new("tests/data/main.rs"))
+        .unwrap();
+    temp.child("src/foo/bar.rs")
+        .write_file(Path::new("tests/data/main.rs"))
+        .unwrap();
+    temp.child("src/foo/baz/qux.rs")
+        .write_file(Path::new("tests/data/main.rs"))
+        .unwrap();
+    temp
+}
+
+#[test]
+fn test_recurse_into_subdirectories() {
+    let temp = setup_nested_env();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--add-comment")
+        .arg("MPL-2.0")
+        .assert()
+        .success();
+    for file in ["src/main.rs", "src/foo/bar.rs", "src/foo/baz/qux.rs"] {
+        temp.child(file).assert(predicate::str::starts_with(
+            "// SPDX-License-Identifier: MPL-2.0",
+        ));
+    }
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_recurse_max_depth() {
+    let temp = setup_nested_env();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--add-comment")
+        .arg("--max-depth")
+        .arg("1")
+        .arg("MPL-2.0")
+        .assert()
+        .success();
+    for file in ["src/main.rs", "src/foo/bar.rs"] {
+        temp.child(file).assert(predicate::str::starts_with(
+            "// SPDX-License-Identifier: MPL-2.0",
+        ));
+    }
+    temp.child("src/foo/baz/qux.rs")
+        .assert(predicate::path::eq_file(Path::new("tests/data/main.rs")));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_skip_license_files_in_source_path() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("main.rs").write_str("fn main() {}\n").unwrap();
+    temp.child("COPYING.LIB")
+        .write_str("old license\n")
+        .unwrap();
+    temp.child("LEGAL").write_str("previous output\n").unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--source-path")
+        .arg(".")
+        .arg("--add-comment")
+        .arg("--default-comment")
+        .arg("#")
+        .arg("--output")
+        .arg("LEGAL")
+        .arg("MPL-2.0")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Added license headers to 1 file(s)",
+        ));
+    temp.child("main.rs").assert(predicate::str::starts_with(
+        "// SPDX-License-Identifier: MPL-2.0",
+    ));
+    temp.child("COPYING.LIB").assert("old license\n");
+    temp.child("LEGAL")
+        .assert(predicate::str::contains("SPDX-License-Identifier").not());
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_save_and_replay_answers() {
+    let temp = setup_test_env();
+    let mut cmd = gen_cmd(Lang::Rust, temp.path(), "MIT");
+    cmd.arg("--save-answers").arg("answers.toml");
+    let mut session = spawn_session(cmd);
+    basic_interact(&mut session);
+    temp.child("answers.toml")
+        .assert(predicate::str::contains(
+            "prompt = \"Enter the copyright year\"",
+        ))
+        .assert(predicate::str::contains("value = \"2025\""));
+
+    let replay = setup_test_env();
+    replay
+        .child("answers.toml")
+        .write_file(temp.child("answers.toml").path())
+        .unwrap();
+    gen_assert_cmd(Lang::Rust, replay.path(), "MIT")
+        .arg("--answers")
+        .arg("answers.toml")
+        .assert()
+        .success();
+    assert_files(
+        &replay,
+        Lang::Rust,
+        "MIT",
+        Some(Location::License("Copyright (c) 2025 Your Name")),
+    );
+    temp.close().unwrap();
+    replay.close().unwrap();
+}
+
+#[test]
+fn test_answers_are_not_logged_by_default() {
+    let temp = setup_test_env();
+    temp.child("answers.toml")
+        .write_str("[[answers]]\nprompt = \"Enter the copyright year\"\nvalue = \"2025\"\n\n[[answers]]\nprompt = \"Enter the full name of the copyright holder\"\nvalue = \"Private Person\"\n")
+        .unwrap();
+    gen_assert_cmd(Lang::Rust, temp.path(), "BSD-3-Clause")
+        .arg("--answers")
+        .arg("answers.toml")
+        .arg("-vvvv")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Answered prompt").not())
+        .stdout(predicate::str::contains("Private Person").not());
+    gen_assert_cmd(Lang::Rust, temp.path(), "BSD-3-Clause")
+        .arg("--answers")
+        .arg("answers.toml")
+        .arg("--log-answers")
+        .arg("-vvvv")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Answered prompt"))
+        .stdout(predicate::str::contains("Private Person"));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_remember_previous_answers() {
+    let temp = setup_test_env();
+    let mut session = spawn_session(gen_cmd(Lang::Rust, temp.path(), "MIT"));
+    basic_interact(&mut session);
+    temp.child(".config/license-gen/last.toml")
+        .assert(predicate::str::contains("year = \"2025\""));
+
+    let mut session = spawn_session(gen_cmd(Lang::Rust, temp.path(), "MIT"));
+    session
+        .exp_string("Enter the copyright year [2025]:")
+        .unwrap();
+    session.send_line("").unwrap();
+    session
+        .exp_string("Enter the full name of the copyright holder [Your Name]:")
+        .unwrap();
+    session.send_line("").unwrap();
+    session.exp_eof().unwrap();
+    assert_files(
+        &temp,
+        Lang::Rust,
+        "MIT",
+        Some(Location::License("Copyright (c) 2025 Your Name")),
+    );
+
+    let mut cmd = gen_cmd(Lang::Rust, temp.path(), "MIT");
+    cmd.arg("--no-remember");
+    let mut session = spawn_session(cmd);
+    basic_interact(&mut session);
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_year_range() {
+    let temp = setup_test_env();
+    let mut cmd = gen_cmd(Lang::Rust, temp.path(), "BSD-3-Clause");
+    cmd.arg("--year").arg("2019-2025");
+    let mut session = spawn_session(cmd);
+    session
+        .exp_string("Enter the full name of the copyright holder:")
+        .unwrap();
+    session.send_line("Your Name").unwrap();
+    session.exp_eof().unwrap();
+    temp.child("LICENSE.rust.txt")
+        .assert(predicate::str::contains(
+            "Copyright (c) 2019-2025 Your Name.",
+        ));
+
+    let mut session = spawn_session(gen_cmd(Lang::Python, temp.path(), "MIT"));
+    session.exp_string("Enter the copyright year").unwrap();
+    session.send_line("2025-2019").unwrap();
+    session.exp_string("Invalid input").unwrap();
+    session.send_line("2020 - 2024").unwrap();
+    session
+        .exp_string("Enter the full name of the copyright holder")
+        .unwrap();
+    session.send_line("Your Name").unwrap();
+    session.exp_eof().unwrap();
+    temp.child("LICENSE.python.txt")
+        .assert(predicate::str::contains(
+            "Copyright (c) 2020-2024 Your Name\n",
+        ));
+
+    gen_assert_cmd(Lang::Rust, temp.path(), "MIT")
+        .arg("--year")
+        .arg("twenty")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("expected a year"));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_quote_style() {
+    let temp = setup_test_env();
+    gen_assert_cmd(Lang::Rust, temp.path(), "MIT")
+        .arg("--year")
+        .arg("2025")
+        .arg("--quote-style")
+        .arg("curly")
+        .write_stdin("Your Name\n")
+        .assert()
+        .success();
+    temp.child("LICENSE.rust.txt").assert(
+        predicate::str::contains("(the “Software”)")
+            .and(predicate::str::contains("“AS IS”"))
+            .and(predicate::str::contains('"').not()),
+    );
+    temp.child("rust/main.rs")
+        .assert(predicate::str::starts_with(
+            "// SPDX-License-Identifier: MIT\n",
+        ));
+
+    gen_assert_cmd(Lang::Python, temp.path(), "CC-BY-4.0")
+        .arg("--year")
+        .arg("2025")
+        .write_stdin("Your Name\n")
+        .assert()
+        .success();
+    temp.child("LICENSE.python.txt").assert(
+        predicate::str::contains("the \"Licensor.\"").and(predicate::str::contains('“').not()),
+    );
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_deterministic() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    for args in [
+        &["init", "--quiet"][..],
+        &["config", "user.name", "Git Person"],
+    ] {
+        StdCommand::new("git")
+            .args(args)
+            .current_dir(temp.path())
+            .status()
+            .unwrap();
+    }
+    temp.child(".config/license-gen/license.toml")
+        .write_str("copyright_holder = \"User Config\"\nyear = 1999\n")
+        .unwrap();
+    let run = |args: &[&str]| {
+        let mut cmd = AssertCommand::new(cargo_bin!("license"));
+        cmd.current_dir(temp.path())
+            .env("XDG_CONFIG_HOME", temp.child(".config").path())
+            .arg("--deterministic")
+            .args(args)
+            .arg("MIT");
+        cmd.assert()
+    };
+    run(&[]).failure().stderr(predicate::str::contains(
+        "needs an answer for 'Enter the copyright year'",
+    ));
+    run(&["--year", "2020"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "needs an answer for 'Enter the full name of the copyright holder'",
+        ));
+
+    temp.child("license.toml")
+        .write_str("copyright_holder = \"Ada Lovelace\"\n")
+        .unwrap();
+    run(&["--year", "2020"]).success();
+    let license = temp.child("LICENSE.txt");
+    license.assert(predicate::str::contains("Copyright (c) 2020 Ada Lovelace"));
+    let first = std::fs::read(license.path()).unwrap();
+    run(&["--year", "2020"]).success();
+    assert_eq!(first, std::fs::read(license.path()).unwrap());
+    temp.child(".config/license-gen/last.toml")
+        .assert(predicate::path::missing());
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_config_file_defaults() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/main.rs")
+        .write_str("fn main() {}\n")
+        .unwrap();
+    temp.child("license.toml")
+        .write_str("copyright_holder = \"Ada Lovelace\"\nyear = 1843\ncomment = \"#\"\noutput = \"COPYING.txt\"\n")
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .env("XDG_CONFIG_HOME", temp.child(".config").path())
+        .arg("--add-comment")
+        .arg("MIT")
+        .assert()
+        .success();
+    temp.child("COPYING.txt")
+        .assert(predicate::str::contains("Copyright (c) 1843 Ada Lovelace"));
+    temp.child("src/main.rs")
+        .assert(predicate::str::starts_with(
+            "# SPDX-License-Identifier: MIT\n",
+        ));
+
+    temp.child("other.toml")
+        .write_str(
+            "copyright_holder = \"Charles Babbage\"\nyear = 1837\noutput = \"IGNORED.txt\"\n",
+        )
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .env("XDG_CONFIG_HOME", temp.child(".config").path())
+        .arg("--config")
+        .arg("other.toml")
+        .arg("--output")
+        .arg("LICENSE.txt")
+        .arg("MIT")
+        .assert()
+        .success();
+    temp.child("LICENSE.txt").assert(predicate::str::contains(
+        "Copyright (c) 1837 Charles Babbage",
+    ));
+    temp.child("IGNORED.txt").assert(predicate::path::missing());
+
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--config")
+        .arg("missing.toml")
+        .arg("MIT")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Failed to load config"));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_skip_existing_spdx_header() {
+    let temp = setup_test_env();
+    for _ in 0..2 {
+        gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+            .assert()
+            .success();
+    }
+    gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Added license headers to 0 file(s)",
+        ))
+        .stdout(predicate::str::contains("skipped 1 file(s)"));
+    temp.child("rust/main.rs").assert(
+        predicate::str::contains("SPDX-License-Identifier")
+            .count(1)
+            .and(predicate::str::contains("fn main()")),
+    );
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_force_replaces_existing_spdx_header() {
+    let temp = setup_test_env();
+    gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+        .assert()
+        .success();
+    gen_assert_cmd(Lang::Rust, temp.path(), "Unlicense")
+        .arg("--force")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Added license headers to 1 file(s)",
+        ));
+    temp.child("rust/main.rs").assert(
+        predicate::str::starts_with("// SPDX-License-Identifier: Unlicense\nfn main()")
+            .and(predicate::str::contains("MPL-2.0").not()),
+    );
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_position_top_keeps_shebang() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/tool.py")
+        .write_str("#!/usr/bin/env python\nimport os\n")
+        .unwrap();
+    temp.child("src/lib.rs")
+        .write_str("#![allow(dead_code)]\nfn lib() {}\n")
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .args(["--add-comment", "Unlicense"])
+        .assert()
+        .success();
+    temp.child("src/tool.py")
+        .assert("#!/usr/bin/env python\n# SPDX-License-Identifier: Unlicense\nimport os\n");
+    temp.child("src/lib.rs")
+        .assert("// SPDX-License-Identifier: Unlicense\n#![allow(dead_code)]\nfn lib() {}\n");
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_position_bottom() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/tool.py")
+        .write_str("import os\nprint(os.name)\n")
+        .unwrap();
+    for _ in 0..2 {
+        AssertCommand::new(cargo_bin!("license"))
+            .current_dir(temp.path())
+            .args(["--add-comment", "--position", "bottom", "Unlicense"])
+            .assert()
+            .success();
+    }
+    temp.child("src/tool.py").assert(
+        predicate::str::starts_with(
+            "import os\nprint(os.name)\n\n# SPDX-License-Identifier: Unlicense\n",
+        )
+        .and(predicate::str::contains("SPDX-License-Identifier").count(1)),
+    );
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .args(["--check", "--position", "bottom", "Unlicense"])
+        .assert()
+        .success();
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_strip_existing_headers_bottom() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/tool.py")
+        .write_str("#!/usr/bin/env python\n# SPDX-License-Identifier: MIT\n# Copyright (c) 2020 Old Owner\n\nimport os\n\n# SPDX-License-Identifier: Apache-2.0\n")
+        .unwrap();
+    for _ in 0..2 {
+        AssertCommand::new(cargo_bin!("license"))
+            .current_dir(temp.path())
+            .args([
+                "--add-comment",
+                "--position",
+                "bottom",
+                "--strip-existing-headers",
+                "Unlicense",
+            ])
+            .assert()
+            .success();
+        temp.child("src/tool.py")
+            .assert("#!/usr/bin/env python\nimport os\n\n# SPDX-License-Identifier: Unlicense\n");
+    }
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_remove_header() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/tool.py")
+        .write_str("#!/usr/bin/env python\n# SPDX-License-Identifier: MIT\n# Copyright (c) 2020 Old Owner\n\nimport os\n")
+        .unwrap();
+    temp.child("src/notes.py")
+        .write_str("# Helpers for the build scripts.\n\nimport os\n")
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .args(["remove-header", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("-# SPDX-License-Identifier: MIT"))
+        .stdout(predicate::str::contains(
+            "Would remove license headers from 1 file(s)",
+        ));
+    temp.child("src/tool.py")
+        .assert(predicate::str::contains("SPDX-License-Identifier: MIT"));
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("uncomment")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Removed license headers from 1 file(s)",
+        ));
+    temp.child("src/tool.py")
+        .assert("#!/usr/bin/env python\nimport os\n");
+    temp.child("src/notes.py")
+        .assert("# Helpers for the build scripts.\n\nimport os\n");
+    temp.close().unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_keeps_file_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = assert_fs::TempDir::new().unwrap();
+    let script = temp.child("src/run.sh");
+    script.write_str("#!/bin/sh\necho hi\n").unwrap();
+    std::fs::set_permissions(script.path(), std::fs::Permissions::from_mode(0o750)).unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .args(["--add-comment", "Unlicense"])
+        .assert()
+        .success();
+    script.assert(predicate::str::contains(
+        "# SPDX-License-Identifier: Unlicense",
+    ));
+    let mode = std::fs::metadata(script.path())
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o750);
+    let leftovers: Vec<_> = std::fs::read_dir(temp.child("src").path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(leftovers, ["run.sh"]);
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_spdx_only() {
+    let temp = setup_test_env();
+    temp.child("LICENSE.rust.txt")
+        .write_str("Committed license.\n")
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .args(["--source-path", "rust/", "--output", "LICENSE.rust.txt"])
+        .args(["--spdx-only", "Unlicense"])
+        .assert()
+        .success();
+    temp.child("LICENSE.rust.txt")
+        .assert("Committed license.\n");
+    temp.child("rust/main.rs")
+        .assert(predicate::str::starts_with(
+            "// SPDX-License-Identifier: Unlicense\n",
+        ));
+
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .args(["--source-path", "python/", "--no-license-file", "MPL-2.0"])
+        .assert()
+        .success();
+    temp.child("LICENSE.txt").assert(predicate::path::missing());
+    temp.child("python/__init__.py")
+        .assert(predicate::str::starts_with(
+            "# SPDX-License-Identifier: MPL-2.0\n",
+        ));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_keeps_bom_and_crlf() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/main.rs")
+        .write_str("\u{feff}fn main() {}\r\n\r\nfn helper() {}\r\n")
+        .unwrap();
+    temp.child("src/lib.rs")
+        .write_str("pub fn lib() {}\n")
+        .unwrap();
+    for _ in 0..2 {
+        AssertCommand::new(cargo_bin!("license"))
+            .current_dir(temp.path())
+            .args(["--add-comment", "Unlicense"])
+            .assert()
+            .success();
+    }
+    temp.child("src/main.rs").assert(
+        "\u{feff}// SPDX-License-Identifier: Unlicense\r\nfn main() {}\r\n\r\nfn helper() {}\r\n",
+    );
+    temp.child("src/lib.rs")
+        .assert("// SPDX-License-Identifier: Unlicense\npub fn lib() {}\n");
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_progress_without_terminal() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    for i in 0..120 {
+        temp.child(format!("src/file{i}.rs"))
+            .write_str("fn f() {}\n")
+            .unwrap();
+    }
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .args(["--add-comment", "--dry-run", "Unlicense"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Processed 12/120 files"))
+        .stderr(predicate::str::contains("Processed 120/120 files"));
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .args(["--add-comment", "--dry-run", "-q", "Unlicense"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Processed").not());
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_backup() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/main.rs")
+        .write_str("fn main() {}\n")
+        .unwrap();
+    temp.child("src/lib.rs")
+        .write_str("// SPDX-License-Identifier: Unlicense\npub fn lib() {}\n")
+        .unwrap();
+    for _ in 0..2 {
+        AssertCommand::new(cargo_bin!("license"))
+            .current_dir(temp.path())
+            .args(["--add-comment", "--comment", "//", "--backup", "Unlicense"])
+            .assert()
+            .success();
+    }
+    temp.child("src/main.rs")
+        .assert("// SPDX-License-Identifier: Unlicense\nfn main() {}\n");
+    temp.child("src/main.rs.bak").assert("fn main() {}\n");
+    temp.child("src/lib.rs.bak")
+        .assert(predicate::path::missing());
+    temp.child("src/main.rs.bak.bak")
+        .assert(predicate::path::missing());
+
+    temp.child("src/other.rs")
+        .write_str("fn other() {}\n")
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .args(["--add-comment", "--backup=.orig", "Unlicense"])
+        .assert()
+        .success();
+    temp.child("src/other.rs.orig").assert("fn other() {}\n");
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .args(["--add-comment", "--backup=", "Unlicense"])
+        .assert()
+        .failure();
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_strip_existing_headers() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/tool.py")
+        .write_str("#!/usr/bin/env python\n# SPDX-License-Identifier: MIT\n# Copyright (c) 2020 Old Owner\n\n# SPDX-License-Identifier: Apache-2.0\n\n# Helpers for the build scripts.\n\nimport os\n")
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--add-comment")
+        .arg("--strip-existing-headers")
+        .arg("MPL-2.0")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Added license headers to 1 file(s)",
+        ));
+    temp.child("src/tool.py").assert(
+        predicate::str::starts_with("#!/usr/bin/env python\n# SPDX-License-Identifier: MPL-2.0\n")
+            .and(predicate::str::ends_with(
+                "\n\n# Helpers for the build scripts.\n\nimport os\n",
+            ))
+            .and(predicate::str::contains("SPDX-License-Identifier").count(1))
+            .and(predicate::str::contains("Old Owner").not()),
+    );
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_update_markdown_license_section() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/README.md")
+        .write_str("# Tool\n\n## License\n\nMIT, probably.\n\n### Third party\n\nVendored code.\n\n## Contributing\n\nSend patches.\n")
+        .unwrap();
+    temp.child("src/NOTES.md").write_str("# Notes\n").unwrap();
+    for expected in ["1 file(s)", "0 file(s)"] {
+        AssertCommand::new(cargo_bin!("license"))
+            .current_dir(temp.path())
+            .arg("--add-comment")
+            .arg("--spdx-expression")
+            .arg("MPL-2.0 OR Unlicense")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(format!(
+                "Added license headers to {expected}"
+            )));
+    }
+    temp.child("src/README.md").assert(
+        "# Tool\n\n## License\n\n<!-- license-gen:start -->\nSPDX-License-Identifier: `MPL-2.0 OR Unlicense` ([MPL-2.0](https://spdx.org/licenses/MPL-2.0.html), [Unlicense](https://spdx.org/licenses/Unlicense.html))\n<!-- license-gen:end -->\n\n## Contributing\n\nSend patches.\n",
+    );
+    temp.child("src/NOTES.md").assert("# Notes\n");
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_dry_run() {
+    let temp = setup_test_env();
+    gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "--- a/rust/main.rs\n+++ b/rust/main.rs\n@@ -1,3 +1,7 @@\n+// SPDX-License-Identifier: MPL-2.0\n",
+        ))
+        .stdout(predicate::str::contains(
+            "Would write license text to LICENSE.rust.txt",
+        ))
+        .stdout(predicate::str::contains(
+            "Would add license headers to 1 file(s)",
+        ));
+    temp.child("rust/main.rs")
+        .assert(predicate::str::contains("SPDX-License-Identifier").not());
+    temp.child("LICENSE.rust.txt")
+        .assert(predicate::path::missing());
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_as_patch() {
+    let patched = setup_test_env();
+    patched
+        .child("rust/lib.rs")
+        .write_str("pub fn lib() {}")
+        .unwrap();
+    gen_assert_cmd(Lang::Rust, patched.path(), "MPL-2.0")
+        .arg("--as-patch")
+        .arg("license.patch")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Wrote patch to license.patch"));
+    patched
+        .child("LICENSE.rust.txt")
+        .assert(predicate::path::missing());
+    patched.child("rust/lib.rs").assert("pub fn lib() {}");
+    patched.child("license.patch").assert(
+        predicate::str::contains("--- /dev/null\n+++ b/LICENSE.rust.txt\n").and(
+            predicate::str::contains("--- a/rust/main.rs\n+++ b/rust/main.rs\n"),
+        ),
+    );
+    let status = StdCommand::new("git")
+        .args(["apply", "license.patch"])
+        .current_dir(patched.path())
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let written = setup_test_env();
+    written
+        .child("rust/lib.rs")
+        .write_str("pub fn lib() {}")
+        .unwrap();
+    gen_assert_cmd(Lang::Rust, written.path(), "MPL-2.0")
+        .assert()
+        .success();
+    for file in ["LICENSE.rust.txt", "rust/main.rs", "rust/lib.rs"] {
+        patched
+            .child(file)
+            .assert(std::fs::read_to_string(written.child(file).path()).unwrap());
+    }
+    patched.close().unwrap();
+    written.close().unwrap();
+}
+
+#[test]
+fn test_check_reports_missing_and_wrong_headers() {
+    let temp = setup_mixed_env();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--check")
+        .arg("MPL-2.0")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Missing license header"))
+        .stderr(predicate::str::contains("main.rs"))
+        .stderr(predicate::str::contains("__init__.py"));
+    temp.child("src/notes.xyz").assert("notes\n");
+
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--add-comment")
+        .arg("MPL-2.0")
+        .assert()
+        .success();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--check")
+        .arg("MPL-2.0")
+        .assert()
+        .success();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--check")
+        .arg("Unlicense")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Wrong license identifier"))
+        .stderr(predicate::str::contains("found 'MPL-2.0'"));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_content_match() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/marked.rs")
+        .write_str("// TODO: add license\nfn marked() {}\n")
+        .unwrap();
+    temp.child("src/unmarked.rs")
+        .write_str("fn unmarked() {}\n")
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--add-comment")
+        .arg("--content-match")
+        .arg("TODO: add license")
+        .arg("MPL-2.0")
+        .assert()
+        .success();
+    temp.child("src/marked.rs")
+        .assert(predicate::str::starts_with(
+            "// SPDX-License-Identifier: MPL-2.0",
+        ));
+    temp.child("src/marked.rs")
+        .assert(predicate::str::contains("// TODO: add license"));
+    temp.child("src/unmarked.rs").assert("fn unmarked() {}\n");
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_content_match_replace() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/marked.rs")
+        .write_str("#![allow(dead_code)]\n// TODO: add license\nfn marked() {}\n")
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--add-comment")
+        .arg("--content-match")
+        .arg("TODO: add license")
+        .arg("--replace-match")
+        .arg("Unlicense")
+        .assert()
+        .success();
+    temp.child("src/marked.rs")
+        .assert("#![allow(dead_code)]\n// SPDX-License-Identifier: Unlicense\nfn marked() {}\n");
+
+    // A match ending in a newline replaces only the lines it covers.
+    temp.child("src/old.rs")
+        .write_str("// old header\nfn old() {}\n")
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("--add-comment")
+        .arg("--content-match")
+        .arg("(?m)^// old header\n")
+        .arg("--replace-match")
+        .arg("Unlicense")
+        .assert()
+        .success();
+    temp.child("src/old.rs")
+        .assert("// SPDX-License-Identifier: Unlicense\nfn old() {}\n");
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_list_licenses() {
+    AssertCommand::new(cargo_bin!("license"))
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("MIT\tinteractive\tMIT License\n"))
+        .stdout(predicate::str::contains(
+            "GPL-3.0-or-later\tinteractive\tGNU General Public License v3.0 or later\n",
+        ))
+        .stdout(predicate::str::contains("CC0-1.0\tstatic\t"))
+        .stdout(predicate::function(|out: &str| {
+            out.lines().all(|line| line.split('\t').count() == 3)
+        }));
+}
+
+#[test]
+fn test_completions() {
+    AssertCommand::new(cargo_bin!("license"))
+        .args(["completions", "bash"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("_license()"))
+        .stdout(predicate::str::contains("--source-path"));
+    AssertCommand::new(cargo_bin!("license"))
+        .args(["completions", "zsh"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("#compdef license"));
+    AssertCommand::new(cargo_bin!("license"))
+        .args(["completions", "tcsh"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_man() {
+    AssertCommand::new(cargo_bin!("license"))
+        .arg("man")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(".TH license 1"));
+    AssertCommand::new(cargo_bin!("license"))
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("  man ").not());
+}
+
+#[test]
+fn test_self_test() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .env("XDG_CONFIG_HOME", temp.path())
+        .arg("self-test")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("MIT\n"))
+        .stdout(predicate::str::contains("CC-BY-4.0\n"))
+        .stdout(predicate::str::contains("FAIL").not())
+        .stdout(predicate::str::contains("All licenses rendered"));
+    temp.child(".config").assert(predicate::path::missing());
+}
+
+#[test]
+fn test_modernize_legacy_headers() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/mit.rs")
+        .write_str("// Copyright 2020 Your Name\n// Licensed under the MIT license\n//! Docs.\nfn mit() {}\n")
+        .unwrap();
+    temp.child("src/apache.py")
+        .write_str("#!/usr/bin/env python\n# Licensed under the Apache License, Version 2.0 (the \"License\");\n# you may not use this file except in compliance with the License.\n\nimport os\n")
+        .unwrap();
+    temp.child("src/custom.rs")
+        .write_str("// Licensed under the ACME internal license\nfn custom() {}\n")
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .arg("modernize")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Modernized 2 file(s)"))
+        .stderr(predicate::str::contains("Please review them manually"))
+        .stderr(predicate::str::contains("custom.rs"));
+    temp.child("src/mit.rs").assert(
+        "// SPDX-License-Identifier: MIT\n// Copyright 2020 Your Name\n//! Docs.\nfn mit() {}\n",
+    );
+    temp.child("src/apache.py")
+        .assert("#!/usr/bin/env python\n# SPDX-License-Identifier: Apache-2.0\n\nimport os\n");
+    temp.child("src/custom.rs")
+        .assert("// Licensed under the ACME internal license\nfn custom() {}\n");
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_output_to_stdout() {
+    let temp = setup_test_env();
+    temp.child("answers.toml")
+        .write_str("[[answers]]\nprompt = \"Enter the copyright year\"\nvalue = \"2025\"\n\n[[answers]]\nprompt = \"Enter the full name of the copyright holder\"\nvalue = \"Your Name\"\n")
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .env("XDG_CONFIG_HOME", temp.path().join(".config"))
+        .arg("--source-path")
+        .arg("rust/")
+        .arg("--add-comment")
+        .arg("--output")
+        .arg("-")
+        .arg("--answers")
+        .arg("answers.toml")
+        .arg("MIT")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "MIT License\n\nCopyright (c) 2025 Your Name\n",
+        ))
+        .stderr(predicate::str::contains(
+            "Added license headers to 1 file(s)",
+        ));
+    temp.child("-").assert(predicate::path::missing());
+    temp.child("rust/main.rs")
+        .assert(predicate::str::starts_with(
+            "// SPDX-License-Identifier: MIT\n",
+        ));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_notices_go_to_stderr() {
+    let temp = setup_test_env();
+    let answers = [
+        ("Enter the copyright year", "2025"),
+        ("Enter the full name of the copyright holder", "Your Name"),
+        ("Enter the name of the program", "license"),
+        (
+            "Enter a short description of the program (5-10 words)",
+            "A tool for managing licenses",
+        ),
+        (
+            "Do you need a signed release for this software? (e.g., for an organization)",
+            "yes",
+        ),
+        ("Enter the name of the organization", "ACME, Inc."),
+        (
+            "Enter the name of the signer from the organization",
+            "Road Runner",
+        ),
+        (
+            "Enter the position within the organization of the signer",
+            "The Boss",
+        ),
+        ("Enter the day of the signing", "7"),
+        ("Enter the month of the signing", "April"),
+        ("Enter the year of the signing", "2025"),
+    ];
+    let file: String = answers
+        .iter()
+        .map(|(prompt, value)| format!("[[answers]]\nprompt = {prompt:?}\nvalue = {value:?}\n\n"))
+        .collect();
+    temp.child("answers.toml").write_str(&file).unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .env("XDG_CONFIG_HOME", temp.path().join(".config"))
+        .arg("--output")
+        .arg("-")
+        .arg("--answers")
+        .arg("answers.toml")
+        .arg("LGPL-3.0")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "GNU LESSER GENERAL PUBLIC LICENSE",
+        ))
+        .stdout(predicate::str::contains("amendment").not())
+        .stdout(predicate::str::contains("hereby disclaims").not())
+        .stderr(predicate::str::contains(
+            "You'll need to include the following amendment to your license.",
+        ))
+        .stderr(predicate::str::contains(
+            "ACME, Inc., hereby disclaims all copyright interest",
+        ));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_copyright_format() {
+    for license in ["MIT", "Apache-2.0"] {
+        let temp = setup_test_env();
+        let mut cmd = gen_cmd(Lang::Rust, temp.path(), license);
+        cmd.arg("--copyright-format").arg("© {year} {name}");
+        let mut session = spawn_session(cmd);
+        basic_interact(&mut session);
+        let location = if license == "Apache-2.0" {
+            Location::Source("© 2025 Your Name")
+        } else {
+            Location::License("© 2025 Your Name")
+        };
+        assert_files(&temp, Lang::Rust, license, Some(location));
+        temp.close().unwrap();
+    }
+}
+
+#[test]
+fn test_template_dir() {
+    let temp = setup_test_env();
+    temp.child("templates/MIT.hbs")
+        .write_str("ACME MIT License for {{fullname}} ({{year}})\n\n{{{copyright}}}\n")
+        .unwrap();
+    temp.child("templates/Unlicense.hbs")
+        .write_str("Released into the public domain per ACME policy.\n")
+        .unwrap();
+
+    let mut cmd = gen_cmd(Lang::Rust, temp.path(), "MIT");
+    cmd.arg("--template-dir").arg("templates");
+    let mut session = spawn_session(cmd);
+    basic_interact(&mut session);
+    temp.child("LICENSE.rust.txt")
+        .assert("ACME MIT License for Your Name (2025)\n\nCopyright (c) 2025 Your Name\n");
+
+    gen_assert_cmd(Lang::Python, temp.path(), "Unlicense")
+        .arg("--template-dir")
+        .arg("templates")
+        .assert()
+        .success();
+    temp.child("LICENSE.python.txt")
+        .assert("Released into the public domain per ACME policy.\n");
+
+    gen_assert_cmd(Lang::Python, temp.path(), "0BSD")
+        .arg("--template-dir")
+        .arg("templates")
+        .write_stdin("2025\nYour Name\n")
+        .assert()
+        .success();
+    temp.child("LICENSE.python.txt")
+        .assert(predicate::str::contains("Permission to use, copy, modify"));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_template_vars() {
+    let temp = setup_test_env();
+    temp.child("templates/Unlicense.hbs")
+        .write_str("{{project}} is released into the public domain by {{owner}}.\n")
+        .unwrap();
+    temp.child("templates/MIT.hbs")
+        .write_str("{{project}}\n\n{{{copyright}}}\n")
+        .unwrap();
+
+    gen_assert_cmd(Lang::Python, temp.path(), "Unlicense")
+        .args(["--template-dir", "templates"])
+        .args(["--var", "project=Widget", "--var", "owner=ACME, Inc."])
+        .assert()
+        .success();
+    temp.child("LICENSE.python.txt")
+        .assert("Widget is released into the public domain by ACME, Inc..\n");
+
+    gen_assert_cmd(Lang::Rust, temp.path(), "MIT")
+        .args(["--template-dir", "templates"])
+        .args(["--var", "project=Widget", "--var", "fullname=Someone"])
+        .write_stdin("2025\nYour Name\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid template variable"))
+        .stderr(predicate::str::contains(
+            "'fullname' is a built-in variable",
+        ));
+    temp.child("LICENSE.rust.txt")
+        .assert(predicate::path::missing());
+
+    gen_assert_cmd(Lang::Rust, temp.path(), "MIT")
+        .args(["--var", "project"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("expected 'key=value'"));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_license_title() {
+    let temp = setup_test_env();
+    gen_assert_cmd(Lang::Rust, temp.path(), "MIT")
+        .args(["--title", "License (MIT)"])
+        .write_stdin("2025\nYour Name\n")
+        .assert()
+        .success();
+    temp.child("LICENSE.rust.txt")
+        .assert(predicate::str::starts_with(
+            "License (MIT)\n\nCopyright (c) 2025 Your Name\n",
+        ));
+
+    gen_assert_cmd(Lang::Python, temp.path(), "MPL-2.0")
+        .args(["--var", "title=License (MPL)"])
+        .assert()
+        .success();
+    temp.child("LICENSE.python.txt")
+        .assert(predicate::str::starts_with("License (MPL)\n"))
+        .assert(predicate::str::contains("Mozilla Public License Version 2.0").not());
+
+    temp.child("templates/Unlicense.hbs")
+        .write_str("{{title}}: public domain\n")
+        .unwrap();
+    gen_assert_cmd(Lang::Python, temp.path(), "Unlicense")
+        .args(["--template-dir", "templates", "--title", "Dedication"])
+        .assert()
+        .success();
+    temp.child("LICENSE.python.txt")
+        .assert("Dedication: public domain\n");
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_apache_notice() {
+    let temp = setup_test_env();
+    let mut cmd = gen_cmd(Lang::Rust, temp.path(), "Apache-2.0");
+    cmd.arg("--notice");
+    let mut session = spawn_session(cmd);
+    session.exp_string("Enter the copyright year:").unwrap();
+    session.send_line("2025").unwrap();
+    session
+        .exp_string("Enter the full name of the copyright holder:")
+        .unwrap();
+    session.send_line("Your Name").unwrap();
+    session
+        .exp_string("Enter the name of the project:")
+        .unwrap();
+    session.send_line("Widget").unwrap();
+    session.exp_eof().unwrap();
+    assert_files(&temp, Lang::Rust, "Apache-2.0", None);
+    temp.child("NOTICE").assert(
+        "Widget\nCopyright 2025 Your Name\n\nThis product includes software developed by Your Name.\n",
+    );
+
+    let temp = setup_test_env();
+    let mut cmd = gen_cmd(Lang::Rust, temp.path(), "MIT");
+    cmd.arg("--notice");
+    let mut session = spawn_session(cmd);
+    basic_interact(&mut session);
+    assert_files(&temp, Lang::Rust, "MIT", None);
+    temp.child("NOTICE").assert(predicate::path::missing());
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_comment_token_validation() {
+    let temp = setup_test_env();
+    for token in ["", "   "] {
+        AssertCommand::new(cargo_bin!("license"))
+            .current_dir(temp.path())
+            .args(["--comment", token, "Unlicense"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "the comment token cannot be empty",
+            ));
+    }
+    temp.child("license.toml")
+        .write_str("comment = \" \"\n")
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .env("XDG_CONFIG_HOME", temp.path().join(".config"))
+        .args(["--config", "license.toml", "Unlicense"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid comment in config"));
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .env("XDG_CONFIG_HOME", temp.path().join(".config"))
+        .args([
+            "--source-path",
+            "rust/",
+            "--add-comment",
+            "--comment",
+            "REM",
+        ])
+        .args(["--output", "LICENSE.rust.txt", "Unlicense"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Unusual comment token"));
+    temp.child("rust/main.rs")
+        .assert(predicate::str::starts_with(
+            "REM SPDX-License-Identifier: Unlicense\n",
+        ));
+    gen_assert_cmd(Lang::Python, temp.path(), "Unlicense")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Unusual comment token").not());
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_all_rights_reserved() {
+    for license in ["MIT", "BSD-3-Clause"] {
+        let temp = setup_test_env();
+        let mut cmd = gen_cmd(Lang::Rust, temp.path(), license);
+        cmd.arg("--all-rights-reserved");
+        let mut session = spawn_session(cmd);
+        basic_interact(&mut session);
+        let (license_file, _) = assert_files(
+            &temp,
+            Lang::Rust,
+            license,
+            Some(Location::License(
+                "Copyright (c) 2025 Your Name. All rights reserved.",
+            )),
+        );
+        license_file.assert(predicate::str::contains("Name.. All").not());
+        temp.close().unwrap();
+    }
+}
+
+#[test]
+fn test_epl_rejects_itself_as_secondary() {
+    let temp = setup_test_env();
+    let cmd = gen_cmd(Lang::Rust, temp.path(), "EPL-2.0");
+    let mut session = spawn_session(cmd);
+    session
+        .exp_string("Enter the secondary licenses that are permitted (comma separated) (optional):")
+        .unwrap();
+    session.send_line("EPL-2.0, GPL-2.0-or-later").unwrap();
+    session
+        .exp_string("EPL-2.0 cannot be listed as its own secondary license.")
+        .unwrap();
+    session
+        .exp_string("Enter the secondary licenses that are permitted (comma separated) (optional):")
+        .unwrap();
+    session.send_line("GPL-2.0-or-later, MTI").unwrap();
+    session
+        .exp_string("Unknown SPDX license identifier(s)")
+        .unwrap();
+    session
+        .exp_string("Enter the secondary licenses that are permitted (comma separated) (optional):")
+        .unwrap();
+    session.send_line("gpl-2.0-or-later, mit").unwrap();
+    session
+        .exp_string("MIT is not a version of the GNU General Public License")
+        .unwrap();
+    session.exp_string("- GPL-2.0-or-later").unwrap();
+    session.exp_string("- MIT").unwrap();
+    session.exp_eof().unwrap();
+    assert_files(&temp, Lang::Rust, "EPL-2.0", None);
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_git_add_stages_written_files() {
+    let temp = setup_test_env();
+    StdCommand::new("git")
+        .arg("init")
+        .arg("--quiet")
+        .current_dir(temp.path())
+        .status()
+        .unwrap();
+    gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+        .arg("--git-add")
+        .assert()
+        .success();
+    let staged = StdCommand::new("git")
+        .args(["diff", "--cached", "--name-only"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    let staged = String::from_utf8(staged.stdout).unwrap();
+    assert!(staged.contains("LICENSE.rust.txt"), "{staged}");
+    assert!(staged.contains("rust/main.rs"), "{staged}");
+    assert!(!staged.contains("python/__init__.py"), "{staged}");
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_only_dirty_skips_clean_files() {
+    let temp = setup_test_env();
+    temp.child("rust/lib.rs")
+        .write_str("pub fn lib() {}\n")
+        .unwrap();
+    for args in [
+        &["init", "--quiet"][..],
+        &["add", "rust"],
+        &[
+            "-c",
+            "user.name=Git Person",
+            "-c",
+            "user.email=git@example.com",
+            "commit",
+            "--quiet",
+            "-m",
+            "Initial commit",
+        ],
+    ] {
+        StdCommand::new("git")
+            .args(args)
+            .current_dir(temp.path())
+            .status()
+            .unwrap();
+    }
+    temp.child("rust/lib.rs")
+        .write_str("pub fn lib() {}\npub fn changed() {}\n")
+        .unwrap();
+
+    gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+        .arg("--warn-clean")
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "rust/main.rs was unchanged in git",
+        ))
+        .stderr(predicate::str::contains("rust/lib.rs was unchanged").not());
+
+    gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+        .arg("--only-dirty")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Left 1 file(s) unchanged in git alone.",
+        ));
+    temp.child("rust/lib.rs")
+        .assert(predicate::str::contains("SPDX-License-Identifier: MPL-2.0"));
+    temp.child("rust/main.rs")
+        .assert(predicate::str::contains("SPDX-License-Identifier: MPL-2.0").not());
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_git_user_name_default() {
+    let temp = setup_test_env();
+    for args in [
+        &["init", "--quiet"][..],
+        &["config", "user.name", "Git Person"],
+    ] {
+        StdCommand::new("git")
+            .args(args)
+            .current_dir(temp.path())
+            .status()
+            .unwrap();
+    }
+    let mut session = spawn_session(gen_cmd(Lang::Rust, temp.path(), "MIT"));
+    session.exp_string("Enter the copyright year:").unwrap();
+    session.send_line("2025").unwrap();
+    session
+        .exp_string("Enter the full name of the copyright holder [Git Person]:")
+        .unwrap();
+    session.send_line("").unwrap();
+    session.exp_eof().unwrap();
+    assert_files(
+        &temp,
+        Lang::Rust,
+        "MIT",
+        Some(Location::License("Copyright (c) 2025 Git Person")),
+    );
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_from_manifest_defaults() {
+    let temp = setup_test_env();
+    temp.child("Cargo.toml")
+        .write_str(
+            r#"[package]
+name = "demo-crate"
+authors = { workspace = true }
+
+[workspace.package]
+authors = ["Jane Doe <jane@example.com>", "John Roe"]
+"#,
+        )
+        .unwrap();
+    let answers = GPL_ANSWERS
+        .split("[[answers]]")
+        .filter(|answer| {
+            !answer.contains("copyright holder") && !answer.contains("name of the program")
+        })
+        .collect::<Vec<_>>()
+        .join("[[answers]]");
+    temp.child("answers.toml").write_str(&answers).unwrap();
+    let mut cmd = gen_cmd(Lang::Rust, temp.path(), "GPL-3.0-or-later");
+    cmd.args(["--answers", "answers.toml", "--from-manifest"]);
+    let mut session = spawn_session(cmd);
+    session
+        .exp_string("Enter the full name of the copyright holder [Jane Doe, John Roe]:")
+        .unwrap();
+    session.send_line("").unwrap();
+    session
+        .exp_string("Enter the name of the program [demo-crate]:")
+        .unwrap();
+    session.send_line("").unwrap();
+    session
+        .exp_string("Enter the version of the program (optional):")
+        .unwrap();
+    session.send_line("").unwrap();
+    session.exp_eof().unwrap();
+    temp.child("LICENSE.rust.txt")
+        .assert(predicate::str::contains("Jane Doe, John Roe"))
+        .assert(predicate::str::contains("demo-crate"));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_git_add_outside_repo() {
+    let temp = setup_test_env();
+    gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+        .env("GIT_CEILING_DIRECTORIES", temp.path().parent().unwrap())
+        .arg("--git-add")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Not in a git repository"));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_parallel_stamping() {
+    for jobs in ["1", "4"] {
+        let temp = assert_fs::TempDir::new().unwrap();
+        for i in 0..400 {
+            temp.child(format!("src/mod{}/file{i}.rs", i % 8))
+                .write_str("fn f() {}\n")
+                .unwrap();
+        }
+        AssertCommand::new(cargo_bin!("license"))
+            .current_dir(temp.path())
+            .args([
+                "--add-comment",
+                "--comment",
+                "//",
+                "-q",
+                "--jobs",
+                jobs,
+                "Unlicense",
+            ])
+            .assert()
+            .success();
+        for i in 0..400 {
+            temp.child(format!("src/mod{}/file{i}.rs", i % 8))
+                .assert("// SPDX-License-Identifier: Unlicense\nfn f() {}\n");
+        }
+        temp.close().unwrap();
+    }
+    AssertCommand::new(cargo_bin!("license"))
+        .args(["--add-comment", "--jobs", "0", "Unlicense"])
+        .assert()
+        .failure();
+}
+
+const GPL_ANSWERS: &str = r#"
+[[answers]]
+prompt = "Enter the copyright year"
+value = "2025"
+
+[[answers]]
+prompt = "Enter the full name of the copyright holder"
+value = "Your Name"
+
+[[answers]]
+prompt = "Enter the name of the program"
+value = "license"
+
+[[answers]]
+prompt = "Enter the version of the program (optional)"
+value = "1.0.0"
+
+[[answers]]
+prompt = "Enter a short description of the program (5-10 words)"
+value = "A tool for managing licenses"
+
+[[answers]]
+prompt = "Is this program interactive? (e.g., a website, CLI tool, etc.)"
+value = "yes"
+
+[[answers]]
+prompt = "Do you need a signed release for this software? (e.g., for an organization)"
+value = "yes"
+
+[[answers]]
+prompt = "Enter the name of the organization"
+value = "ACME, Inc."
+
+[[answers]]
+prompt = "Enter the name of the signer from the organization"
+value = "Road Runner"
+
+[[answers]]
+prompt = "Enter the position within the organization of the signer"
+value = "The Boss"
+
+[[answers]]
+prompt = "Enter the day of the signing"
+value = "7"
+
+[[answers]]
+prompt = "Enter the month of the signing"
+value = "April"
+
+[[answers]]
+prompt = "Enter the year of the signing"
+value = "2025"
+"#;
+
+#[test]
+fn test_quiet_skips_notices() {
+    let temp = setup_test_env();
+    temp.child("answers.toml").write_str(GPL_ANSWERS).unwrap();
+    gen_assert_cmd(Lang::Rust, temp.path(), "GPL-3.0-or-later")
+        .args(["--answers", "answers.toml", "-q"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("You'll need to include").not())
+        .stderr(predicate::str::contains("Since your program is interactive").not());
+    temp.child("LICENSE.rust.txt")
+        .assert(predicate::str::contains("GNU GENERAL PUBLIC LICENSE"))
+        .assert(predicate::str::contains(
+            "ACME, Inc., hereby disclaims all copyright interest",
+        ));
+    temp.child("INTERACTIVE_NOTICE.txt")
+        .assert(predicate::str::contains(
+            "license comes with ABSOLUTELY NO WARRANTY.",
+        ));
+
+    let loud = setup_test_env();
+    loud.child("answers.toml").write_str(GPL_ANSWERS).unwrap();
+    gen_assert_cmd(Lang::Rust, loud.path(), "GPL-3.0-or-later")
+        .args(["--answers", "answers.toml"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("You'll need to include"))
+        .stderr(predicate::str::contains(
+            "Since your program is interactive",
+        ));
+    loud.child("LICENSE.rust.txt")
+        .assert(predicate::str::contains(
+            "hereby disclaims all copyright interest",
+        ));
+    loud.child("INTERACTIVE_NOTICE.txt")
+        .assert(predicate::path::exists());
+    temp.close().unwrap();
+    loud.close().unwrap();
+}
+
+#[test]
+fn test_amendment_output() {
+    let temp = setup_test_env();
+    temp.child("answers.toml").write_str(GPL_ANSWERS).unwrap();
+    gen_assert_cmd(Lang::Rust, temp.path(), "GPL-3.0-or-later")
+        .args([
+            "--answers",
+            "answers.toml",
+            "--amendment-output",
+            "DISCLAIMER.txt",
+        ])
+        .assert()
+        .success();
+    temp.child("LICENSE.rust.txt")
+        .assert(predicate::str::contains("GNU GENERAL PUBLIC LICENSE"))
+        .assert(predicate::str::contains("hereby disclaims all copyright interest").not());
+    temp.child("DISCLAIMER.txt")
+        .assert(predicate::str::contains(
+            "ACME, Inc., hereby disclaims all copyright interest",
+        ))
+        .assert(predicate::str::contains("Road Runner, The Boss"));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_append_keeps_license_file() {
+    let temp = setup_test_env();
+    temp.child("answers.toml").write_str(GPL_ANSWERS).unwrap();
+    temp.child("LICENSE.rust.txt")
+        .write_str("GNU GENERAL PUBLIC LICENSE (hand-edited)\n")
+        .unwrap();
+    for _ in 0..2 {
+        gen_assert_cmd(Lang::Rust, temp.path(), "GPL-3.0-or-later")
+            .args(["--answers", "answers.toml", "--append"])
+            .assert()
+            .success();
+    }
+    let text = std::fs::read_to_string(temp.child("LICENSE.rust.txt").path()).unwrap();
+    assert!(text.starts_with("GNU GENERAL PUBLIC LICENSE (hand-edited)\n\n"));
+    assert!(!text.contains("TERMS AND CONDITIONS"));
+    assert_eq!(
+        text.matches("hereby disclaims all copyright interest")
+            .count(),
+        1
+    );
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_final_newline() {
+    let temp = setup_test_env();
+    let license = temp.child("LICENSE.rust.txt");
+    gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+        .assert()
+        .success();
+    let text = std::fs::read_to_string(license.path()).unwrap();
+    assert!(text.ends_with('\n') && !text.ends_with("\n\n"));
+
+    gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+        .args(["--final-newline", "strip"])
+        .assert()
+        .success();
+    let stripped = std::fs::read_to_string(license.path()).unwrap();
+    assert_eq!(stripped, text.trim_end_matches('\n'));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_wrap_license_and_header() {
+    let temp = setup_test_env();
+    gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+        .args(["--wrap", "40"])
+        .assert()
+        .success();
+    let text = std::fs::read_to_string(temp.child("LICENSE.rust.txt").path()).unwrap();
+    assert!(text.contains("1. Definitions\n--------------\n"));
+    let main = std::fs::read_to_string(temp.child("rust/main.rs").path()).unwrap();
+    assert!(main.starts_with(
+        "// SPDX-License-Identifier: MPL-2.0\n// This Source Code Form is subject to\n"
+    ));
+    let header = main.lines().take_while(|line| line.starts_with("//"));
+    for line in text.lines().chain(header) {
+        assert!(
+            line.chars().count() <= 40 || !line.trim().contains(' '),
+            "{line}"
+        );
+    }
+    gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+        .args(["--wrap", "10"])
+        .assert()
+        .failure();
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_prompt_end_of_input() {
+    let temp = setup_test_env();
+    gen_assert_cmd(Lang::Rust, temp.path(), "GPL-3.0-only")
+        .write_stdin("")
+        .timeout(std::time::Duration::from_secs(30))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Unexpected end of input: stdin closed before 'Enter the copyright year' was answered.",
+        ));
+
+    gen_assert_cmd(Lang::Rust, temp.path(), "GPL-3.0-only")
+        .args(["--prompt-retries", "1"])
+        .write_stdin("soon\nlater\n2025\n")
+        .timeout(std::time::Duration::from_secs(30))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Too many invalid answers: giving up on 'Enter the copyright year' after 2 tries.",
+        ));
+    temp.child("LICENSE.rust.txt")
+        .assert(predicate::path::missing());
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_files_from_stdin() {
+    let temp = setup_test_env();
+    temp.child("rust/lib.rs")
+        .write_str("pub fn lib() {}\n")
+        .unwrap();
+    gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+        .args(["--files-from", "-"])
+        .write_stdin("rust/main.rs\n\nmissing.rs\npython/__init__.py\n")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Skipping missing.rs"));
+    temp.child("rust/main.rs")
+        .assert(predicate::str::contains("SPDX-License-Identifier: MPL-2.0"));
+    temp.child("python/__init__.py")
+        .assert(predicate::str::contains("SPDX-License-Identifier: MPL-2.0"));
+    temp.child("rust/lib.rs")
+        .assert(predicate::str::contains("SPDX-License-Identifier").not());
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_failed_files_exit_code() {
+    let temp = setup_test_env();
+    temp.child("rust/latin1.rs")
+        .write_binary(b"// caf\xe9\nfn latin1() {}\n")
+        .unwrap();
+    temp.child("rust/lib.rs")
+        .write_str("pub fn lib() {}\n")
+        .unwrap();
+    gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+        .args(["--jobs", "1"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Failed to write comment for file rust/latin1.rs",
+        ))
+        .stdout(predicate::str::contains(
+            "Added license headers to 2 file(s), skipped 0 file(s) that already had an SPDX header. 1 file(s) failed.",
+        ));
+    temp.child("rust/main.rs")
+        .assert(predicate::str::contains("SPDX-License-Identifier: MPL-2.0"));
+    temp.child("rust/lib.rs")
+        .assert(predicate::str::contains("SPDX-License-Identifier: MPL-2.0"));
+    temp.child("LICENSE.rust.txt")
+        .assert(predicate::path::exists());
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_output_dir() {
+    let temp = setup_test_env();
+    temp.child("answers.toml").write_str(GPL_ANSWERS).unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .env("XDG_CONFIG_HOME", temp.child(".config").path())
+        .args([
+            "--source-path",
+            "rust/",
+            "--add-comment",
+            "--comment",
+            "//",
+            "--answers",
+            "answers.toml",
+            "--output-dir",
+            "dist",
+            "GPL-3.0-or-later",
+        ])
+        .assert()
+        .success();
+    temp.child("dist/LICENSE")
+        .assert(predicate::str::contains("GNU GENERAL PUBLIC LICENSE"))
+        .assert(predicate::str::contains("hereby disclaims all copyright interest").not());
+    temp.child("dist/AMENDMENT.txt")
+        .assert(predicate::str::contains(
+            "ACME, Inc., hereby disclaims all copyright interest",
+        ));
+    temp.child("dist/INTERACTIVE_NOTICE.txt")
+        .assert(predicate::path::is_file());
+    temp.child("LICENSE.txt").assert(predicate::path::missing());
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_new_project() {
+    let temp = setup_test_env();
+    temp.child("Cargo.toml")
+        .write_str("[package]\nname = \"demo\"\nauthors = [\"Road Runner <beep@example.com>\"]\n")
+        .unwrap();
+    temp.child(".gitignore").write_str("target/\n").unwrap();
+    temp.child("target/build.rs")
+        .write_str("fn main() {}\n")
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .env("XDG_CONFIG_HOME", temp.path().join(".config"))
+        .args(["new", "MIT"])
+        .write_stdin("\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Wrote LICENSE (MIT)"))
+        .stdout(predicate::str::contains("Added a header to ./rust/main.rs"))
+        .stdout(predicate::str::contains(
+            "Added a header to ./python/__init__.py",
+        ));
+    temp.child("LICENSE")
+        .assert(predicate::str::contains("MIT License"))
+        .assert(predicate::str::contains("Road Runner\n"));
+    temp.child("rust/main.rs")
+        .assert(predicate::str::starts_with(
+            "// SPDX-License-Identifier: MIT\n",
+        ));
+    temp.child("python/__init__.py")
+        .assert(predicate::str::starts_with(
+            "# SPDX-License-Identifier: MIT\n",
+        ));
+    temp.child("target/build.rs").assert("fn main() {}\n");
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_output_from_env() {
+    let temp = setup_test_env();
+    let license = |args: &[&str]| {
+        let mut cmd = AssertCommand::new(cargo_bin!("license"));
+        cmd.current_dir(temp.path())
+            .env("XDG_CONFIG_HOME", temp.path().join(".config"))
+            .env("LICENSE_GEN_OUTPUT", "COPYING")
+            .args(args)
+            .arg("MPL-2.0");
+        cmd.assert()
+    };
+    license(&[]).success();
+    temp.child("COPYING").assert(predicate::str::contains(
+        "Mozilla Public License Version 2.0",
+    ));
+    temp.child("LICENSE.txt").assert(predicate::path::missing());
+
+    license(&["--output", "LICENSE.md"]).success();
+    temp.child("LICENSE.md").assert(predicate::str::contains(
+        "Mozilla Public License Version 2.0",
+    ));
+    temp.child("LICENSE.txt").assert(predicate::path::missing());
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_license_filename() {
+    let temp = setup_test_env();
+    temp.child("answers.toml").write_str(GPL_ANSWERS).unwrap();
+    let license = |args: &[&str]| {
+        let mut cmd = AssertCommand::new(cargo_bin!("license"));
+        cmd.current_dir(temp.path())
+            .env("XDG_CONFIG_HOME", temp.path().join(".config"))
+            .env_remove("LICENSE_GEN_OUTPUT")
+            .args(["--answers", "answers.toml", "-q"])
+            .args(args);
+        cmd.assert()
+    };
+    license(&["--license-filename", "auto", "GPL-3.0-or-later"]).success();
+    temp.child("COPYING")
+        .assert(predicate::str::contains("GNU GENERAL PUBLIC LICENSE"));
+
+    license(&["--license-filename", "auto", "MPL-2.0"]).success();
+    temp.child("LICENSE.txt").assert(predicate::str::contains(
+        "Mozilla Public License Version 2.0",
+    ));
+
+    license(&["--license-filename", "license-md", "MPL-2.0"]).success();
+    temp.child("LICENSE.md").assert(predicate::str::contains(
+        "Mozilla Public License Version 2.0",
+    ));
+
+    license(&[
+        "--license-filename",
+        "copying",
+        "--output",
+        "MPL.txt",
+        "MPL-2.0",
+    ])
+    .success();
+    temp.child("MPL.txt").assert(predicate::path::exists());
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_markdown_license() {
+    let temp = setup_test_env();
+    gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+        .args(["--format", "md"])
+        .assert()
+        .success();
+    temp.child("LICENSE.rust.txt")
+        .assert(predicate::str::starts_with(
+            "# Mozilla Public License 2.0\n\n## Mozilla Public License Version 2.0\n\n## 1. Definitions\n\n1.1. \"Contributor\"\nmeans",
+        ))
+        .assert(predicate::str::contains("\n---").not());
+    temp.child("rust/main.rs")
+        .assert(predicate::str::starts_with(
+            "// SPDX-License-Identifier: MPL-2.0\n",
+        ));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_reuse_copyright_text() {
+    let temp = setup_test_env();
+    for _ in 0..2 {
+        gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+            .args(["--reuse", "--year", "2025"])
+            .write_stdin("Road Runner\n")
+            .assert()
+            .success();
+    }
+    temp.child("rust/main.rs").assert(predicate::str::starts_with(
+        "// SPDX-FileCopyrightText: 2025 Road Runner\n// SPDX-License-Identifier: MPL-2.0\n// This Source Code Form",
+    ));
+    let main = std::fs::read_to_string(temp.child("rust/main.rs").path()).unwrap();
+    assert_eq!(main.matches("SPDX-FileCopyrightText").count(), 1);
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_reuse_toml() {
+    let temp = setup_test_env();
+    for lang in [Lang::Rust, Lang::Python] {
+        gen_assert_cmd(lang, temp.path(), "MPL-2.0")
+            .args(["--reuse", "--year", "2025"])
+            .write_stdin("Road Runner\n")
+            .assert()
+            .success();
+    }
+    temp.child("REUSE.toml")
+        .assert(predicate::str::starts_with("version = 1\n"));
+    let reuse = std::fs::read_to_string(temp.child("REUSE.toml").path()).unwrap();
+    assert_eq!(reuse.matches("[[annotations]]").count(), 1);
+    assert!(reuse.contains(r#"path = ["python/__init__.py", "rust/main.rs"]"#));
+    assert!(reuse.contains(r#"SPDX-FileCopyrightText = ["2025 Road Runner"]"#));
+    assert!(reuse.contains(r#"SPDX-License-Identifier = "MPL-2.0""#));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_guess_license() {
+    let temp = setup_test_env();
+    gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+        .assert()
+        .success();
+    temp.child("README.md")
+        .write_str("# Demo\n\nA project without a license file.\n")
+        .unwrap();
+    let guess = |path: &str| {
+        let mut cmd = AssertCommand::new(cargo_bin!("license"));
+        cmd.current_dir(temp.path()).args(["guess", path]);
+        cmd.assert()
+    };
+    guess("LICENSE.rust.txt")
+        .success()
+        .stdout(predicate::str::starts_with("MPL-2.0\t100.0%"));
+    guess("README.md")
+        .failure()
+        .stderr(predicate::str::contains(
+            "No built-in license matches README.md",
+        ));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_verify_license_file() {
+    let temp = setup_test_env();
+    temp.child("answers.toml").write_str(GPL_ANSWERS).unwrap();
+    gen_assert_cmd(Lang::Rust, temp.path(), "GPL-3.0-or-later")
+        .args(["--answers", "answers.toml"])
+        .assert()
+        .success();
+    let license = temp.child("LICENSE.rust.txt");
+    let text = std::fs::read_to_string(license.path()).unwrap();
+    let verify = || {
+        let mut cmd = gen_assert_cmd(Lang::Rust, temp.path(), "GPL-3.0-or-later");
+        cmd.args(["--answers", "answers.toml", "--verify", "LICENSE.rust.txt"]);
+        cmd.assert()
+    };
+
+    license
+        .write_str(&format!("  {}\n\n\n", text.replace(". ", ".  ")))
+        .unwrap();
+    verify()
+        .success()
+        .stdout(predicate::str::contains("matches the license text"));
+
+    license
+        .write_str(&text.replace("Everyone is permitted", "Nobody is permitted"))
+        .unwrap();
+    verify()
+        .failure()
+        .stderr(predicate::str::contains("does not match the license text"))
+        .stdout(predicate::str::contains("-Nobody is permitted"))
+        .stdout(predicate::str::contains("+Everyone is permitted"));
+    license.assert(predicate::str::contains("Nobody is permitted"));
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_notebook_license_cell() {
+    let temp = setup_test_env();
+    let notebook = r#"{
+ "cells": [
+  {
+   "cell_type": "code",
+   "execution_count": null,
+   "id": "a1b2c3",
+   "metadata": {},
+   "outputs": [],
+   "source": ["print('hello')"]
+  }
+ ],
+ "metadata": {},
+ "nbformat": 4,
+ "nbformat_minor": 5
+}
+"#;
+    temp.child("rust/analysis.ipynb")
+        .write_str(notebook)
+        .unwrap();
+    temp.child("rust/broken.ipynb")
+        .write_str("{\"cells\": [")
+        .unwrap();
+    for _ in 0..2 {
+        gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Skipping malformed notebook rust/broken.ipynb",
+            ));
+    }
+    let stamped = std::fs::read_to_string(temp.child("rust/analysis.ipynb").path()).unwrap();
+    assert!(stamped.starts_with("{\n \"cells\": [\n  {\n"), "{stamped}");
+    let stamped: serde_json::Value = serde_json::from_str(&stamped).unwrap();
+    let cells = stamped["cells"].as_array().unwrap();
+    assert_eq!(cells.len(), 2);
+    assert_eq!(cells[0]["cell_type"], "markdown");
+    assert_eq!(cells[0]["id"], "license-header");
+    assert!(
+        cells[0]["source"][0]
+            .as_str()
+            .unwrap()
+            .starts_with("SPDX-License-Identifier: MPL-2.0")
+    );
+    assert_eq!(cells[1]["id"], "a1b2c3");
+    temp.child("rust/broken.ipynb").assert("{\"cells\": [");
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_color_choice() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let run = |args: &[&str], force: bool| {
+        let mut cmd = AssertCommand::new(cargo_bin!("license"));
+        cmd.current_dir(temp.path())
+            .env_remove("NO_COLOR")
+            .env_remove("CLICOLOR_FORCE")
+            .args(args)
+            .args(["--answers", "missing.toml", "MIT"]);
+        if force {
+            cmd.env("CLICOLOR_FORCE", "1");
+        }
+        cmd.assert().failure()
+    };
+    let plain = predicate::str::contains("Failed to load answers:");
+    let colored = predicate::str::contains("Failed to load answers\x1b[");
+    run(&[], false).stderr(plain.clone());
+    run(&[], true).stderr(colored.clone());
+    run(&["--color", "never"], true).stderr(plain);
+    run(&["--color", "always"], false).stderr(colored);
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_render_without_io() {
+    use license_gen_bin::license::{Licenses, VersionAmmendment, render};
+    use license_gen_bin::texts::{LicenseAnswers, RenderError, RenderOptions, TemplateVar};
+
+    let answers = LicenseAnswers {
+        year: Some("2025".parse().unwrap()),
+        fullname: Some("Jane Doe".to_string()),
+        ..LicenseAnswers::default()
+    };
+    let texts = render(&Licenses::Mit, &answers).unwrap();
+    assert!(texts.text.contains("Copyright (c) 2025 Jane Doe"));
+    assert_eq!(texts.comment, "SPDX-License-Identifier: MIT");
+
+    let reuse = RenderOptions {
+        reuse: true,
+        copyright_format: Some("(C) {year} {name}".to_string()),
+        template: Some("{{title}}: {{{copyright}}} {{extra}}".to_string()),
+        vars: vec!["extra=EXTRA".parse::<TemplateVar>().unwrap()],
+        ..RenderOptions::default()
+    };
+    let reused = LicenseAnswers {
+        options: reuse.clone(),
+        ..answers.clone()
+    };
+    let texts = render(&Licenses::Mit, &reused).unwrap();
+    assert_eq!(texts.text, "MIT License: (C) 2025 Jane Doe EXTRA");
+    assert_eq!(
+        texts.comment,
+        "SPDX-FileCopyrightText: 2025 Jane Doe\nSPDX-License-Identifier: MIT"
+    );
+
+    let error = render(&Licenses::Mit, &LicenseAnswers::default()).unwrap_err();
+    assert_eq!(error, RenderError::Missing(vec!["year", "fullname"]));
+    assert_eq!(error.to_string(), "no answer for 'year', 'fullname'");
+    assert!(render(&Licenses::Mpl2, &LicenseAnswers::default()).is_ok());
+    let unanswered = LicenseAnswers {
+        options: reuse,
+        ..LicenseAnswers::default()
+    };
+    assert_eq!(
+        render(&Licenses::Mpl2, &unanswered).unwrap_err(),
+        RenderError::Missing(vec!["year", "fullname"])
+    );
+    let error = render(&Licenses::Gpl3(VersionAmmendment::OrLater), &answers).unwrap_err();
+    assert_eq!(error, RenderError::Missing(vec!["program", "description"]));
+
+    let shadowing = LicenseAnswers {
+        options: RenderOptions {
+            vars: vec!["year=1999".parse::<TemplateVar>().unwrap()],
+            ..RenderOptions::default()
+        },
+        ..answers.clone()
+    };
+    let error = render(&Licenses::Mit, &shadowing).unwrap_err();
+    assert_eq!(error, RenderError::Variable("year".to_string()));
+    let broken = LicenseAnswers {
+        options: RenderOptions {
+            template: Some("{{#if}}".to_string()),
+            ..RenderOptions::default()
+        },
+        ..answers
+    };
+    let error = render(&Licenses::Mit, &broken).unwrap_err();
+    assert!(matches!(error, RenderError::Template(_)));
+
+    let answers = LicenseAnswers {
+        secondary_licenses: vec!["GPL-2.0-or-later".parse().unwrap()],
+        ..LicenseAnswers::default()
+    };
+    let texts = render(&Licenses::Epl2, &answers).unwrap();
+    assert!(texts.alt.unwrap().contains("- GPL-2.0-or-later"));
+
+    let answers = LicenseAnswers {
+        secondary_licenses: vec!["GPL-2.0-or-later".parse().unwrap(), Licenses::Epl2],
+        ..LicenseAnswers::default()
+    };
+    let error = render(&Licenses::Epl2, &answers).unwrap_err();
+    assert_eq!(
+        error,
+        RenderError::Invalid {
+            field: "secondary_licenses",
+            value: "GPL-2.0-or-later, EPL-2.0".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_parse_license_identifiers() {
+    use clap::ValueEnum;
+    use license_gen_bin::license::{BsdAmmendment, Licenses, VersionAmmendment};
+
+    for license in Licenses::value_variants() {
+        assert_eq!(
+            license.to_string().parse::<Licenses>().as_ref(),
+            Ok(license)
+        );
+    }
+    assert_eq!(
+        "gpl-3.0-or-later".parse(),
+        Ok(Licenses::Gpl3(VersionAmmendment::OrLater))
+    );
+    assert_eq!(
+        Licenses::try_from("BSD-3-Clause-No-Military-License"),
+        Ok(Licenses::Bsd3Clause(BsdAmmendment::NoMilitary))
+    );
+    for unknown in ["GPL-3.0-maybe", "BSD-3-Clause-Clear", "Foo-1.0", ""] {
+        let error = unknown.parse::<Licenses>().unwrap_err();
+        assert!(error.contains("Unknown SPDX license identifier"), "{error}");
+    }
+}
+
+#[test]
+fn test_serde_licenses() {
+    use license_gen_bin::license::{Licenses, VersionAmmendment};
+    use license_gen_bin::texts::LicenseTexts;
+
+    let license = Licenses::Lgpl21(VersionAmmendment::Only);
+    let json = serde_json::to_string(&license).unwrap();
+    assert_eq!(json, "\"LGPL-2.1-only\"");
+    assert_eq!(serde_json::from_str::<Licenses>(&json).unwrap(), license);
+    assert_eq!(
+        serde_json::to_string(&VersionAmmendment::OrLater).unwrap(),
+        "\"-or-later\""
+    );
+    let error = serde_json::from_str::<Licenses>("\"Foo-1.0\"").unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("Unknown SPDX license identifier 'Foo-1.0'")
+    );
+
+    let texts = LicenseTexts {
+        text: "Permission is hereby granted".to_string(),
+        comment: "SPDX-License-Identifier: MIT".to_string(),
+        alt: None,
+        interactive: Some("This program comes with ABSOLUTELY NO WARRANTY".to_string()),
+        notice: None,
+    };
+    let json = serde_json::to_string(&texts).unwrap();
+    assert_eq!(serde_json::from_str::<LicenseTexts>(&json).unwrap(), texts);
+}
+
+#[test]
+fn test_license_texts_display_multibyte() {
+    use license_gen_bin::texts::LicenseTexts;
+
+    let texts = LicenseTexts {
+        text: "Copyright© 2025 Zoë Ångström".to_string(),
+        comment: "SPDX-License-Identifier: MIT".to_string(),
+        alt: Some("Çà et là, ünïcödé".to_string()),
+        interactive: None,
+        notice: None,
+    };
+    assert_eq!(
+        texts.to_string(),
+        "Text: 'Copyright©', Comment: 'SPDX-Licen', Alternative Text: 'Çà et là, ', Interactive Text: 'N/A'"
+    );
+}
+
+#[test]
+fn test_mpl_header() {
+    let temp = setup_test_env();
+    gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+        .assert()
+        .success();
+    temp.child("rust/main.rs")
+        .assert(predicate::str::contains(
+            "// SPDX-License-Identifier: MPL-2.0\n// This Source Code Form is subject to the terms of the Mozilla Public",
+        ));
+    temp.close().unwrap();
+
+    let temp = setup_test_env();
+    gen_assert_cmd(Lang::Rust, temp.path(), "MPL-2.0")
+        .args(["--mpl-header", "spdx"])
+        .assert()
+        .success();
+    temp.child("rust/main.rs")
+        .assert(predicate::str::contains(
+            "// SPDX-License-Identifier: MPL-2.0\n",
+        ))
+        .assert(predicate::str::contains("This Source Code Form").not());
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_pick_license_without_argument() {
+    let temp = setup_test_env();
+    let mut cmd = StdCommand::new(cargo_bin!("license"));
+    cmd.current_dir(temp.path())
+        .env("XDG_CONFIG_HOME", temp.path().join(".config"))
+        .args(["--source-path", "rust/", "--add-comment", "--comment", "//"])
+        .args(["--output", "LICENSE.rust.txt"]);
+    let mut session = spawn_session(cmd);
+    session.exp_string("2) AGPL-3.0").unwrap();
+    session.exp_string("Select a license").unwrap();
+    session.send_line("0").unwrap();
+    session
+        .exp_string("Please enter a number between 1 and")
+        .unwrap();
+    session.send_line("1").unwrap();
+    basic_interact(&mut session);
+    assert_files(
+        &temp,
+        Lang::Rust,
+        "MIT",
+        Some(Location::License("Copyright (c) 2025 Your Name")),
+    );
+    temp.close().unwrap();
+
+    AssertCommand::new(cargo_bin!("license"))
+        .write_stdin("")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("No license given"));
+}
+
+#[test]
+fn test_suggest_license_identifier() {
+    AssertCommand::new(cargo_bin!("license"))
+        .arg("Apache2")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "unknown license 'Apache2', did you mean 'Apache-2.0'?",
+        ));
+    AssertCommand::new(cargo_bin!("license"))
+        .arg("Foo")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "unknown license 'Foo' (see 'license list')",
+        ))
+        .stderr(predicate::str::contains("did you mean").not());
+    AssertCommand::new(cargo_bin!("license"))
+        .args(["--output", "-", "unlicense"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "This is free and unencumbered software released into the public domain.",
+        ));
+}
+
+#[test]
+fn test_case_insensitive_expression() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/main.rs")
+        .write_str("fn main() {}\n")
+        .unwrap();
+    AssertCommand::new(cargo_bin!("license"))
+        .current_dir(temp.path())
+        .env("XDG_CONFIG_HOME", temp.path().join(".config"))
+        .args(["--add-comment", "--comment", "//"])
+        .args(["--spdx-expression", "unlicense OR (bsl-1.0)"])
+        .assert()
+        .success();
+    temp.child("src/main.rs")
+        .assert("// SPDX-License-Identifier: Unlicense OR (BSL-1.0)\nfn main() {}\n");
+    temp.child("LICENSE-UNLICENSE")
+        .assert(predicate::path::exists());
+    temp.child("LICENSE-BSL-1.0")
+        .assert(predicate::path::exists());
+    temp.close().unwrap();
+}
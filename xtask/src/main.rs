@@ -1,4 +1,4 @@
-use clap::{Args, CommandFactory, Parser, Subcommand};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use color_print::cprintln;
 use devx_cmd::{cmd, run};
 use devx_pre_commit::{PreCommitContext, locate_project_root};
@@ -12,6 +12,8 @@ use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
 use tar::{Builder, EntryType, Header, HeaderMode};
 use tracing::{error, info, instrument, warn};
 use tracing_subscriber::{EnvFilter, fmt, prelude::*};
@@ -25,9 +27,9 @@ struct XtaskCli {
 
 #[derive(Subcommand, Debug, Clone)]
 enum XtaskCmds {
-    Dist(OptOutDir),
+    Dist(DistOpts),
     Man(OptOutDir),
-    Completion(OptOutDir),
+    Completion(CompletionOpts),
     Clean(OptOutDirWithAll),
     InstallPreCommitHook,
 }
@@ -55,6 +57,55 @@ struct OptOutDir {
     out_dir: PathBuf,
 }
 
+#[derive(Args, Debug, Clone)]
+struct CompletionOpts {
+    /// The output directory for generated files.
+    #[arg(long, default_value = OUT_ENV.clone())]
+    out_dir: PathBuf,
+
+    /// Only generate completions for this shell.
+    #[arg(long, value_enum)]
+    shell: Option<CompletionShell>,
+
+    /// Print the completions for '--shell' to stdout instead of writing
+    /// them to the output directory.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue, requires = "shell")]
+    stdout: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+struct DistOpts {
+    /// The output directory for generated files.
+    #[arg(long, default_value = OUT_ENV.clone())]
+    out_dir: PathBuf,
+
+    /// How many targets to build at the same time.
+    #[arg(short, long, default_value_t = num_cpus::get())]
+    jobs: usize,
+
+    #[command(flatten)]
+    targets: TargetOpts,
+}
+
+#[derive(Args, Debug, Clone)]
+struct TargetOpts {
+    /// Only build these targets instead of all of them. Can be repeated or
+    /// comma separated.
+    #[arg(long = "target", visible_alias = "targets", value_name = "TRIPLE", value_delimiter = ',', value_parser = clap::builder::PossibleValuesParser::new(TARGETS))]
+    targets: Vec<String>,
+}
+
+impl TargetOpts {
+    /// The selected targets, in the order of [`TARGETS`]. All of them if
+    /// none were selected.
+    fn selected(&self) -> Vec<&'static str> {
+        TARGETS
+            .into_iter()
+            .filter(|target| self.targets.is_empty() || self.targets.iter().any(|t| t == target))
+            .collect()
+    }
+}
+
 #[derive(Args, Debug, Clone)]
 struct OptOutDirWithAll {
     /// The output directory for generated files.
@@ -64,6 +115,9 @@ struct OptOutDirWithAll {
     /// Clean all generated files, including archives.
     #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
     all: bool,
+
+    #[command(flatten)]
+    targets: TargetOpts,
 }
 
 #[derive(Debug)]
@@ -112,7 +166,7 @@ type XtaskResult<T> = Result<T, XtaskError>;
 
 fn main() {
     tracing_subscriber::registry()
-        .with(fmt::layer())
+        .with(fmt::layer().with_writer(std::io::stderr))
         .with(EnvFilter::from_default_env())
         .init();
 
@@ -125,9 +179,9 @@ fn main() {
     let cli = XtaskCli::parse();
     match cli.cmd {
         Some(cmd) => match cmd {
-            XtaskCmds::Dist(out) => {
+            XtaskCmds::Dist(opts) => {
                 info!("Building distribution...");
-                dist(out.out_dir);
+                dist(opts.out_dir, opts.jobs, &opts.targets.selected());
             }
             XtaskCmds::Man(out) => {
                 info!("Building manpages...");
@@ -138,9 +192,14 @@ fn main() {
                     STATUS.lock().unwrap().success("manpages");
                 }
             }
-            XtaskCmds::Completion(out) => {
+            XtaskCmds::Completion(opts) => {
                 info!("Building completions...");
-                if let Err(e) = build_completion(out.out_dir) {
+                let result = match (opts.shell, opts.stdout) {
+                    (Some(shell), true) => print_completion(shell),
+                    (Some(shell), false) => build_completion(opts.out_dir, &[shell]),
+                    (None, _) => build_completion(opts.out_dir, CompletionShell::value_variants()),
+                };
+                if let Err(e) = result {
                     warn!("Failed to build completions: {}", e);
                     STATUS.lock().unwrap().fail("completion");
                 } else {
@@ -149,7 +208,7 @@ fn main() {
             }
             XtaskCmds::Clean(out) => {
                 info!("Cleaning generated files...");
-                if let Err(e) = clean(out.out_dir, out.all) {
+                if let Err(e) = clean(out.out_dir, out.all, &out.targets.selected()) {
                     warn!("Failed to clean generated files: {}", e);
                     STATUS.lock().unwrap().fail("clean");
                 } else {
@@ -239,8 +298,8 @@ fn rmdir<P: AsRef<Path> + std::fmt::Debug>(dir: P) -> XtaskResult<()> {
 }
 
 #[instrument]
-fn clean<P: AsRef<Path> + std::fmt::Debug>(dir: P, all: bool) -> XtaskResult<()> {
-    for out_path in TARGETS
+fn clean<P: AsRef<Path> + std::fmt::Debug>(dir: P, all: bool, targets: &[&str]) -> XtaskResult<()> {
+    for out_path in targets
         .iter()
         .chain(
             [
@@ -251,6 +310,7 @@ fn clean<P: AsRef<Path> + std::fmt::Debug>(dir: P, all: bool) -> XtaskResult<()>
                 "fish_completions",
                 "powershell_completions",
                 "elvish_completions",
+                "nushell_completions",
             ]
             .iter(),
         )
@@ -263,7 +323,7 @@ fn clean<P: AsRef<Path> + std::fmt::Debug>(dir: P, all: bool) -> XtaskResult<()>
 
     if all {
         let name = Cli::command().get_name().to_string();
-        for file in TARGETS
+        for file in targets
             .iter()
             .map(|t| dir.as_ref().join(format!("{name}-{t}.tar.zst")))
         {
@@ -278,7 +338,7 @@ fn clean<P: AsRef<Path> + std::fmt::Debug>(dir: P, all: bool) -> XtaskResult<()>
 }
 
 #[instrument]
-fn dist<P: AsRef<Path> + std::fmt::Debug>(out: P) {
+fn dist<P: AsRef<Path> + std::fmt::Debug>(out: P, jobs: usize, targets: &[&'static str]) {
     info!("Output directory: {:?}", out.as_ref());
     info!("Generating manpages...");
     build_manpages(&out).unwrap_or_else(|e| {
@@ -286,23 +346,30 @@ fn dist<P: AsRef<Path> + std::fmt::Debug>(out: P) {
         STATUS.lock().unwrap().fail("manpages");
     });
     info!("Generating completions...");
-    build_completion(&out).unwrap_or_else(|e| {
+    build_completion(&out, CompletionShell::value_variants()).unwrap_or_else(|e| {
         warn!("Failed to build completions: {}", e);
         STATUS.lock().unwrap().fail("completion");
     });
-    info!("Building targets...");
-    for target in TARGETS {
-        info!("Building target: {}", target);
-        if let Err(e) = build(target) {
-            warn!("Failed to build target {}: {}", target, e);
-            STATUS.lock().unwrap().fail(target);
-        } else {
-            info!("Successfully built target: {}", target);
-            STATUS.lock().unwrap().success(target);
+    info!("Building targets with {} job(s)...", jobs);
+    let next = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, targets.len().max(1)) {
+            scope.spawn(|| {
+                while let Some(&target) = targets.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    info!("Building target: {}", target);
+                    if let Err(e) = build(target) {
+                        warn!("Failed to build target {}: {}", target, e);
+                        STATUS.lock().unwrap().fail(target);
+                    } else {
+                        info!("Successfully built target: {}", target);
+                        STATUS.lock().unwrap().success(target);
+                    }
+                }
+            });
         }
-    }
+    });
     info!("Bundling distribution...");
-    if let Err(e) = bundle(&out) {
+    if let Err(e) = bundle(&out, targets) {
         warn!("Failed to bundle distribution: {}", e);
         STATUS.lock().unwrap().fail("bundle");
     } else {
@@ -326,7 +393,10 @@ fn build_manpages<P: AsRef<Path> + std::fmt::Debug>(out: P) -> XtaskResult<()> {
 }
 
 #[instrument]
-fn build_completion<P: AsRef<Path> + std::fmt::Debug>(out: P) -> XtaskResult<()> {
+fn build_completion<P: AsRef<Path> + std::fmt::Debug>(
+    out: P,
+    shells: &[CompletionShell],
+) -> XtaskResult<()> {
     let mut cmd = Cli::command();
     let name = cmd.get_name().to_string();
     info!(
@@ -334,68 +404,86 @@ fn build_completion<P: AsRef<Path> + std::fmt::Debug>(out: P) -> XtaskResult<()>
         name,
         &out.as_ref().display()
     );
-    clap_complete::aot::generate(
-        clap_complete::aot::Bash,
-        &mut cmd,
-        &name,
-        &mut std::fs::File::create(
-            completion_dir(&out, clap_complete::Shell::Bash)?.join(format!("{name}.sh")),
-        )?,
-    );
-    clap_complete::generate(
-        clap_complete::aot::Zsh,
-        &mut cmd,
-        &name,
-        &mut std::fs::File::create(
-            completion_dir(&out, clap_complete::Shell::Zsh)?.join(format!("{name}.zsh")),
-        )?,
-    );
-    clap_complete::generate(
-        clap_complete::aot::Fish,
-        &mut cmd,
-        &name,
-        &mut std::fs::File::create(
-            completion_dir(&out, clap_complete::Shell::Fish)?.join(format!("{name}.fish")),
-        )?,
-    );
-    clap_complete::generate(
-        clap_complete::aot::PowerShell,
-        &mut cmd,
-        &name,
-        &mut std::fs::File::create(
-            completion_dir(&out, clap_complete::Shell::PowerShell)?.join(format!("{name}.ps1")),
-        )?,
-    );
-    clap_complete::generate(
-        clap_complete::aot::Elvish,
-        &mut cmd,
-        &name,
-        &mut std::fs::File::create(
-            completion_dir(&out, clap_complete::Shell::Elvish)?.join(format!("{name}.elv")),
-        )?,
-    );
+    for &shell in shells {
+        let file = completion_dir(&out, shell)?.join(format!("{name}.{}", shell.extension()));
+        shell.generate(&mut cmd, &name, &mut std::fs::File::create(file)?);
+    }
     Ok(())
 }
 
+#[instrument]
+fn print_completion(shell: CompletionShell) -> XtaskResult<()> {
+    let mut cmd = Cli::command();
+    let name = cmd.get_name().to_string();
+    info!("Printing {:?} completions for {}", shell, name);
+    shell.generate(&mut cmd, &name, &mut std::io::stdout());
+    Ok(())
+}
+
+/// The shells completions are generated for: those supported by
+/// `clap_complete`, plus Nushell.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    #[value(name = "powershell")]
+    PowerShell,
+    Elvish,
+    Nushell,
+}
+
+impl CompletionShell {
+    /// The extension of the completion script, e.g. `sh` for Bash.
+    fn extension(self) -> &'static str {
+        match self {
+            CompletionShell::Bash => "sh",
+            CompletionShell::Zsh => "zsh",
+            CompletionShell::Fish => "fish",
+            CompletionShell::PowerShell => "ps1",
+            CompletionShell::Elvish => "elv",
+            CompletionShell::Nushell => "nu",
+        }
+    }
+
+    /// Writes the completion script for `cmd` to `buf`.
+    fn generate(self, cmd: &mut clap::Command, name: &str, buf: &mut dyn Write) {
+        match self {
+            CompletionShell::Bash => {
+                clap_complete::generate(clap_complete::aot::Bash, cmd, name, buf)
+            }
+            CompletionShell::Zsh => {
+                clap_complete::generate(clap_complete::aot::Zsh, cmd, name, buf)
+            }
+            CompletionShell::Fish => {
+                clap_complete::generate(clap_complete::aot::Fish, cmd, name, buf)
+            }
+            CompletionShell::PowerShell => {
+                clap_complete::generate(clap_complete::aot::PowerShell, cmd, name, buf)
+            }
+            CompletionShell::Elvish => {
+                clap_complete::generate(clap_complete::aot::Elvish, cmd, name, buf)
+            }
+            CompletionShell::Nushell => {
+                clap_complete::generate(clap_complete_nushell::Nushell, cmd, name, buf)
+            }
+        }
+    }
+}
+
 #[instrument]
 fn completion_dir<P: AsRef<Path> + std::fmt::Debug>(
     out: P,
-    shell: clap_complete::Shell,
+    shell: CompletionShell,
 ) -> XtaskResult<PathBuf> {
-    info!(
-        "Creating completion directory for {} in {:?}",
-        shell.to_string(),
-        out
-    );
+    info!("Creating completion directory for {:?} in {:?}", shell, out);
     let shell = match shell {
-        clap_complete::Shell::Bash => "bash_completions",
-        clap_complete::Shell::Zsh => "zsh_completions",
-        clap_complete::Shell::Fish => "fish_completions",
-        clap_complete::Shell::PowerShell => "powershell_completions",
-        clap_complete::Shell::Elvish => "elvish_completions",
-        _ => {
-            return Err(format!("Unsupported shell: {shell:?}").into());
-        }
+        CompletionShell::Bash => "bash_completions",
+        CompletionShell::Zsh => "zsh_completions",
+        CompletionShell::Fish => "fish_completions",
+        CompletionShell::PowerShell => "powershell_completions",
+        CompletionShell::Elvish => "elvish_completions",
+        CompletionShell::Nushell => "nushell_completions",
     };
     let out = out.as_ref().join(shell);
     mkdir(&out, false)?;
@@ -403,7 +491,7 @@ fn completion_dir<P: AsRef<Path> + std::fmt::Debug>(
 }
 
 #[instrument]
-fn bundle<P: AsRef<Path> + std::fmt::Debug>(out: P) -> XtaskResult<()> {
+fn bundle<P: AsRef<Path> + std::fmt::Debug>(out: P, targets: &[&str]) -> XtaskResult<()> {
     info!("Bundling distribution in {}", out.as_ref().display());
     let cur_dir = env::current_dir()?;
     let bundle_dir = out.as_ref().join("bundle");
@@ -443,6 +531,7 @@ fn bundle<P: AsRef<Path> + std::fmt::Debug>(out: P) -> XtaskResult<()> {
         "fish_completions",
         "powershell_completions",
         "elvish_completions",
+        "nushell_completions",
     ] {
         let shell = complete.trim_end_matches("_completions");
         for file in out.as_ref().join(complete).read_dir()? {
@@ -458,7 +547,7 @@ fn bundle<P: AsRef<Path> + std::fmt::Debug>(out: P) -> XtaskResult<()> {
     }
     info!("Files to include in bundle: {:?}", files);
     let mut dirs: Vec<PathBuf> = Vec::new();
-    for tgt in TARGETS {
+    for &tgt in targets {
         if !out.as_ref().join(tgt).join("release").join(&name).exists() {
             warn!("Skipping target {}: binary not found", tgt);
             continue;
@@ -513,12 +602,12 @@ fn bundle<P: AsRef<Path> + std::fmt::Debug>(out: P) -> XtaskResult<()> {
                     PathBuf::from(format!("license/{}", rel_path.display())),
                 )?;
             }
-            let mut header = Header::new_gnu();
-            header.set_entry_type(EntryType::Regular);
-            header.set_mode(0o644);
-            header.set_size(std::mem::size_of_val(&hashes) as u64);
-            tar_builder.append_data(&mut header, "./sha256sums.txt", hashes.as_bytes())?;
         }
+        let mut header = Header::new_gnu();
+        header.set_entry_type(EntryType::Regular);
+        header.set_mode(0o644);
+        header.set_size(hashes.len() as u64);
+        tar_builder.append_data(&mut header, "./sha256sums.txt", hashes.as_bytes())?;
         let tarball = format!(
             "{name}-{}.tar.zst",
             dir.file_name().unwrap().to_string_lossy()